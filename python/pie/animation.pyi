@@ -0,0 +1,25 @@
+from typing import TYPE_CHECKING, List, Optional
+
+if TYPE_CHECKING:
+    from .mesh import Mesh
+
+class Animator:
+    """Plays the animation clips of a skinned glTF mesh."""
+
+    time: float
+    speed: float
+    looping: bool
+    playing: bool
+    clips: List[str]
+    clip: Optional[str]
+    duration: float
+    def __init__(self, mesh: "Mesh") -> None:
+        """Raises ValueError if the mesh has no skeleton."""
+    def play(self, name: str, looping: bool = True) -> None:
+        """Starts a clip from the beginning."""
+    def pause(self) -> None: ...
+    def resume(self) -> None: ...
+    def stop(self) -> None:
+        """Stops playback and returns to the rest pose."""
+    def update(self, delta_time: float) -> None:
+        """Advances the clip. Animators attached to entities are updated by the engine."""
@@ -1,11 +1,25 @@
-from typing import List, Tuple
+from typing import List, Optional, Tuple
 
 class AudioSource:
     """Audio source."""
 
     position: Tuple[float, float, float]
+    velocity: Tuple[float, float, float]
+    """World-space velocity in units per second, used for Doppler shift."""
     positional: bool
+    min_distance: float
+    max_distance: float
+    rolloff_model: str
+    group: str
+    """Mixer group: "sfx" (default), "music", "voice" or "ambient"."""
     looping: bool
+    volume: float
+    pitch: float
+    lowpass_cutoff: Optional[float]
+    """Low-pass cutoff in Hz for muffled sounds, or None to bypass the filter.
+
+    Raises ValueError unless the cutoff is a positive, finite number.
+    """
     playing: bool
     cursor: int
     duration: float
@@ -16,6 +30,29 @@ class AudioSource:
     def new_clip(samples: List[float], looping: bool) -> "AudioSource": ...
     @staticmethod
     def from_wav(file: str, looping: bool) -> "AudioSource": ...
-    def play(self) -> None: ...
-    def pause(self) -> None: ...
+    @staticmethod
+    def from_ogg(file: str, looping: bool) -> "AudioSource": ...
+    @staticmethod
+    def stream_from_file(file: str, looping: bool = False) -> "AudioSource":
+        """Plays a .wav or .ogg file while decoding it on a background thread.
+
+        Only about half a second of audio is held in memory, which suits long music tracks.
+        duration is 0.0 for streamed OGG files, whose length isn't known up front.
+        """
+    def play(self) -> None:
+        """Starts or resumes playback from the current position."""
+    def stop(self) -> None:
+        """Stops playback and rewinds to the start."""
+    def pause(self) -> None:
+        """Pauses playback, keeping the position so play() resumes."""
+    def fade_in(self, seconds: float) -> None:
+        """Starts playing from silence, reaching full volume after seconds."""
+    def fade_out(self, seconds: float) -> None:
+        """Fades to silence over seconds, then pauses."""
     def is_playing(self) -> bool: ...
+    def seek(self, seconds: float) -> None:
+        """Moves playback to a time offset in seconds."""
+    def seek_frames(self, frame: int) -> None:
+        """Moves playback to a frame index."""
+    def restart(self) -> None:
+        """Seeks to the start and plays."""
@@ -1,10 +1,12 @@
-from typing import TYPE_CHECKING, List, Tuple
+from typing import TYPE_CHECKING, List, Optional, Tuple
 
 if TYPE_CHECKING:
     import pie.audio
     import pie.entity
     import pie.events
     import pie.scene
+    import pie.skybox
+    import pie.sprite
     import pie.texture
 
 class Engine:
@@ -17,31 +19,360 @@ class Engine:
     def set_camera(self, camera: "pie.entity.Camera") -> None:
         """Sets the active camera."""
         ...
-    def __init__(self, title: str, width: int, height: int) -> None: ...
+    def __init__(
+        self, title: str, width: int, height: int, msaa_samples: int = 0
+    ) -> None:
+        """A non-zero `msaa_samples` switches to forward rendering into a multisampled buffer.
+
+        Edges are antialiased, but shadows, custom entity shaders, reflections,
+        motion blur and depth of field are unavailable on that path.
+        """
+    @staticmethod
+    def new_headless(width: int, height: int, msaa_samples: int = 0) -> "Engine":
+        """Creates an engine with a hidden window that renders offscreen, for tests and servers.
+
+        Audio uses SDL's dummy driver. Without a display on Linux, SDL's offscreen video driver is used.
+        """
+    def is_forward_rendering(self) -> bool:
+        """Whether the engine was created with `msaa_samples` and renders through the forward path."""
     def quit(self) -> None:
         """Quits the engine."""
+    def set_title(self, title: str) -> None:
+        """Renames the window."""
+    def title(self) -> str:
+        """Returns the window title."""
+    def set_size(self, width: int, height: int) -> None:
+        """Resizes the window and the render targets, in pixels."""
+    def size(self) -> Tuple[int, int]:
+        """Returns the current (width, height) of the render targets in pixels."""
+    def set_skybox(self, skybox: Optional["pie.skybox.Skybox"]) -> None:
+        """Sets the skybox drawn behind the scene, or None to disable it."""
+    def set_light_gizmos(self, enabled: bool) -> None:
+        """Shows or hides the spheres drawn at point lights; they are shown by default."""
+    def set_outline(
+        self,
+        entity: "pie.entity.Entity",
+        color: Tuple[float, float, float] = (1.0, 0.6, 0.1),
+        width: float = 0.03,
+    ) -> None:
+        """Draws a flat-colored rim about `width` world units wide around the entity."""
+    def clear_outline(self, entity: "pie.entity.Entity") -> bool:
+        """Removes the entity's outline; returns whether it had one."""
     def add_entity(self, entity: "pie.entity.Entity") -> None:
         """Adds an entity to the scene."""
+    def frame_entity(self, entity: "pie.entity.Entity") -> None:
+        """Moves the camera back along its view direction until the entity's mesh fills the view."""
     def add_light(self, light: "pie.light.Light") -> None:
         """Adds a light to the scene."""
+    def remove_entity(self, entity: "pie.entity.Entity") -> bool:
+        """Removes an entity from the scene. Returns whether it was present."""
+    def remove_light(self, light: "pie.light.Light") -> bool:
+        """Removes a light from the scene. Returns whether it was present."""
+    def add_sprite(self, sprite: "pie.sprite.Sprite") -> None:
+        """Adds a sprite to the HUD overlay, drawn every frame until removed."""
+    def remove_sprite(self, sprite: "pie.sprite.Sprite") -> bool: ...
+    def draw_sprite(self, sprite: "pie.sprite.Sprite") -> None:
+        """Draws a sprite on the next frame only."""
+    def render_camera(
+        self, camera: "pie.entity.Camera", x: int, y: int, width: int, height: int
+    ) -> None:
+        """Renders the scene from another camera into a window rectangle on the next frame only.
+
+        The rectangle is in pixels from the top-left and is drawn over the main view,
+        under the sprites, for split-screen or picture-in-picture views.
+        """
+    def clear_scene(self) -> None:
+        """Removes all entities and lights from the scene."""
+    def find_by_name(self, name: str) -> Optional["pie.entity.Entity"]:
+        """Returns the first scene entity with the given name."""
+    def find_by_tag(self, tag: str) -> List["pie.entity.Entity"]:
+        """Returns every scene entity carrying tag."""
+    def save_scene(self, path: str) -> None:
+        """Saves entity transforms and mesh sources, lights and the camera to a JSON file.
+
+        Meshes built with Mesh.from_data or Mesh.empty are saved without geometry.
+        """
+    def load_scene(self, path: str) -> None:
+        """Replaces the scene and camera with those saved by save_scene, reloading meshes."""
     def add_audio_source(self, source: "pie.audio.AudioSource") -> None:
         """Adds an audio source to the mixer."""
+    def set_master_volume(self, volume: float) -> None:
+        """Sets the gain applied to the final audio mix."""
+    def set_group_volume(self, group: str, volume: float) -> None:
+        """Sets the volume of a mixer group: "sfx", "music", "voice" or "ambient"."""
+    def group_volume(self, group: str) -> float:
+        """Returns the volume of a mixer group."""
+    def set_doppler_factor(self, factor: float) -> None:
+        """Scales the Doppler pitch shift of moving sources; 1.0 is physical, 0.0 disables it.
+
+        The listener's velocity is taken from the camera's movement each frame.
+        """
+    def set_muted(self, muted: bool) -> None:
+        """Mutes or unmutes all audio output."""
     def is_key_down(self, key: str) -> bool:
-        """Returns True if the key is pressed."""
+        """Returns True if the key is pressed.
+
+        Keys are SDL key or scancode names ("Space", "Left Shift", "A"), matched case-insensitively.
+        Raises ValueError for unknown names.
+        """
+    def is_key_pressed(self, key: str) -> bool:
+        """Returns True if the key went down this frame."""
+    def is_key_released(self, key: str) -> bool:
+        """Returns True if the key went up this frame."""
+    def key_names(self) -> List[str]:
+        """Returns every key name the current keyboard layout produces."""
+    def is_mouse_down(self, button: str) -> bool:
+        """Returns True if the mouse button ("Left", "Right", "Middle") is held."""
+    def is_mouse_pressed(self, button: str) -> bool:
+        """Returns True if the mouse button went down this frame."""
+    def is_mouse_released(self, button: str) -> bool:
+        """Returns True if the mouse button went up this frame."""
+    def gamepad_count(self) -> int:
+        """Returns the number of connected game controllers."""
+    def gamepad_axis(self, index: int, axis: str) -> float:
+        """Returns a deadzone-filtered axis value in [-1, 1] for a controller."""
+    def gamepad_button(self, index: int, button: str) -> bool:
+        """Returns True if the controller button is held."""
     def get_mouse_pos(self) -> Tuple[int, int]:
         """Returns mouse position."""
+    def get_mouse_ndc(self) -> Tuple[float, float]:
+        """Returns the mouse position in normalized device coordinates, [-1, 1] with y up."""
     def get_mouse_rel(self) -> Tuple[int, int]:
         """Returns relative mouse movement."""
+    def get_mouse_scroll(self) -> Tuple[float, float]:
+        """Returns mouse wheel scroll accumulated this frame."""
     def set_mouse_capture(self, enabled: bool) -> None:
         """Enables or disables mouse capture."""
     def update(self) -> bool:
-        """Updates engine state and renders. Returns False on quit."""
+        """Updates engine state and renders. Returns False once the engine has quit, including when the window is closed."""
+    def delta_time(self) -> float:
+        """Returns seconds elapsed between the last two updates, ignoring the time scale."""
+    def elapsed_time(self) -> float:
+        """Returns seconds since the engine was created, ignoring the time scale."""
+    def frame_count(self) -> int:
+        """Returns how many times `update` has been called."""
+    def set_time_scale(self, scale: float) -> None:
+        """Scales physics and animation speed; 0 pauses them. Audio and rendering are unaffected."""
+    def time_scale(self) -> float:
+        """Returns the current time scale."""
+    def step_physics(self, dt: float) -> None:
+        """Advances physics by exactly one step of dt seconds, ignoring the time scale.
+
+        The fixed step used by automatic stepping is left unchanged.
+        """
+    def set_auto_physics(self, enabled: bool) -> None:
+        """Enables or disables the physics stepping done inside update(); on by default."""
+    def add_rigid_body(
+        self, entity: "pie.entity.Entity", body_type: str = "dynamic", collider: str = "box"
+    ) -> None:
+        """Simulates an entity with a rigid body.
+
+        body_type is "dynamic", "fixed" or "kinematic". collider is "box" or
+        "sphere", sized from the mesh bounds and the entity's scale, or "none".
+        Dynamic bodies write their pose back to the entity after each step;
+        kinematic bodies follow the entity's transform. Moving an entity
+        directly teleports its body and wakes it. Bodies live in world space,
+        so parented entities are converted through their parents' transforms.
+        """
+    def remove_rigid_body(self, entity: "pie.entity.Entity") -> bool:
+        """Stops simulating an entity. Returns whether it had a rigid body."""
+    def is_body_sleeping(self, entity: "pie.entity.Entity") -> bool: ...
+    def wake_body(self, entity: "pie.entity.Entity") -> None: ...
+    def sleep_body(self, entity: "pie.entity.Entity") -> None:
+        """Puts a body to sleep until something touches it or it is woken."""
+    def get_linear_velocity(self, entity: "pie.entity.Entity") -> Tuple[float, float, float]: ...
+    def set_linear_velocity(self, entity: "pie.entity.Entity", x: float, y: float, z: float) -> None:
+        """Sets the linear velocity of a dynamic body, waking it up."""
+    def get_angular_velocity(self, entity: "pie.entity.Entity") -> Tuple[float, float, float]:
+        """Returns the angular velocity in radians per second around each world axis."""
+    def set_angular_velocity(self, entity: "pie.entity.Entity", x: float, y: float, z: float) -> None:
+        """Sets the angular velocity of a dynamic body, waking it up."""
+    def add_joint(
+        self,
+        entity_a: "pie.entity.Entity",
+        entity_b: "pie.entity.Entity",
+        kind: str,
+        axis: Tuple[float, float, float] = (0.0, 1.0, 0.0),
+        anchor_a: Tuple[float, float, float] = (0.0, 0.0, 0.0),
+        anchor_b: Tuple[float, float, float] = (0.0, 0.0, 0.0),
+        limits: Optional[Tuple[float, float]] = None,
+    ) -> int:
+        """Connects the rigid bodies of two entities and returns a handle for remove_joint().
+
+        kind is "fixed", "revolute" (rotates around axis) or "prismatic"
+        (slides along axis). Anchors are in each body's local space; limits
+        bounds the angle in radians or the distance along the axis.
+        """
+    def remove_joint(self, handle: int) -> bool:
+        """Removes a joint made by add_joint(). Returns whether it still existed."""
+    def add_heightfield(
+        self,
+        entity: "pie.entity.Entity",
+        heights: List[float],
+        rows: int,
+        cols: int,
+        scale: Tuple[float, float, float],
+    ) -> None:
+        """Gives an entity a heightfield collider for terrain.
+
+        heights holds rows * cols samples row by row, rows running along Z
+        and columns along X. The grid is centered on the entity and spans
+        scale.x by scale.z, with heights multiplied by scale.y, then by the
+        entity's scale, so a mesh built from the same grid lines up with it.
+        Entities without a rigid body get a fixed one.
+        """
+    def add_trimesh_collider(self, entity: "pie.entity.Entity") -> None:
+        """Gives an entity a triangle-mesh collider matching its mesh, scaled by the entity's scale.
+
+        Only static geometry is supported: entities without a rigid body get
+        a fixed one, and other body types are rejected.
+        """
+    def overlap_sphere(
+        self, center: Tuple[float, float, float], radius: float
+    ) -> List["pie.entity.Entity"]:
+        """Returns entities whose colliders intersect a sphere.
+
+        Colliders added since the last physics step are not seen yet.
+        """
+    def shape_cast(
+        self,
+        shape: str,
+        start: Tuple[float, float, float],
+        end: Tuple[float, float, float],
+        radius: float = 0.5,
+        half_extents: Tuple[float, float, float] = (0.5, 0.5, 0.5),
+    ) -> Optional[Tuple["pie.entity.Entity", Tuple[float, float, float]]]:
+        """Sweeps a "sphere" (of radius) or "box" (of half_extents) from start to end.
+
+        Returns the first entity hit and the shape's center at contact, or
+        None if the path is clear.
+        """
+    def fps(self) -> float:
+        """Returns frames per second averaged over recent frames."""
+    def read_pixels(self) -> bytes:
+        """Returns the last rendered frame as width * height * 4 RGBA bytes, top row first."""
     def poll_events(self) -> List["pie.events.SdlEvent"]:
         """Returns and clears pending SDL events."""
         ...
+    def screen_to_ray(
+        self, x: int, y: int
+    ) -> Tuple[Tuple[float, float, float], Tuple[float, float, float]]:
+        """Returns a world-space (origin, direction) ray through a window pixel."""
     def move_camera(self, dx: float, dy: float, dz: float) -> None:
         """Moves the camera."""
+    def move_camera_local(self, forward: float, right: float, up: float) -> None:
+        """Moves the camera relative to its facing direction."""
+    def set_camera_bounds(
+        self, min: Tuple[float, float, float], max: Tuple[float, float, float]
+    ) -> None:
+        """Keeps move_camera and move_camera_local inside the box from min to max."""
+    def unset_camera_bounds(self) -> None:
+        """Removes the camera movement bounds."""
     def rotate_camera(self, yaw: float, pitch: float) -> None:
         """Rotates the camera."""
+    def orbit_camera(self, target: Tuple[float, float, float], yaw: float, pitch: float, distance: float) -> None:
+        """Places the camera distance units from target, facing it from yaw/pitch degrees."""
     def configure_point_lights(self, count: int) -> None:
-        """Configures point light shadow maps."""
+        """Gives the first count point lights shadow maps; raises ValueError above max_point_shadow_lights()."""
+    def max_point_shadow_lights(self) -> int:
+        """Returns how many point lights can cast shadows at once."""
+    def set_shader_directory(self, path: Optional[str]) -> None:
+        """Loads built-in shaders from path on reload, or the embedded sources if None."""
+    def reload_shaders(self) -> None:
+        """Recompiles built-in shaders, keeping the old ones if compilation fails."""
+    def set_vsync(self, mode: str) -> None:
+        """Sets the swap interval: "on", "off" or "adaptive".
+
+        "adaptive" falls back to "off" when the driver lacks it.
+        Raises RuntimeError if the driver rejects any other mode.
+        """
+    def set_shadow_resolution(self, directional: int, point: int) -> None:
+        """Sets the directional shadow map and point shadow cube face sizes."""
+    def set_shadow_softness(self, samples: int) -> None:
+        """Sets the shadow filter kernel width in texels; 1 gives hard edges."""
+    def set_shadow_cascades(self, count: int, split_lambda: float = 0.75, distance: float = 100.0) -> None:
+        """Splits the directional shadow into 1-4 cascades over distance units of view.
+
+        split_lambda blends uniform (0) and logarithmic (1) split spacing.
+        """
+    def set_clear_color(self, r: float, g: float, b: float) -> None:
+        """Sets the background color shown where nothing was drawn."""
+    def set_ambient_light(self, r: float, g: float, b: float) -> None:
+        """Sets the ambient light color; the default is (0.1, 0.1, 0.1)."""
+    def set_polygon_mode(self, mode: str) -> None:
+        """Sets how scene geometry is rasterized: "fill", "line" or "point"."""
+    def set_cull_mode(self, mode: str) -> None:
+        """Sets which faces the geometry pass discards: "back", "front" or "none" (default)."""
+    def set_front_face(self, ccw: bool) -> None:
+        """Treats counter-clockwise (True, default) or clockwise winding as front-facing."""
+    def set_fxaa_enabled(self, enabled: bool) -> None:
+        """Enables or disables FXAA on the final image."""
+    def set_tone_mapping(self, mode: str) -> None:
+        """Sets the tone mapping operator: "reinhard", "aces", "uncharted2" or "none"."""
+    def set_fog(
+        self,
+        color: Tuple[float, float, float],
+        density: float,
+        mode: str,
+        start: float = 10.0,
+        end: float = 100.0,
+    ) -> None:
+        """Sets distance fog: "none", "linear" (start/end), "exp" or "exp2" (density)."""
+    def set_exposure(self, exposure: float) -> None:
+        """Sets the exposure applied before tone mapping."""
+    def set_bloom_enabled(self, enabled: bool) -> None:
+        """Enables or disables bloom."""
+    def set_bloom_threshold(self, threshold: float) -> None:
+        """Sets the luminance threshold for the bloom bright-pass."""
+    def set_bloom_intensity(self, intensity: float) -> None:
+        """Sets the bloom contribution in the composite pass."""
+    def set_bloom_radius(self, radius: float) -> None:
+        """Sets the bloom upsample filter radius in UV units (default 0.005)."""
+    def set_bloom_mip_count(self, count: int) -> None:
+        """Sets how many half-resolution levels the bloom blur walks through, clamped to 1..=8.
+
+        More levels spread the glow further (default 6).
+        """
+    def bloom_mip_count(self) -> int:
+        """Returns the current bloom mip chain length."""
+    def set_ssr_enabled(
+        self, enabled: bool, steps: int = 48, max_distance: float = 20.0
+    ) -> None:
+        """Reflects the visible scene in glossy surfaces by ray-marching the G-buffer.
+
+        steps and max_distance trade cost against how far and how precisely
+        reflections are found.
+        """
+    def set_motion_blur(self, strength: float) -> None:
+        """Blurs the frame along camera motion; 0 turns it off.
+
+        strength scales the distance moved since the last frame into the blur length.
+        """
+    def set_vignette(
+        self, radius: float = 0.75, softness: float = 0.45, intensity: float = 0.5
+    ) -> None:
+        """Darkens the frame edges.
+
+        radius is the distance from the screen center (0.5 reaches the edge
+        midpoints) where darkening starts, fading in over softness. intensity
+        0 disables it and 1 fades to black.
+        """
+    def set_color_lut(self, texture: Optional["pie.texture.Texture"]) -> None:
+        """Grades the final image through a color lookup table, or None to remove it.
+
+        The texture holds an n*n by n strip of slices (e.g. 256x16): red runs
+        across each slice, green down it, and blue steps between slices. Load
+        it without srgb.
+        """
+    def set_chromatic_aberration(self, strength: float) -> None:
+        """Splits red and blue towards the frame edges by strength times the distance from the center.
+
+        Around 0.01 is subtle; 0 disables it.
+        """
+    def set_film_grain(self, strength: float) -> None:
+        """Adds animated noise of amplitude strength (around 0.05 is subtle); 0 disables it."""
+    def set_dof(self, focus_distance: float, aperture: float, enabled: bool = True) -> None:
+        """Keeps surfaces focus_distance away from the camera sharp and blurs the rest.
+
+        A larger aperture gives a shallower focus.
+        """
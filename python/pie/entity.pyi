@@ -1,7 +1,10 @@
-from typing import TYPE_CHECKING, Tuple
+from typing import TYPE_CHECKING, List, Optional, Tuple
 
 if TYPE_CHECKING:
+    from .animation import Animator
+    from .material import Material
     from .mesh import Mesh
+    from .shader import Shader
 
 class Entity:
     """Scene entity with transform and optional mesh."""
@@ -10,14 +13,61 @@ class Entity:
     rotation: Tuple[float, float, float]
     scale: Tuple[float, float, float]
     mesh: "Mesh"
-    def __init__(self) -> None: ...
+    material: Optional["Material"]
+    parent: Optional["Entity"]
+    shader: Optional["Shader"]
+    animator: Optional["Animator"]
+    transparent: bool
+    opacity: float
+    depth_test: bool
+    """When False the entity draws over earlier geometry, e.g. for gizmos."""
+    depth_write: bool
+    """When False the entity does not occlude geometry drawn after it."""
+    name: Optional[str]
+    tags: List[str]
+    def __init__(self, name: Optional[str] = None) -> None: ...
+    def add_tag(self, tag: str) -> None:
+        """Adds tag unless the entity already has it."""
+    def remove_tag(self, tag: str) -> bool:
+        """Removes tag; returns whether it was present."""
+    def has_tag(self, tag: str) -> bool: ...
     def set_mesh(self, mesh: "Mesh") -> None: ...
+    def set_material(self, material: Optional["Material"]) -> None: ...
+    def set_shader(self, shader: Optional["Shader"]) -> None:
+        """Overrides the geometry shader used to draw this entity."""
+    def set_animator(self, animator: Optional["Animator"]) -> None:
+        """Poses the skinned mesh with animator, advanced every frame."""
+    def get_quaternion(self) -> Tuple[float, float, float, float]:
+        """Returns the rotation as an (x, y, z, w) quaternion."""
+    def set_quaternion(self, x: float, y: float, z: float, w: float) -> None:
+        """Sets the rotation from a quaternion; it is normalized."""
+    def look_at(
+        self, target: Tuple[float, float, float], up: Tuple[float, float, float] = (0.0, 1.0, 0.0)
+    ) -> None:
+        """Rotates the entity so its -Z axis points at target."""
+    def set_parent(self, parent: Optional["Entity"]) -> None:
+        """Makes this entity's transform relative to parent."""
+    def get_world_matrix(self) -> List[List[float]]:
+        """Returns the column-major model matrix including parent transforms."""
+
+class ProjectionMode:
+    Perspective: int = 0
+    Orthographic: int = 1
 
 class Camera:
     """Camera entity."""
 
     position: Tuple[float, float, float]
     fov: float
+    projection_mode: ProjectionMode
+    ortho_size: float
     yaw_pitch: Tuple[float, float]
+    up: Tuple[float, float, float]
+    """Reference up direction; roll rotates the view around the facing direction from it."""
+    roll: float
     def __init__(self, x: float, y: float, z: float) -> None:
         """Creates camera at position."""
+    def get_view_matrix(self) -> List[List[float]]:
+        """Returns the column-major view matrix."""
+    def get_projection_matrix(self, aspect: float) -> List[List[float]]:
+        """Returns the column-major projection matrix for an aspect ratio."""
@@ -3,8 +3,13 @@ class InputState:
 
     mouse_pos: tuple[int, int]
     mouse_rel: tuple[int, int]
+    scroll_delta: tuple[float, float]
     mouse_captured: bool
     def __init__(self) -> None: ...
     def prepare_update(self) -> None: ...
     def is_key_down(self, key: str) -> bool: ...
+    def is_key_pressed(self, key: str) -> bool: ...
+    def is_key_released(self, key: str) -> bool: ...
     def is_mouse_down(self, btn: str) -> bool: ...
+    def is_mouse_pressed(self, btn: str) -> bool: ...
+    def is_mouse_released(self, btn: str) -> bool: ...
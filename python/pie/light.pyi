@@ -11,6 +11,12 @@ class Light:
     color: Tuple[float, float, float]
     radius: float
     light_type: LightType
+    show_gizmo: bool
+    """Whether a sphere is drawn at this point light while light gizmos are enabled."""
+    cast_shadows: bool
+    """Whether the light renders a shadow map; off makes a cheap fill light."""
+    shadow_bias: float
+    """Scales the shadow depth bias (default 1.0): raise it to fix acne, lower it to fix peter-panning."""
     def __init__(
         self,
         r: float,
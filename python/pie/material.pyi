@@ -0,0 +1,42 @@
+from typing import TYPE_CHECKING, Optional, Tuple
+
+if TYPE_CHECKING:
+    import pie.texture
+
+class Material:
+    """Metallic/roughness PBR material."""
+
+    albedo: Tuple[float, float, float]
+    metallic: float
+    roughness: float
+    emissive: Tuple[float, float, float]
+    """Self-illumination in linear HDR units; it glows and blooms but lights nothing else."""
+    specular_color: Tuple[float, float, float]
+    """Tints the reflectance of non-metals; metals take their specular color from the albedo."""
+    shininess: float
+    """Blinn-Phong style specular exponent, mapped onto `roughness`; must be non-negative."""
+    albedo_texture: Optional["pie.texture.Texture"]
+    metallic_roughness_texture: Optional["pie.texture.Texture"]
+    normal_texture: Optional["pie.texture.Texture"]
+    height_texture: Optional["pie.texture.Texture"]
+    height_scale: float
+    """Parallax depth in UV units; white texels in the height map are the top."""
+    parallax_steps: int
+    """Ray-march layers for parallax occlusion mapping (1-128)."""
+    def __init__(
+        self, r: float, g: float, b: float, metallic: float, roughness: float
+    ) -> None: ...
+    def set_albedo_texture(self, texture: Optional["pie.texture.Texture"]) -> None:
+        """Sets the base color texture."""
+        ...
+    def set_metallic_roughness_texture(
+        self, texture: Optional["pie.texture.Texture"]
+    ) -> None:
+        """Sets the metallic (blue) / roughness (green) texture."""
+        ...
+    def set_normal_texture(self, texture: Optional["pie.texture.Texture"]) -> None:
+        """Sets the tangent-space normal map (OpenGL convention, +Y up)."""
+        ...
+    def set_height_texture(self, texture: Optional["pie.texture.Texture"]) -> None:
+        """Sets the height map (red channel) used for parallax occlusion mapping."""
+        ...
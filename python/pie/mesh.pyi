@@ -1,6 +1,7 @@
-from typing import TYPE_CHECKING, Optional, Tuple
+from typing import TYPE_CHECKING, List, Optional, Tuple
 
 if TYPE_CHECKING:
+    import pie.material
     import pie.texture
 
 class Mesh:
@@ -8,9 +9,32 @@ class Mesh:
 
     texture_id: int
     color: Optional[Tuple[int, int, int, int]]
+    material: Optional["pie.material.Material"]
+    uv_scale: Tuple[float, float]
+    uv_offset: Tuple[float, float]
+    uv_scroll: Tuple[float, float]
+    """Texture widths per second the UV offset scrolls by; see set_uv_scroll."""
+    is_deleted: bool
     @staticmethod
-    def from_obj(path: str) -> "Mesh":
-        """Loads mesh from OBJ file."""
+    def from_obj(path: str, filter: str = "linear") -> "Mesh":
+        """Loads mesh from OBJ file; filter applies to its material textures."""
+        ...
+    @staticmethod
+    def from_gltf(path: str) -> "Mesh":
+        """Loads mesh from glTF or GLB file. Skinned files can be played with an Animator."""
+        ...
+    @staticmethod
+    def from_data(
+        positions: List[float],
+        normals: List[float],
+        tex_coords: List[float],
+        indices: List[int],
+        dynamic: bool = False,
+    ) -> "Mesh":
+        """Creates mesh from flat vertex arrays; empty normals are computed.
+
+        Dynamic meshes can be changed afterwards with update_vertices.
+        """
         ...
     @staticmethod
     def empty() -> "Mesh":
@@ -25,6 +49,18 @@ class Mesh:
         """Creates icosphere mesh."""
         ...
     @staticmethod
+    def cylinder(segments: int) -> "Mesh":
+        """Creates capped cylinder mesh."""
+        ...
+    @staticmethod
+    def cone(segments: int) -> "Mesh":
+        """Creates capped cone mesh."""
+        ...
+    @staticmethod
+    def torus(major_segments: int, minor_segments: int) -> "Mesh":
+        """Creates torus mesh."""
+        ...
+    @staticmethod
     def plane() -> "Mesh":
         """Creates plane mesh."""
         ...
@@ -34,3 +70,21 @@ class Mesh:
     def clear_texture(self) -> None:
         """Removes attached texture."""
         ...
+    def set_material(self, material: Optional["pie.material.Material"]) -> None:
+        """Attaches a PBR material, overriding per-submesh colors."""
+        ...
+    def set_uv_scroll(self, speed_u: float, speed_v: float) -> None:
+        """Scrolls the texture by the given texture widths per second of engine time."""
+        ...
+    def update_vertices(self, positions: List[float], normals: Optional[List[float]] = None) -> None:
+        """Replaces the positions of a dynamic mesh; normals are recomputed unless given."""
+        ...
+    def bounds(self) -> Tuple[Tuple[float, float, float], Tuple[float, float, float]]:
+        """Returns the local-space (min, max) corners of the bounding box."""
+        ...
+    def bounding_sphere(self) -> Tuple[Tuple[float, float, float], float]:
+        """Returns the local-space (center, radius) of the bounding sphere."""
+        ...
+    def delete(self) -> None:
+        """Frees the GPU buffers and model-file textures now rather than on garbage collection; the mesh then draws nothing."""
+        ...
@@ -1,9 +1,14 @@
+import animation  # pyright: ignore[reportMissingImports]
 import audio  # pyright: ignore[reportMissingImports]
 import engine  # pyright: ignore[reportMissingImports]
 import entity  # pyright: ignore[reportMissingImports]
 import events  # pyright: ignore[reportMissingImports]
 import light  # pyright: ignore[reportMissingImports]
+import material  # pyright: ignore[reportMissingImports]
 import mesh  # pyright: ignore[reportMissingImports]
 import scene  # pyright: ignore[reportMissingImports]
+import shader  # pyright: ignore[reportMissingImports]
+import skybox  # pyright: ignore[reportMissingImports]
+import sprite  # pyright: ignore[reportMissingImports]
 
-__all__ = ["engine", "events", "mesh", "scene", "audio", "entity", "light"]
+__all__ = ["engine", "events", "mesh", "scene", "audio", "entity", "light", "material", "shader", "skybox", "animation", "sprite"]
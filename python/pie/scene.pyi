@@ -1,4 +1,4 @@
-from typing import TYPE_CHECKING, List
+from typing import TYPE_CHECKING, List, Optional
 
 if TYPE_CHECKING:
     from .entity import Entity
@@ -12,3 +12,10 @@ class Scene:
     def __init__(self) -> None: ...
     def add_entity(self, entity: "Entity") -> None: ...
     def add_light(self, light: "Light") -> None: ...
+    def remove_entity(self, entity: "Entity") -> bool: ...
+    def remove_light(self, light: "Light") -> bool: ...
+    def clear(self) -> None: ...
+    def find_by_name(self, name: str) -> Optional["Entity"]:
+        """Returns the first entity with the given name."""
+    def find_by_tag(self, tag: str) -> List["Entity"]:
+        """Returns every entity carrying tag, in scene order."""
@@ -0,0 +1,29 @@
+from typing import List, Tuple
+
+class Shader:
+    """GLSL shader program.
+
+    Custom geometry shaders must write the engine's G-buffer outputs: gPosition,
+    gNormal and gAlbedoSpec at locations 0-2. gSpecular at location 4 (rgb tint,
+    alpha 1) is optional; texels without it get an untinted specular.
+    gVelocity at location 3 (vec2, UV-space motion since the last frame) is
+    optional too; texels without it read as still and are not motion blurred.
+    """
+
+    id: int
+    @staticmethod
+    def from_files(vsh: str, fsh: str) -> "Shader":
+        """Compiles a shader from vertex and fragment source files."""
+        ...
+    @staticmethod
+    def from_source(vsh: str, fsh: str) -> "Shader":
+        """Compiles a shader from vertex and fragment source strings."""
+        ...
+    def set_int(self, name: str, value: int) -> None: ...
+    def set_float(self, name: str, value: float) -> None: ...
+    def set_vec2(self, name: str, value: Tuple[float, float]) -> None: ...
+    def set_vec3(self, name: str, value: Tuple[float, float, float]) -> None: ...
+    def set_vec4(self, name: str, value: Tuple[float, float, float, float]) -> None: ...
+    def set_mat4(self, name: str, value: List[List[float]]) -> None:
+        """Sets a column-major 4x4 matrix uniform."""
+        ...
@@ -0,0 +1,10 @@
+class Skybox:
+    """Cubemap drawn behind the scene."""
+
+    id: int
+    @staticmethod
+    def from_faces(
+        right: str, left: str, top: str, bottom: str, front: str, back: str
+    ) -> "Skybox":
+        """Loads a cubemap from six face images (+X, -X, +Y, -Y, +Z, -Z)."""
+        ...
@@ -0,0 +1,16 @@
+from typing import TYPE_CHECKING, Optional, Tuple
+
+if TYPE_CHECKING:
+    from .texture import Texture
+
+class Sprite:
+    """Textured rectangle drawn over the frame, in window pixels from the top-left."""
+
+    texture: Optional["Texture"]
+    position: Tuple[float, float]
+    size: Tuple[float, float]
+    tint: Tuple[float, float, float, float]
+    visible: bool
+    def __init__(self, texture: Optional["Texture"], x: float, y: float, width: float, height: float) -> None:
+        """Without a texture the sprite is a solid rectangle of its tint."""
+    def set_texture(self, texture: Optional["Texture"]) -> None: ...
@@ -13,9 +13,25 @@ class Texture:
         """Creates texture from RGBA color."""
         ...
     @staticmethod
-    def from_image(path: str) -> "Texture":
-        """Loads texture from image file."""
+    def from_image(
+        path: str, filter: str = "linear", wrap: str = "repeat", srgb: bool = False
+    ) -> "Texture":
+        """Loads texture from image file; filter is "nearest" or "linear", wrap is "repeat", "clamp" or "mirror". Set srgb for color textures."""
+        ...
+    @staticmethod
+    def from_bytes(
+        data: bytes,
+        width: int,
+        height: int,
+        filter: str = "linear",
+        wrap: str = "repeat",
+        srgb: bool = False,
+    ) -> "Texture":
+        """Creates texture from tightly packed RGBA8 bytes."""
         ...
     def to_rgba_f32(self) -> Tuple[float, float, float, float]:
         """Returns color as normalized floats."""
         ...
+    def delete(self) -> None:
+        """Frees the GPU texture now rather than on garbage collection; users of it draw untextured."""
+        ...
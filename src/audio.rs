@@ -2,39 +2,292 @@ use glam::Vec3;
 use pyo3::prelude::*;
 use resampler::{ResamplerFft, SampleRate};
 use sdl2::audio::AudioCallback;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Speed of sound in world units (metres) per second, for Doppler shift.
+const SPEED_OF_SOUND: f32 = 343.3;
+const MIN_DOPPLER_SHIFT: f32 = 0.5;
+const MAX_DOPPLER_SHIFT: f32 = 2.0;
+
+/// Decoded audio a stream keeps queued ahead of the mixer, in frames.
+const STREAM_BUFFER_FRAMES: usize = 22050;
+/// Frames a stream decodes from a WAV file per read.
+const STREAM_READ_FRAMES: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RolloffModel {
+    Inverse,
+    Linear,
+    /// Inverse-square falloff.
+    Exponential,
+}
+
+/// Mixer bus a source plays through; each has its own volume on top of the
+/// source's and the master volume.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AudioGroup {
+    Sfx,
+    Music,
+    Voice,
+    Ambient,
+}
+
+impl AudioGroup {
+    pub const COUNT: usize = 4;
+
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "sfx" => Ok(AudioGroup::Sfx),
+            "music" => Ok(AudioGroup::Music),
+            "voice" => Ok(AudioGroup::Voice),
+            "ambient" => Ok(AudioGroup::Ambient),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown audio group: {}",
+                name
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AudioGroup::Sfx => "sfx",
+            AudioGroup::Music => "music",
+            AudioGroup::Voice => "voice",
+            AudioGroup::Ambient => "ambient",
+        }
+    }
+}
 
 pub enum AudioData {
     Clip { samples: Vec<f32>, channels: usize },
     Sine { freq: f32, phase: f32 },
+    Stream(AudioStream),
+}
+
+/// State shared between an `AudioStream` and its decoder thread.
+struct StreamShared {
+    /// Interleaved 44.1kHz samples waiting to be mixed.
+    buffer: Mutex<VecDeque<f32>>,
+    looping: AtomicBool,
+    /// Set once the decoder has queued its last sample.
+    finished: AtomicBool,
+    /// Asks the decoder thread to exit.
+    stop: AtomicBool,
+    /// Output frame the decoder should restart from, with the generation
+    /// that seek started.
+    seek: Mutex<Option<(usize, u64)>>,
+    /// Bumped by every seek while `buffer` is locked, so the decoder can
+    /// drop a block it started before the seek.
+    generation: AtomicU64,
+}
+
+/// A file decoded on a background thread into a small ring buffer, so long
+/// music tracks never sit fully decoded in memory.
+pub struct AudioStream {
+    shared: Arc<StreamShared>,
+    channels: usize,
+    /// Length in seconds, when the file header records it.
+    duration: Option<f32>,
+    /// The frames either side of the playback position, as (left, right).
+    window: [(f32, f32); 2],
+    window_len: usize,
+}
+
+impl AudioStream {
+    fn open(path: &Path, looping: bool) -> Result<Self, String> {
+        let decoder = StreamDecoder::open(path)?;
+        let channels = decoder.channels();
+        let duration = decoder.duration();
+        let shared = Arc::new(StreamShared {
+            buffer: Mutex::new(VecDeque::with_capacity(STREAM_BUFFER_FRAMES * channels)),
+            looping: AtomicBool::new(looping),
+            finished: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+            seek: Mutex::new(None),
+            generation: AtomicU64::new(0),
+        });
+
+        let thread_shared = Arc::clone(&shared);
+        let path = path.to_path_buf();
+        std::thread::Builder::new()
+            .name("pie-audio-stream".to_string())
+            .spawn(move || run_stream_decoder(path, decoder, thread_shared))
+            .map_err(|e| format!("Failed to start audio stream thread: {}", e))?;
+
+        Ok(AudioStream {
+            shared,
+            channels,
+            duration,
+            window: [(0.0, 0.0); 2],
+            window_len: 0,
+        })
+    }
+
+    /// Restarts decoding at `frame` and drops everything already queued.
+    fn seek(&mut self, frame: usize) {
+        if let Ok(mut seek) = self.shared.seek.lock()
+            && let Ok(mut buffer) = self.shared.buffer.lock()
+        {
+            let generation = self.shared.generation.fetch_add(1, Ordering::SeqCst) + 1;
+            *seek = Some((frame, generation));
+            buffer.clear();
+        }
+        self.shared.finished.store(false, Ordering::SeqCst);
+        self.window_len = 0;
+    }
+
+    /// Tops the playback window up to two frames from `buffer`.
+    fn refill(&mut self, buffer: &mut VecDeque<f32>) {
+        while self.window_len < 2 && buffer.len() >= self.channels {
+            let left = buffer.pop_front().unwrap_or(0.0);
+            let right = if self.channels == 2 {
+                buffer.pop_front().unwrap_or(0.0)
+            } else {
+                left
+            };
+            self.window[self.window_len] = (left, right);
+            self.window_len += 1;
+        }
+    }
+
+    /// Steps the playback window one frame forward.
+    fn advance(&mut self, buffer: &mut VecDeque<f32>) {
+        if self.window_len > 0 {
+            self.window[0] = self.window[1];
+            self.window_len -= 1;
+        }
+        self.refill(buffer);
+    }
+}
+
+impl Drop for AudioStream {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::SeqCst);
+    }
 }
 
 #[pyclass]
 pub struct AudioSource {
     pub position: Vec3,
+    /// World-space velocity, used only for Doppler shift.
+    pub velocity: Vec3,
     pub data: AudioData,
     #[pyo3(get, set)]
     pub looping: bool,
     #[pyo3(get)]
     pub playing: bool,
     pub cursor: usize,
+    /// Sub-frame playback position, used when `pitch` is not 1.0.
+    pub cursor_frac: f32,
+    #[pyo3(get, set)]
+    pub volume: f32,
+    #[pyo3(get, set)]
+    pub pitch: f32,
     pub current_left_gain: f32,
     pub current_right_gain: f32,
+    /// Fade envelope, ramped by `envelope_step` per frame towards its target.
+    pub envelope_gain: f32,
+    pub envelope_target: f32,
+    pub envelope_step: f32,
     #[pyo3(get, set)]
     pub positional: bool,
+    /// Distance within which a positional source plays at full volume.
+    #[pyo3(get, set)]
+    pub min_distance: f32,
+    /// Distance beyond which a positional source is silent.
+    #[pyo3(get, set)]
+    pub max_distance: f32,
+    pub rolloff_model: RolloffModel,
+    pub group: AudioGroup,
+    /// Cutoff in Hz of a one-pole low-pass applied before panning, for
+    /// muffled or underwater sounds. `None` bypasses the filter.
+    #[pyo3(get)]
+    pub lowpass_cutoff: Option<f32>,
+    /// Filter output of the previous frame, as (left, right).
+    pub lowpass_state: (f32, f32),
 }
 
 impl AudioSource {
     pub fn new(position: Vec3, data: AudioData, looping: bool) -> Self {
         Self {
             position,
+            velocity: Vec3::ZERO,
             data,
             looping,
             playing: false,
             cursor: 0,
+            cursor_frac: 0.0,
+            volume: 1.0,
+            pitch: 1.0,
             current_left_gain: 0.0,
             current_right_gain: 0.0,
+            envelope_gain: 1.0,
+            envelope_target: 1.0,
+            envelope_step: 0.0,
             positional: true,
+            min_distance: 1.0,
+            max_distance: 100.0,
+            rolloff_model: RolloffModel::Inverse,
+            group: AudioGroup::Sfx,
+            lowpass_cutoff: None,
+            lowpass_state: (0.0, 0.0),
+        }
+    }
+
+    /// Smoothing factor of the low-pass filter at 44.1kHz, if enabled.
+    fn lowpass_alpha(&self) -> Option<f32> {
+        self.lowpass_cutoff.map(|cutoff| {
+            let cutoff = cutoff.clamp(1.0, 22050.0);
+            1.0 - (-2.0 * std::f32::consts::PI * cutoff / 44100.0).exp()
+        })
+    }
+
+    /// Runs one frame through the low-pass filter. With the filter bypassed
+    /// the state still tracks the input, so enabling it later doesn't click.
+    fn lowpass(&mut self, alpha: Option<f32>, left: f32, right: f32) -> (f32, f32) {
+        let (prev_left, prev_right) = self.lowpass_state;
+        self.lowpass_state = match alpha {
+            Some(a) => (
+                prev_left + a * (left - prev_left),
+                prev_right + a * (right - prev_right),
+            ),
+            None => (left, right),
+        };
+        self.lowpass_state
+    }
+
+    /// Advances the fade envelope by one frame and returns its new value.
+    fn next_envelope_gain(&mut self) -> f32 {
+        if self.envelope_gain < self.envelope_target {
+            self.envelope_gain =
+                (self.envelope_gain + self.envelope_step).min(self.envelope_target);
+        } else if self.envelope_gain > self.envelope_target {
+            self.envelope_gain =
+                (self.envelope_gain - self.envelope_step).max(self.envelope_target);
+        }
+        self.envelope_gain
+    }
+
+    /// Distance attenuation for a positional source, in the range [0, 1].
+    fn distance_gain(&self, dist: f32) -> f32 {
+        let min = self.min_distance.max(0.001);
+        let max = self.max_distance.max(min);
+
+        if dist <= min {
+            return 1.0;
+        }
+        if dist >= max {
+            return 0.0;
+        }
+
+        match self.rolloff_model {
+            RolloffModel::Inverse => min / dist,
+            RolloffModel::Linear => 1.0 - (dist - min) / (max - min),
+            RolloffModel::Exponential => (min / dist).powi(2),
         }
     }
 }
@@ -86,42 +339,103 @@ impl AudioSource {
             frames.push(s as f32 / i16::MAX as f32);
         }
 
-        let (samples, channels) = if spec.sample_rate != 44100 {
-            let source_rate = SampleRate::try_from(spec.sample_rate).map_err(|_| {
-                pyo3::exceptions::PyRuntimeError::new_err(format!(
-                    "Unsupported sample rate: {}",
-                    spec.sample_rate
-                ))
-            })?;
-            let target_rate = SampleRate::Hz44100;
+        let samples = resample_to_44100(frames, ch, spec.sample_rate)?;
+
+        Ok(AudioSource::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            AudioData::Clip {
+                samples,
+                channels: ch,
+            },
+            looping,
+        ))
+    }
+
+    #[staticmethod]
+    pub fn from_ogg(file: &str, looping: bool) -> PyResult<Self> {
+        let reader = std::fs::File::open(file).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to open OGG file: {}", e))
+        })?;
+        let mut stream = lewton::inside_ogg::OggStreamReader::new(reader).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to open OGG file: {}", e))
+        })?;
+
+        let ch = stream.ident_hdr.audio_channels as usize;
+        let sample_rate = stream.ident_hdr.audio_sample_rate;
+        if !(ch == 1 || ch == 2) {
+            return Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "Unsupported OGG format: only mono/stereo supported",
+            ));
+        }
 
-            let mut resampler = ResamplerFft::new(ch, source_rate, target_rate);
-            let output_frames = resampler.chunk_size_output();
-            let mut output = vec![0.0f32; output_frames];
+        let mut frames: Vec<f32> = Vec::new();
+        while let Some(packet) = stream.read_dec_packet_itl().map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to decode OGG file: {}", e))
+        })? {
+            frames.extend(packet.into_iter().map(|s| s as f32 / i16::MAX as f32));
+        }
 
-            resampler
-                .resample(&frames, &mut output)
-                .map_err(|e| {
-                    pyo3::exceptions::PyRuntimeError::new_err(format!("Unexpected error: {}", e))
-                })
-                .unwrap();
+        let samples = resample_to_44100(frames, ch, sample_rate)?;
 
-            (output, ch)
-        } else {
-            (frames, ch)
-        };
+        Ok(AudioSource::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            AudioData::Clip {
+                samples,
+                channels: ch,
+            },
+            looping,
+        ))
+    }
 
+    /// Plays a WAV or OGG file while decoding it on a background thread,
+    /// keeping only about half a second of audio in memory. Prefer this over
+    /// `from_wav`/`from_ogg` for long music tracks.
+    #[staticmethod]
+    #[pyo3(signature = (file, looping=false))]
+    pub fn stream_from_file(file: &str, looping: bool) -> PyResult<Self> {
+        let stream = AudioStream::open(Path::new(file), looping)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
         Ok(AudioSource::new(
             Vec3::new(0.0, 0.0, 0.0),
-            AudioData::Clip { samples, channels },
+            AudioData::Stream(stream),
             looping,
         ))
     }
 
+    /// Starts or resumes playback from the current cursor.
     pub fn play(&mut self) {
         self.playing = true;
     }
 
+    /// Stops playback and rewinds to the start.
+    pub fn stop(&mut self) {
+        self.playing = false;
+        if let AudioData::Stream(stream) = &mut self.data {
+            stream.seek(0);
+        }
+        self.cursor = 0;
+        self.cursor_frac = 0.0;
+        self.current_left_gain = 0.0;
+        self.current_right_gain = 0.0;
+        self.envelope_gain = 1.0;
+        self.envelope_target = 1.0;
+    }
+
+    /// Starts playback from silence, reaching full volume after `seconds`.
+    pub fn fade_in(&mut self, seconds: f32) {
+        self.envelope_gain = 0.0;
+        self.envelope_target = 1.0;
+        self.envelope_step = envelope_step_for(1.0, seconds);
+        self.playing = true;
+    }
+
+    /// Fades to silence over `seconds`, then pauses.
+    pub fn fade_out(&mut self, seconds: f32) {
+        self.envelope_target = 0.0;
+        self.envelope_step = envelope_step_for(self.envelope_gain, seconds);
+    }
+
+    /// Pauses playback, keeping the cursor so `play()` resumes from here.
     pub fn pause(&mut self) {
         self.playing = false;
     }
@@ -136,6 +450,67 @@ impl AudioSource {
         self.position = Vec3::new(position.0, position.1, position.2);
     }
 
+    #[getter]
+    pub fn get_velocity(&self) -> (f32, f32, f32) {
+        (self.velocity.x, self.velocity.y, self.velocity.z)
+    }
+
+    #[setter]
+    pub fn set_velocity(&mut self, velocity: (f32, f32, f32)) {
+        self.velocity = Vec3::from(velocity);
+    }
+
+    #[getter]
+    pub fn get_rolloff_model(&self) -> &'static str {
+        match self.rolloff_model {
+            RolloffModel::Inverse => "inverse",
+            RolloffModel::Linear => "linear",
+            RolloffModel::Exponential => "exponential",
+        }
+    }
+
+    #[setter]
+    pub fn set_rolloff_model(&mut self, model: &str) -> PyResult<()> {
+        self.rolloff_model = match model {
+            "inverse" => RolloffModel::Inverse,
+            "linear" => RolloffModel::Linear,
+            "exponential" => RolloffModel::Exponential,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown rolloff model: {}",
+                    model
+                )));
+            }
+        };
+        Ok(())
+    }
+
+    #[setter]
+    pub fn set_lowpass_cutoff(&mut self, cutoff: Option<f32>) -> PyResult<()> {
+        if let Some(cutoff) = cutoff
+            && (!cutoff.is_finite() || cutoff <= 0.0)
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Low-pass cutoff must be a positive number of Hz or None, got {}",
+                cutoff
+            )));
+        }
+        self.lowpass_cutoff = cutoff;
+        Ok(())
+    }
+
+    /// Mixer group: "sfx" (the default), "music", "voice" or "ambient".
+    #[getter]
+    pub fn get_group(&self) -> &'static str {
+        self.group.name()
+    }
+
+    #[setter]
+    pub fn set_group(&mut self, group: &str) -> PyResult<()> {
+        self.group = AudioGroup::from_name(group)?;
+        Ok(())
+    }
+
     pub fn is_playing(&self) -> bool {
         self.playing
     }
@@ -145,6 +520,48 @@ impl AudioSource {
         self.cursor
     }
 
+    /// Moves playback to `seconds` into the clip. Wraps when looping and
+    /// clamps to the end otherwise.
+    pub fn seek(&mut self, seconds: f32) {
+        let frame = (seconds.max(0.0) * 44100.0) as usize;
+        self.seek_frames(frame);
+    }
+
+    pub fn seek_frames(&mut self, frame: usize) {
+        let frames_available = match &mut self.data {
+            AudioData::Clip { samples, channels } if *channels > 0 => samples.len() / *channels,
+            AudioData::Clip { .. } => 0,
+            AudioData::Sine { .. } => return,
+            AudioData::Stream(stream) => {
+                let frames = stream.duration.map(|d| (d * 44100.0) as usize);
+                let frame = match frames {
+                    Some(0) => 0,
+                    Some(n) if self.looping => frame % n,
+                    Some(n) => frame.min(n),
+                    None => frame,
+                };
+                stream.seek(frame);
+                self.cursor = frame;
+                self.cursor_frac = 0.0;
+                return;
+            }
+        };
+
+        self.cursor = if frames_available == 0 {
+            0
+        } else if self.looping {
+            frame % frames_available
+        } else {
+            frame.min(frames_available)
+        };
+        self.cursor_frac = 0.0;
+    }
+
+    pub fn restart(&mut self) {
+        self.seek_frames(0);
+        self.playing = true;
+    }
+
     #[getter]
     pub fn get_duration(&self) -> f32 {
         match &self.data {
@@ -157,18 +574,35 @@ impl AudioSource {
                 }
             }
             AudioData::Sine { .. } => f32::MAX,
+            AudioData::Stream(stream) => stream.duration.unwrap_or(0.0),
         }
     }
 }
 
+/// How much a source directly behind the listener is attenuated.
+const REAR_ATTENUATION: f32 = 0.4;
+/// How much a source directly above or below the listener is attenuated.
+const ELEVATION_ATTENUATION: f32 = 0.2;
+
 pub struct ListenerState {
     pub position: Vec3,
+    pub velocity: Vec3,
+    pub front: Vec3,
+    pub up: Vec3,
     pub right: Vec3,
+    pub master_volume: f32,
+    /// Scales Doppler pitch shift; 0 disables it.
+    pub doppler_factor: f32,
+    /// Volume of each `AudioGroup`, indexed by its discriminant.
+    pub group_volumes: [f32; AudioGroup::COUNT],
+    pub muted: bool,
 }
 
 pub struct AudioMixer {
     pub sources: Arc<Mutex<Vec<Py<AudioSource>>>>,
     pub listener_state: Arc<Mutex<ListenerState>>,
+    /// Master gain applied on the previous callback, ramped towards the target.
+    current_master_gain: f32,
 }
 
 impl AudioMixer {
@@ -179,6 +613,7 @@ impl AudioMixer {
         AudioMixer {
             sources,
             listener_state,
+            current_master_gain: 1.0,
         }
     }
 }
@@ -189,8 +624,16 @@ impl Default for AudioMixer {
             sources: Arc::new(Mutex::new(Vec::new())),
             listener_state: Arc::new(Mutex::new(ListenerState {
                 position: Vec3::ZERO,
+                velocity: Vec3::ZERO,
+                front: Vec3::NEG_Z,
+                up: Vec3::Y,
                 right: Vec3::X,
+                master_volume: 1.0,
+                doppler_factor: 1.0,
+                group_volumes: [1.0; AudioGroup::COUNT],
+                muted: false,
             })),
+            current_master_gain: 1.0,
         }
     }
 }
@@ -203,11 +646,36 @@ impl AudioCallback for AudioMixer {
             *x = 0.0;
         }
 
-        let (listener_pos, listener_right) = if let Ok(state) = self.listener_state.lock() {
-            (state.position, state.right)
-        } else {
-            (Vec3::ZERO, Vec3::X)
-        };
+        let (listener_pos, listener_front, listener_up, listener_right, master_gain, group_gains) =
+            if let Ok(state) = self.listener_state.lock() {
+                let master_gain = if state.muted {
+                    0.0
+                } else {
+                    state.master_volume.max(0.0)
+                };
+                (
+                    state.position,
+                    state.front,
+                    state.up,
+                    state.right,
+                    master_gain,
+                    state.group_volumes,
+                )
+            } else {
+                (
+                    Vec3::ZERO,
+                    Vec3::NEG_Z,
+                    Vec3::Y,
+                    Vec3::X,
+                    self.current_master_gain,
+                    [1.0; AudioGroup::COUNT],
+                )
+            };
+        let (listener_velocity, doppler_factor) = self
+            .listener_state
+            .lock()
+            .map(|state| (state.velocity, state.doppler_factor.max(0.0)))
+            .unwrap_or((Vec3::ZERO, 0.0));
 
         Python::attach(|py| {
             if let Ok(mut sources) = self.sources.lock() {
@@ -221,8 +689,8 @@ impl AudioCallback for AudioMixer {
                         continue;
                     }
 
-                    let (target_left, target_right) = if !source.positional {
-                        (1.0f32, 1.0f32)
+                    let (target_left, target_right, doppler) = if !source.positional {
+                        (1.0f32, 1.0f32, 1.0f32)
                     } else {
                         let to_source = source.position - listener_pos;
                         let dist_sq = to_source.length_squared();
@@ -231,10 +699,18 @@ impl AudioCallback for AudioMixer {
                         let direction = if dist > 0.001 {
                             to_source / dist
                         } else {
-                            Vec3::Y
+                            listener_front
                         };
 
-                        let gain = 1.0 / (dist_sq + 1.0);
+                        // Stereo panning alone can't tell front from back or
+                        // level from elevated, so those are cued by a mild
+                        // loss of gain instead.
+                        let rear = (-direction.dot(listener_front)).max(0.0);
+                        let elevation = direction.dot(listener_up).abs();
+                        let orientation_gain = (1.0 - REAR_ATTENUATION * rear)
+                            * (1.0 - ELEVATION_ATTENUATION * elevation);
+
+                        let gain = source.distance_gain(dist) * orientation_gain;
 
                         let pan = direction.dot(listener_right);
 
@@ -242,9 +718,30 @@ impl AudioCallback for AudioMixer {
                         let right_gain = p.sqrt();
                         let left_gain = (1.0_f32 - p).sqrt();
 
-                        (left_gain * gain, right_gain * gain)
+                        // Velocities along the source-to-listener line, capped
+                        // just under the speed of sound so the ratio stays finite.
+                        let doppler = if doppler_factor > 0.0 && dist > 0.001 {
+                            let cap = SPEED_OF_SOUND * 0.99;
+                            let toward_listener = -direction;
+                            let listener_speed =
+                                (listener_velocity.dot(toward_listener) * doppler_factor).min(cap);
+                            let source_speed =
+                                (source.velocity.dot(toward_listener) * doppler_factor).min(cap);
+                            // Bounded so a one-frame camera jump can't squeal.
+                            ((SPEED_OF_SOUND - listener_speed) / (SPEED_OF_SOUND - source_speed))
+                                .clamp(MIN_DOPPLER_SHIFT, MAX_DOPPLER_SHIFT)
+                        } else {
+                            1.0
+                        };
+
+                        (left_gain * gain, right_gain * gain, doppler)
                     };
 
+                    let volume = source.volume.max(0.0) * group_gains[source.group as usize];
+                    let (target_left, target_right) = (target_left * volume, target_right * volume);
+                    let pitch = source.pitch.max(0.0) * doppler;
+                    let lowpass = source.lowpass_alpha();
+
                     let samples_len = out.len() / 2;
                     let left_step = if samples_len > 0 {
                         (target_left - source.current_left_gain) / samples_len as f32
@@ -269,18 +766,21 @@ impl AudioCallback for AudioMixer {
                         AudioData::Sine { freq, phase } => {
                             let freq_copy = *freq;
                             let mut phase_local = *phase;
-                            let increment = freq_copy * 2.0 * std::f32::consts::PI / 44100.0;
+                            let increment =
+                                freq_copy * pitch * 2.0 * std::f32::consts::PI / 44100.0;
 
                             for frame in out.chunks_mut(2) {
                                 source.current_left_gain += left_step;
                                 source.current_right_gain += right_step;
+                                let envelope = source.next_envelope_gain();
 
                                 phase_local =
                                     (phase_local + increment) % (2.0 * std::f32::consts::PI);
-                                let sample = phase_local.sin() * 0.5;
+                                let sample = phase_local.sin() * 0.5 * envelope;
+                                let (l, r) = source.lowpass(lowpass, sample, sample);
 
-                                frame[0] += sample * source.current_left_gain;
-                                frame[1] += sample * source.current_right_gain;
+                                frame[0] += l * source.current_left_gain;
+                                frame[1] += r * source.current_right_gain;
                             }
 
                             *phase = phase_local;
@@ -302,29 +802,43 @@ impl AudioCallback for AudioMixer {
                                 source.current_right_gain += right_step;
 
                                 if let Some(fidx) = next_frame_opt {
-                                    if ch == 1 || source.positional {
-                                        let sample = if ch == 1 {
-                                            samples[fidx]
-                                        } else {
-                                            let l = samples[fidx * 2];
-                                            let r = samples[fidx * 2 + 1];
-                                            0.5 * (l + r)
-                                        };
+                                    let next_idx = if fidx + 1 < frames_available {
+                                        fidx + 1
+                                    } else if source.looping {
+                                        0
+                                    } else {
+                                        fidx
+                                    };
 
+                                    let (l0, r0) = read_clip_frame(samples, ch, fidx);
+                                    let (l1, r1) = read_clip_frame(samples, ch, next_idx);
+                                    let t = source.cursor_frac;
+                                    let envelope = source.next_envelope_gain();
+                                    let l = (l0 + (l1 - l0) * t) * envelope;
+                                    let r = (r0 + (r1 - r0) * t) * envelope;
+                                    let (l, r) = source.lowpass(lowpass, l, r);
+
+                                    if source.positional {
+                                        let sample = 0.5 * (l + r);
                                         frame[0] += sample * source.current_left_gain;
                                         frame[1] += sample * source.current_right_gain;
                                     } else {
-                                        let l = samples[fidx * 2];
-                                        let r = samples[fidx * 2 + 1];
                                         frame[0] += l * source.current_left_gain;
                                         frame[1] += r * source.current_right_gain;
                                     }
-                                    if source.cursor + 1 < frames_available {
-                                        source.cursor += 1;
-                                    } else if source.looping && frames_available > 0 {
-                                        source.cursor = 0;
-                                    } else {
-                                        source.cursor = frames_available;
+
+                                    source.cursor_frac += pitch;
+                                    while source.cursor_frac >= 1.0 {
+                                        source.cursor_frac -= 1.0;
+                                        if source.cursor + 1 < frames_available {
+                                            source.cursor += 1;
+                                        } else if source.looping && frames_available > 0 {
+                                            source.cursor = 0;
+                                        } else {
+                                            source.cursor = frames_available;
+                                            source.cursor_frac = 0.0;
+                                            break;
+                                        }
                                     }
                                 } else {
                                     source.playing = false;
@@ -332,14 +846,410 @@ impl AudioCallback for AudioMixer {
                                 }
                             }
                         }
+                        AudioData::Stream(stream) => {
+                            stream
+                                .shared
+                                .looping
+                                .store(source.looping, Ordering::SeqCst);
+                            let shared = Arc::clone(&stream.shared);
+                            let mut buffer =
+                                shared.buffer.lock().unwrap_or_else(|e| e.into_inner());
+                            stream.refill(&mut buffer);
+
+                            for frame in out.chunks_mut(2) {
+                                source.current_left_gain += left_step;
+                                source.current_right_gain += right_step;
+
+                                if stream.window_len == 0 {
+                                    if shared.finished.load(Ordering::SeqCst) && buffer.is_empty() {
+                                        source.playing = false;
+                                        break;
+                                    }
+                                    // The decoder fell behind; play silence
+                                    // rather than skipping ahead.
+                                    continue;
+                                }
+
+                                let (l0, r0) = stream.window[0];
+                                let (l1, r1) = if stream.window_len > 1 {
+                                    stream.window[1]
+                                } else {
+                                    stream.window[0]
+                                };
+                                let t = source.cursor_frac;
+                                let envelope = source.next_envelope_gain();
+                                let l = (l0 + (l1 - l0) * t) * envelope;
+                                let r = (r0 + (r1 - r0) * t) * envelope;
+                                let (l, r) = source.lowpass(lowpass, l, r);
+
+                                if source.positional {
+                                    let sample = 0.5 * (l + r);
+                                    frame[0] += sample * source.current_left_gain;
+                                    frame[1] += sample * source.current_right_gain;
+                                } else {
+                                    frame[0] += l * source.current_left_gain;
+                                    frame[1] += r * source.current_right_gain;
+                                }
+
+                                source.cursor_frac += pitch;
+                                while source.cursor_frac >= 1.0 {
+                                    source.cursor_frac -= 1.0;
+                                    source.cursor += 1;
+                                    stream.advance(&mut buffer);
+                                }
+                            }
+                        }
                     }
 
                     source.data = data;
 
                     source.current_left_gain = target_left;
                     source.current_right_gain = target_right;
+
+                    // A completed fade-out pauses the source and restores the
+                    // envelope so a later `play()` is audible again.
+                    if source.envelope_target == 0.0 && source.envelope_gain == 0.0 {
+                        source.playing = false;
+                        source.envelope_gain = 1.0;
+                        source.envelope_target = 1.0;
+                    }
                 }
             }
         });
+
+        let frames = out.len() / 2;
+        let master_step = if frames > 0 {
+            (master_gain - self.current_master_gain) / frames as f32
+        } else {
+            0.0
+        };
+        for frame in out.chunks_mut(2) {
+            self.current_master_gain += master_step;
+            for sample in frame.iter_mut() {
+                *sample *= self.current_master_gain;
+            }
+        }
+        self.current_master_gain = master_gain;
+    }
+}
+
+/// Per-frame envelope increment covering `distance` over `seconds`.
+fn envelope_step_for(distance: f32, seconds: f32) -> f32 {
+    if seconds <= 0.0 {
+        f32::MAX
+    } else {
+        distance / (seconds * 44100.0)
+    }
+}
+
+/// Converts interleaved frames to the mixer's 44.1kHz output rate.
+fn resample_to_44100(frames: Vec<f32>, ch: usize, sample_rate: u32) -> PyResult<Vec<f32>> {
+    if sample_rate == 44100 {
+        return Ok(frames);
+    }
+
+    let source_rate = SampleRate::try_from(sample_rate).map_err(|_| {
+        pyo3::exceptions::PyRuntimeError::new_err(format!(
+            "Unsupported sample rate: {}",
+            sample_rate
+        ))
+    })?;
+    let target_rate = SampleRate::Hz44100;
+
+    let mut resampler = ResamplerFft::new(ch, source_rate, target_rate);
+    let chunk_input = resampler.chunk_size_input();
+    let chunk_output = resampler.chunk_size_output();
+
+    let to_output_frames =
+        |input_frames: usize| (input_frames as u64 * 44100 / sample_rate as u64) as usize;
+    let expected_len = to_output_frames(frames.len() / ch) * ch;
+    // The resampler lags its input by `delay()` frames: feed that much
+    // silence after the clip to flush its tail, then drop the lag from the
+    // front of the output.
+    let delay = resampler.delay();
+    let skip = to_output_frames(delay) * ch;
+
+    let mut input = frames;
+    input.resize(input.len() + delay * ch, 0.0);
+
+    let mut output = Vec::with_capacity(expected_len + skip + chunk_output);
+    let mut block_output = vec![0.0f32; chunk_output];
+    let mut last_block = Vec::new();
+    for block in input.chunks(chunk_input) {
+        let block = if block.len() < chunk_input {
+            last_block.extend_from_slice(block);
+            last_block.resize(chunk_input, 0.0);
+            &last_block
+        } else {
+            block
+        };
+        resampler.resample(block, &mut block_output).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Unexpected error: {}", e))
+        })?;
+        output.extend_from_slice(&block_output);
+    }
+
+    output.drain(..skip.min(output.len()));
+    output.truncate(expected_len);
+    Ok(output)
+}
+
+/// Reads one clip frame as a (left, right) pair, duplicating mono samples.
+fn read_clip_frame(samples: &[f32], channels: usize, frame: usize) -> (f32, f32) {
+    if channels == 1 {
+        (samples[frame], samples[frame])
+    } else {
+        (samples[frame * 2], samples[frame * 2 + 1])
+    }
+}
+
+/// A file being decoded incrementally for an `AudioStream`.
+enum StreamDecoder {
+    Wav(hound::WavReader<std::io::BufReader<std::fs::File>>),
+    Ogg(Box<lewton::inside_ogg::OggStreamReader<std::fs::File>>),
+}
+
+impl StreamDecoder {
+    fn open(path: &Path) -> Result<Self, String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let decoder = match extension.as_deref() {
+            Some("wav") => {
+                let reader = hound::WavReader::open(path)
+                    .map_err(|e| format!("Failed to open WAV file: {}", e))?;
+                let spec = reader.spec();
+                if spec.bits_per_sample != 16 || !(spec.channels == 1 || spec.channels == 2) {
+                    return Err("Unsupported WAV format: only 16-bit mono/stereo supported".into());
+                }
+                StreamDecoder::Wav(reader)
+            }
+            Some("ogg") => {
+                let file = std::fs::File::open(path)
+                    .map_err(|e| format!("Failed to open OGG file: {}", e))?;
+                let reader = lewton::inside_ogg::OggStreamReader::new(file)
+                    .map_err(|e| format!("Failed to open OGG file: {}", e))?;
+                let ch = reader.ident_hdr.audio_channels;
+                if !(ch == 1 || ch == 2) {
+                    return Err("Unsupported OGG format: only mono/stereo supported".into());
+                }
+                StreamDecoder::Ogg(Box::new(reader))
+            }
+            _ => {
+                return Err(format!(
+                    "Unsupported audio file (expected .wav or .ogg): {}",
+                    path.display()
+                ));
+            }
+        };
+        SampleRate::try_from(decoder.sample_rate())
+            .map_err(|_| format!("Unsupported sample rate: {}", decoder.sample_rate()))?;
+        Ok(decoder)
+    }
+
+    fn channels(&self) -> usize {
+        match self {
+            StreamDecoder::Wav(reader) => reader.spec().channels as usize,
+            StreamDecoder::Ogg(reader) => reader.ident_hdr.audio_channels as usize,
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        match self {
+            StreamDecoder::Wav(reader) => reader.spec().sample_rate,
+            StreamDecoder::Ogg(reader) => reader.ident_hdr.audio_sample_rate,
+        }
+    }
+
+    /// Length in seconds; OGG headers don't record it.
+    fn duration(&self) -> Option<f32> {
+        match self {
+            StreamDecoder::Wav(reader) => {
+                Some(reader.duration() as f32 / reader.spec().sample_rate as f32)
+            }
+            StreamDecoder::Ogg(_) => None,
+        }
+    }
+
+    /// Appends the next block of interleaved samples to `out`. Returns false
+    /// at the end of the file.
+    fn read_block(&mut self, out: &mut Vec<f32>) -> Result<bool, String> {
+        match self {
+            StreamDecoder::Wav(reader) => {
+                let ch = reader.spec().channels as usize;
+                let start = out.len();
+                for sample in reader.samples::<i16>().take(STREAM_READ_FRAMES * ch) {
+                    let sample = sample.map_err(|e| format!("Failed to decode WAV file: {}", e))?;
+                    out.push(sample as f32 / i16::MAX as f32);
+                }
+                Ok(out.len() > start)
+            }
+            StreamDecoder::Ogg(reader) => {
+                match reader
+                    .read_dec_packet_itl()
+                    .map_err(|e| format!("Failed to decode OGG file: {}", e))?
+                {
+                    Some(packet) => {
+                        out.extend(packet.into_iter().map(|s| s as f32 / i16::MAX as f32));
+                        Ok(true)
+                    }
+                    None => Ok(false),
+                }
+            }
+        }
+    }
+}
+
+/// Chunked conversion to 44.1kHz for streamed audio. Carries resampler state
+/// across calls, and across loop points so looping streams stay seamless.
+struct StreamResampler {
+    resampler: Option<ResamplerFft>,
+    channels: usize,
+    sample_rate: u32,
+    pending: Vec<f32>,
+    /// Output samples still to drop to compensate for the resampler delay.
+    skip: usize,
+    block: Vec<f32>,
+}
+
+impl StreamResampler {
+    fn new(channels: usize, sample_rate: u32) -> Self {
+        let resampler = SampleRate::try_from(sample_rate)
+            .ok()
+            .filter(|_| sample_rate != 44100)
+            .map(|rate| ResamplerFft::new(channels, rate, SampleRate::Hz44100));
+        let skip = resampler
+            .as_ref()
+            .map(|r| (r.delay() as u64 * 44100 / sample_rate as u64) as usize * channels)
+            .unwrap_or(0);
+        let block = vec![0.0; resampler.as_ref().map_or(0, |r| r.chunk_size_output())];
+        StreamResampler {
+            resampler,
+            channels,
+            sample_rate,
+            pending: Vec::new(),
+            skip,
+            block,
+        }
+    }
+
+    /// Converts as much of `input` as fills whole resampler chunks into `out`.
+    fn process(&mut self, input: &[f32], out: &mut Vec<f32>) -> Result<(), String> {
+        let Some(resampler) = &mut self.resampler else {
+            out.extend_from_slice(input);
+            return Ok(());
+        };
+        self.pending.extend_from_slice(input);
+        let chunk = resampler.chunk_size_input();
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= chunk {
+            resampler
+                .resample(&self.pending[consumed..consumed + chunk], &mut self.block)
+                .map_err(|e| format!("Unexpected error: {}", e))?;
+            let skipped = self.skip.min(self.block.len());
+            self.skip -= skipped;
+            out.extend_from_slice(&self.block[skipped..]);
+            consumed += chunk;
+        }
+        self.pending.drain(..consumed);
+        Ok(())
+    }
+
+    /// Pushes the resampler's buffered tail out at the end of the file.
+    fn flush(&mut self, out: &mut Vec<f32>) -> Result<(), String> {
+        let Some(resampler) = &self.resampler else {
+            return Ok(());
+        };
+        let delay = resampler.delay();
+        let padding = vec![0.0; resampler.chunk_size_input() + delay * self.channels];
+        let frames = self.pending.len() / self.channels + delay;
+        let expected = ((frames as u64 * 44100 / self.sample_rate as u64) as usize * self.channels)
+            .saturating_sub(self.skip);
+
+        let start = out.len();
+        self.process(&padding, out)?;
+        out.truncate(start + expected);
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// Body of a stream's decoder thread: keeps `shared.buffer` topped up until
+/// the stream is dropped.
+fn run_stream_decoder(path: PathBuf, mut decoder: StreamDecoder, shared: Arc<StreamShared>) {
+    let channels = decoder.channels();
+    let mut resampler = StreamResampler::new(channels, decoder.sample_rate());
+    let mut decoded = Vec::new();
+    let mut converted = Vec::new();
+    // Samples to throw away after a seek before queueing resumes.
+    let mut discard = 0usize;
+    // Guards against spinning on a looping file with no samples in it.
+    let mut decoded_since_rewind = false;
+    // Seek generation the decoder is currently producing samples for.
+    let mut generation = shared.generation.load(Ordering::SeqCst);
+
+    while !shared.stop.load(Ordering::SeqCst) {
+        let seek = shared.seek.lock().ok().and_then(|mut seek| seek.take());
+        if let Some((frame, seek_generation)) = seek {
+            generation = seek_generation;
+            match StreamDecoder::open(&path) {
+                Ok(reopened) => decoder = reopened,
+                Err(_) => {
+                    shared.finished.store(true, Ordering::SeqCst);
+                    continue;
+                }
+            }
+            resampler = StreamResampler::new(channels, decoder.sample_rate());
+            discard = frame * channels;
+            decoded_since_rewind = false;
+            if let Ok(mut buffer) = shared.buffer.lock() {
+                buffer.clear();
+            }
+            shared.finished.store(false, Ordering::SeqCst);
+        }
+
+        let full = shared
+            .buffer
+            .lock()
+            .map(|buffer| buffer.len() >= STREAM_BUFFER_FRAMES * channels)
+            .unwrap_or(true);
+        if full || shared.finished.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+
+        decoded.clear();
+        let mut finished = false;
+        let result = match decoder.read_block(&mut decoded) {
+            Ok(true) => {
+                decoded_since_rewind = true;
+                resampler.process(&decoded, &mut converted)
+            }
+            Ok(false) if shared.looping.load(Ordering::SeqCst) && decoded_since_rewind => {
+                decoded_since_rewind = false;
+                StreamDecoder::open(&path).map(|reopened| decoder = reopened)
+            }
+            Ok(false) => {
+                finished = true;
+                resampler.flush(&mut converted)
+            }
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            finished = true;
+        }
+
+        let dropped = discard.min(converted.len());
+        discard -= dropped;
+        if let Ok(mut buffer) = shared.buffer.lock()
+            && shared.generation.load(Ordering::SeqCst) == generation
+        {
+            buffer.extend(converted.drain(dropped..));
+            if finished {
+                shared.finished.store(true, Ordering::SeqCst);
+            }
+        }
+        converted.clear();
     }
 }
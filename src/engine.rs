@@ -1,18 +1,45 @@
-use glam::{Mat4, Quat, Vec3};
+use glam::{Mat4, Quat, Vec2, Vec3, Vec4};
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
+use rapier3d::parry::utils::Array2;
+use rapier3d::prelude::{
+    ColliderBuilder, ColliderHandle, FixedJointBuilder, GenericJoint, Pose, PrismaticJointBuilder,
+    RevoluteJointBuilder, RigidBody, RigidBodyHandle, RigidBodyType, SharedShape,
+};
 use sdl2::Sdl;
+use sdl2::keyboard::{Keycode, Scancode};
 use sdl2::video::GLContext;
 use sdl2::video::Window;
 
 use crate::input::InputState;
-use crate::physics::PhysicsWorld;
-use crate::renderer::Renderer;
+use crate::physics::{self, PhysicsWorld};
+use crate::renderer::animation;
+use crate::renderer::cascades;
+use crate::renderer::frustum::Frustum;
+use crate::renderer::material::MaterialParams;
+use crate::renderer::mesh::{self, MeshDrawInfo};
+use crate::renderer::shader::Shader;
+use crate::renderer::skybox::Skybox;
+use crate::renderer::sprite::{Sprite, SpriteDrawInfo};
+use crate::renderer::texture::Texture;
+use crate::renderer::{self, Renderer, ViewportRect};
 use crate::scene::LightType;
-use crate::scene::{Camera, Entity, Light, Scene};
+use crate::scene::{Camera, Entity, Light, ProjectionMode, Scene};
 
-use crate::audio::{AudioMixer, AudioSource, ListenerState};
+use crate::audio::{AudioGroup, AudioMixer, AudioSource, ListenerState};
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Entity hit by `Engine::shape_cast` and the shape's center at contact.
+type ShapeHit = (Py<Entity>, (f32, f32, f32));
+
+/// Number of recent frames averaged by `Engine::fps`.
+const FPS_SAMPLE_FRAMES: usize = 60;
+
+/// How far an entity's pose may drift from its body's before `run_physics`
+/// treats it as moved outside physics.
+const POSE_EPSILON: f32 = 1e-5;
 
 #[pyclass]
 pub struct SdlEvent {
@@ -29,20 +56,58 @@ pub struct SdlEvent {
 #[pyclass(unsendable)]
 pub struct Engine {
     sdl_context: Sdl,
-    _video_subsystem: sdl2::VideoSubsystem,
+    video_subsystem: sdl2::VideoSubsystem,
     _audio_subsystem: sdl2::AudioSubsystem,
+    controller_subsystem: sdl2::GameControllerSubsystem,
+    controllers: Vec<sdl2::controller::GameController>,
     _audio_device: sdl2::audio::AudioDevice<AudioMixer>,
     window: Option<Window>,
     _gl_context: GLContext,
+    /// Set for engines made with `new_headless`: the window stays hidden and
+    /// frames go to an offscreen buffer read with `read_pixels`.
+    headless: bool,
 
     renderer: Renderer,
     scene: Scene,
     camera: Py<Camera>,
+    skybox: Option<Py<Skybox>>,
+    /// Box (min, max) that `move_camera` and `move_camera_local` keep the
+    /// camera position inside.
+    camera_bounds: Option<(Vec3, Vec3)>,
+    /// Last frame's view-projection, which the geometry pass compares
+    /// against to fill the velocity buffer.
+    previous_view_projection: Option<Mat4>,
+    /// Draws a sphere at each point light whose `show_gizmo` is set.
+    light_gizmos: bool,
+    /// Entities drawn with a flat-colored rim, e.g. to mark a selection.
+    outlines: Vec<Outline>,
+    /// Sprites drawn every frame, in insertion order.
+    sprites: Vec<Py<Sprite>>,
+    /// Sprites queued with `draw_sprite`, drawn after `sprites` and then cleared.
+    queued_sprites: Vec<Py<Sprite>>,
+    /// Extra cameras queued with `render_camera` for the next frame, with
+    /// their (x, y, width, height) window rectangles.
+    queued_views: Vec<(Py<Camera>, ViewportRect)>,
     physics_world: PhysicsWorld,
+    /// Whether `update` advances physics itself; off when Python drives it
+    /// through `step_physics`.
+    auto_physics: bool,
+    /// Entities simulated by `physics_world`, paired with their body.
+    rigid_bodies: Vec<(Py<Entity>, RigidBodyHandle)>,
     input: InputState,
 
     should_quit: bool,
 
+    /// When the engine was constructed; drives time-based effects.
+    created: Instant,
+    /// Number of `update` calls so far.
+    frame_count: u64,
+    last_frame: Instant,
+    delta_time: f32,
+    /// Multiplies the time physics and animation advance by each frame.
+    time_scale: f32,
+    frame_times: VecDeque<f32>,
+
     audio_sources: Arc<Mutex<Vec<Py<AudioSource>>>>,
     pending_events: Arc<Mutex<Vec<Py<SdlEvent>>>>,
     listener_state: Arc<Mutex<ListenerState>>,
@@ -50,114 +115,203 @@ pub struct Engine {
 
 #[pymethods]
 impl Engine {
+    /// A non-zero `msaa_samples` trades the deferred pipeline for forward
+    /// rendering into a multisampled buffer: edges are antialiased, but
+    /// shadows, custom entity shaders, reflections, motion blur and depth
+    /// of field are unavailable.
     #[new]
-    pub fn new(title: &str, width: u32, height: u32) -> PyResult<Self> {
-        let sdl_context =
-            sdl2::init().map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
-        let video_subsystem = sdl_context
-            .video()
-            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
-        let audio_subsystem = sdl_context
-            .audio()
-            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+    #[pyo3(signature = (title, width, height, msaa_samples=0))]
+    pub fn new(title: &str, width: u32, height: u32, msaa_samples: u32) -> PyResult<Self> {
+        Engine::create(title, width, height, false, msaa_samples)
+    }
 
-        let gl_attr = video_subsystem.gl_attr();
-        gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
-        gl_attr.set_context_version(3, 3);
+    /// Creates an engine that renders offscreen, for tests and servers
+    /// without a display. Audio goes to SDL's dummy driver.
+    #[staticmethod]
+    #[pyo3(signature = (width, height, msaa_samples=0))]
+    pub fn new_headless(width: u32, height: u32, msaa_samples: u32) -> PyResult<Self> {
+        Engine::create("pie", width, height, true, msaa_samples)
+    }
 
-        let window = video_subsystem
-            .window(title, width, height)
-            .opengl()
-            .resizable()
-            .position_centered()
-            .build()
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    /// Whether the engine was created with `msaa_samples` and renders
+    /// through the forward path.
+    pub fn is_forward_rendering(&self) -> bool {
+        self.renderer.forward_rendering()
+    }
 
-        let _gl_context = window
-            .gl_create_context()
-            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+    #[getter]
+    pub fn get_camera(&mut self, py: Python) -> Py<Camera> {
+        self.camera.clone_ref(py)
+    }
 
-        gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as *const _);
+    #[setter]
+    pub fn set_camera(&mut self, camera: Py<Camera>) {
+        self.camera = camera;
+    }
 
-        unsafe {
-            gl::Enable(gl::DEPTH_TEST);
-        }
+    pub fn set_skybox(&mut self, skybox: Option<Py<Skybox>>) {
+        self.skybox = skybox;
+    }
 
-        let renderer = Renderer::new(width, height)
-            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
-        let scene = Scene::new();
-        let camera = Python::attach(|py| {
-            Py::new(py, Camera::new(0.0, 0.0, 0.0))
-                .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
-                .unwrap()
+    /// Shows or hides the debug spheres drawn at point lights. On by default.
+    pub fn set_light_gizmos(&mut self, enabled: bool) {
+        self.light_gizmos = enabled;
+    }
+
+    /// Draws a `color` rim about `width` world units wide around `entity`,
+    /// replacing any outline it already has.
+    #[pyo3(signature = (entity, color=(1.0, 0.6, 0.1), width=0.03))]
+    pub fn set_outline(
+        &mut self,
+        entity: &Bound<'_, Entity>,
+        color: (f32, f32, f32),
+        width: f32,
+    ) -> PyResult<()> {
+        if !width.is_finite() || width <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "outline width must be a positive number, got {}",
+                width
+            )));
+        }
+        self.clear_outline(entity);
+        self.outlines.push(Outline {
+            entity: entity.clone().unbind(),
+            color: Vec3::from(color),
+            width,
         });
-        let physics_world = PhysicsWorld::new();
-        let input_state = InputState::new();
+        Ok(())
+    }
 
-        // Audio Setup
-        let desired_spec = sdl2::audio::AudioSpecDesired {
-            freq: Some(44100),
-            channels: Some(2),
-            samples: None,
+    /// Returns whether the entity had an outline.
+    pub fn clear_outline(&mut self, entity: &Bound<'_, Entity>) -> bool {
+        let before = self.outlines.len();
+        self.outlines
+            .retain(|outline| outline.entity.as_ptr() != entity.as_ptr());
+        self.outlines.len() != before
+    }
+
+    pub fn add_entity(&mut self, entity: Py<Entity>) {
+        self.scene.add_entity(entity);
+    }
+
+    /// Moves the camera back along its view direction until `entity`'s mesh
+    /// fills the view. Orthographic cameras also resize to fit.
+    pub fn frame_entity(&mut self, py: Python, entity: &Bound<'_, Entity>) -> PyResult<()> {
+        let entity = entity.borrow();
+        let mesh = entity
+            .mesh
+            .as_ref()
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Entity has no mesh"))?;
+        let (center, radius) = mesh
+            .borrow(py)
+            .world_bounding_sphere(&entity.get_world_matrix(py));
+        let radius = radius.max(Camera::NEAR_PLANE);
+
+        let aspect = self.renderer.width as f32 / self.renderer.height as f32;
+        let mut camera = self.camera.borrow_mut(py);
+        let distance = match camera.projection_mode {
+            ProjectionMode::Perspective => {
+                let half_vertical = camera.fov.to_radians() / 2.0;
+                let half_horizontal = (half_vertical.tan() * aspect).atan();
+                radius / half_vertical.min(half_horizontal).sin()
+            }
+            ProjectionMode::Orthographic => {
+                camera.ortho_size = radius * (1.0 / aspect).max(1.0);
+                radius * 2.0
+            }
         };
+        camera.position = center - camera.front * distance;
+        Ok(())
+    }
 
-        let audio_sources = Arc::new(Mutex::new(Vec::new()));
-        let initial_sources = audio_sources.clone();
+    pub fn add_light(&mut self, light: Py<Light>) {
+        self.scene.add_light(light);
+    }
 
-        let listener_state = Arc::new(Mutex::new(ListenerState {
-            position: Vec3::ZERO,
-            right: Vec3::X,
-        }));
-        let listener_state_clone = listener_state.clone();
+    pub fn remove_entity(&mut self, entity: &Bound<'_, Entity>) -> bool {
+        self.remove_rigid_body(entity);
+        self.clear_outline(entity);
+        self.scene.remove_entity(entity)
+    }
 
-        let pending_events = Arc::new(Mutex::new(Vec::new()));
-        let pending_events_clone = pending_events.clone();
+    pub fn remove_light(&mut self, py: Python, light: &Bound<'_, Light>) -> PyResult<bool> {
+        let is_point = light.borrow().light_type == LightType::Point;
+        if !self.scene.remove_light(light) {
+            return Ok(false);
+        }
 
-        let device = audio_subsystem
-            .open_playback(None, &desired_spec, |_spec| {
-                AudioMixer::new(initial_sources.clone(), listener_state_clone.clone())
-            })
-            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        if is_point {
+            self.sync_point_light_count(py)?;
+        }
+        Ok(true)
+    }
 
-        device.resume();
+    /// Adds a sprite to the HUD overlay until it is removed.
+    pub fn add_sprite(&mut self, sprite: Py<Sprite>) {
+        self.sprites.push(sprite);
+    }
 
-        let engine = Engine {
-            sdl_context,
-            _video_subsystem: video_subsystem,
-            _audio_subsystem: audio_subsystem,
-            _audio_device: device,
-            window: Some(window),
-            _gl_context,
-            renderer,
-            scene,
-            camera,
-            physics_world,
-            input: input_state,
-            should_quit: false,
-            audio_sources,
-            pending_events: pending_events_clone,
-            listener_state,
-        };
+    pub fn remove_sprite(&mut self, sprite: &Bound<'_, Sprite>) -> bool {
+        let before = self.sprites.len();
+        self.sprites.retain(|s| s.as_ptr() != sprite.as_ptr());
+        self.sprites.len() != before
+    }
 
-        Ok(engine)
+    /// Draws a sprite on the next frame only, above the added sprites.
+    pub fn draw_sprite(&mut self, sprite: Py<Sprite>) {
+        self.queued_sprites.push(sprite);
     }
 
-    #[getter]
-    pub fn get_camera(&mut self, py: Python) -> Py<Camera> {
-        self.camera.clone_ref(py)
+    /// Renders the scene from `camera` into a window rectangle on the next
+    /// frame only, over the main camera's view and under the sprites. The
+    /// rectangle is in pixels from the top-left, like sprites, so this
+    /// serves both split-screen and picture-in-picture views.
+    pub fn render_camera(
+        &mut self,
+        camera: Py<Camera>,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    ) -> PyResult<()> {
+        if width == 0 || height == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "camera viewport must have a positive size, got {}x{}",
+                width, height
+            )));
+        }
+        self.queued_views.push((camera, (x, y, width, height)));
+        Ok(())
     }
 
-    #[setter]
-    pub fn set_camera(&mut self, camera: Py<Camera>) {
-        self.camera = camera;
+    pub fn clear_scene(&mut self, py: Python) -> PyResult<()> {
+        self.clear_rigid_bodies();
+        self.outlines.clear();
+        self.scene.clear();
+        self.sync_point_light_count(py)
     }
 
-    pub fn add_entity(&mut self, entity: Py<Entity>) {
-        self.scene.add_entity(entity);
+    pub fn find_by_name(&self, py: Python, name: &str) -> Option<Py<Entity>> {
+        self.scene.find_by_name(py, name)
     }
 
-    pub fn add_light(&mut self, light: Py<Light>) {
-        self.scene.add_light(light);
+    pub fn find_by_tag(&self, py: Python, tag: &str) -> Vec<Py<Entity>> {
+        self.scene.find_by_tag(py, tag)
+    }
+
+    /// Saves the scene's entities and lights, plus the camera, to a JSON file.
+    pub fn save_scene(&self, py: Python, path: &str) -> PyResult<()> {
+        self.scene.save(py, path, &self.camera.borrow(py))
+    }
+
+    /// Replaces the scene and camera state with those saved in a JSON file.
+    pub fn load_scene(&mut self, py: Python, path: &str) -> PyResult<()> {
+        let (scene, camera) = Scene::load(py, path)?;
+        self.clear_rigid_bodies();
+        self.outlines.clear();
+        self.scene = scene;
+        *self.camera.borrow_mut(py) = camera;
+        self.sync_point_light_count(py)
     }
 
     pub fn add_audio_source(&mut self, source: Py<AudioSource>) {
@@ -165,31 +319,480 @@ impl Engine {
         sources.push(source);
     }
 
-    pub fn configure_point_lights(&mut self, num_point_lights: usize) {
-        self.renderer.configure_point_lights(num_point_lights);
+    pub fn set_master_volume(&mut self, volume: f32) {
+        if let Ok(mut state) = self.listener_state.lock() {
+            state.master_volume = volume.max(0.0);
+        }
     }
 
-    pub fn is_key_down(&self, key_name: &str) -> bool {
-        self.input.is_key_down(key_name)
+    /// Sets the volume of a mixer group ("sfx", "music", "voice" or
+    /// "ambient"), applied before the master volume.
+    pub fn set_group_volume(&mut self, group: &str, volume: f32) -> PyResult<()> {
+        let group = AudioGroup::from_name(group)?;
+        if let Ok(mut state) = self.listener_state.lock() {
+            state.group_volumes[group as usize] = volume.max(0.0);
+        }
+        Ok(())
     }
 
-    pub fn is_mouse_down(&self, button: &str) -> bool {
-        match button {
-            "Left" => self.input.is_mouse_down(sdl2::mouse::MouseButton::Left),
-            "Right" => self.input.is_mouse_down(sdl2::mouse::MouseButton::Right),
-            "Middle" => self.input.is_mouse_down(sdl2::mouse::MouseButton::Middle),
-            _ => false,
+    pub fn group_volume(&self, group: &str) -> PyResult<f32> {
+        let group = AudioGroup::from_name(group)?;
+        Ok(self
+            .listener_state
+            .lock()
+            .map(|state| state.group_volumes[group as usize])
+            .unwrap_or(1.0))
+    }
+
+    /// Scales the Doppler pitch shift of moving positional sources; 1.0 is
+    /// physically based, 0.0 turns it off.
+    pub fn set_doppler_factor(&mut self, factor: f32) {
+        if let Ok(mut state) = self.listener_state.lock() {
+            state.doppler_factor = factor.max(0.0);
+        }
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        if let Ok(mut state) = self.listener_state.lock() {
+            state.muted = muted;
         }
     }
 
+    /// Sets how many point lights get shadow maps. Fails if the lighting
+    /// shader can't sample that many.
+    pub fn configure_point_lights(&mut self, num_point_lights: usize) -> PyResult<()> {
+        let max = self.renderer.max_point_shadows();
+        if num_point_lights > max {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "At most {} point lights can cast shadows, got {}",
+                max, num_point_lights
+            )));
+        }
+        self.renderer
+            .configure_point_lights(num_point_lights)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    /// Loads built-in shaders from `path` on the next `reload_shaders`, or
+    /// from the compiled-in sources when `None`.
+    pub fn set_shader_directory(&mut self, path: Option<String>) {
+        self.renderer.shader_dir = path;
+    }
+
+    pub fn reload_shaders(&mut self) -> PyResult<()> {
+        self.renderer
+            .reload_shaders()
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    pub fn set_vsync(&mut self, mode: &str) -> PyResult<()> {
+        use sdl2::video::SwapInterval;
+
+        let interval = match mode {
+            "on" => SwapInterval::VSync,
+            "off" => SwapInterval::Immediate,
+            "adaptive" => SwapInterval::LateSwapTearing,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown vsync mode: {}",
+                    mode
+                )));
+            }
+        };
+
+        let result = self.video_subsystem.gl_set_swap_interval(interval);
+        // Adaptive sync is optional in SDL; drivers without it get an uncapped swap.
+        let result = if result.is_err() && matches!(interval, SwapInterval::LateSwapTearing) {
+            self.video_subsystem
+                .gl_set_swap_interval(SwapInterval::Immediate)
+        } else {
+            result
+        };
+        result.map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    /// The most point lights `configure_point_lights` accepts.
+    pub fn max_point_shadow_lights(&self) -> usize {
+        self.renderer.max_point_shadows()
+    }
+
+    pub fn set_shadow_resolution(&mut self, directional: u32, point: u32) -> PyResult<()> {
+        self.renderer
+            .set_shadow_resolution(directional, point)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    /// Splits the directional shadow into `count` cascades over the first
+    /// `distance` units of view. `split_lambda` blends uniform (0) and
+    /// logarithmic (1) spacing.
+    #[pyo3(signature = (count, split_lambda=0.75, distance=100.0))]
+    pub fn set_shadow_cascades(
+        &mut self,
+        count: usize,
+        split_lambda: f32,
+        distance: f32,
+    ) -> PyResult<()> {
+        if count == 0 || count > cascades::MAX_CASCADES {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Cascade count must be between 1 and {}",
+                cascades::MAX_CASCADES
+            )));
+        }
+        self.renderer
+            .set_shadow_cascades(count, split_lambda, distance)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    /// Sets the shadow filter kernel width in texels. Even values round up
+    /// to the next odd width and 1 gives hard, unfiltered edges.
+    pub fn set_shadow_softness(&mut self, samples: u32) -> PyResult<()> {
+        if samples == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Shadow softness must be at least 1",
+            ));
+        }
+        self.renderer.shadow_softness = samples.min(15) as i32;
+        Ok(())
+    }
+
+    /// Sets the background color shown where nothing was drawn.
+    pub fn set_clear_color(&mut self, r: f32, g: f32, b: f32) {
+        self.renderer.clear_color = Vec3::new(r, g, b);
+    }
+
+    /// Sets the constant ambient light, multiplied by each surface's albedo.
+    pub fn set_ambient_light(&mut self, r: f32, g: f32, b: f32) {
+        self.renderer.ambient_light = Vec3::new(r, g, b);
+    }
+
+    pub fn set_polygon_mode(&mut self, mode: &str) -> PyResult<()> {
+        self.renderer.polygon_mode = match mode {
+            "fill" => gl::FILL,
+            "line" => gl::LINE,
+            "point" => gl::POINT,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown polygon mode: {}",
+                    mode
+                )));
+            }
+        };
+        Ok(())
+    }
+
+    /// Sets which faces the geometry pass discards: "back", "front" or
+    /// "none" (the default) to draw both sides.
+    pub fn set_cull_mode(&mut self, mode: &str) -> PyResult<()> {
+        self.renderer.cull_mode = match mode {
+            "back" => Some(gl::BACK),
+            "front" => Some(gl::FRONT),
+            "none" => None,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown cull mode: {}",
+                    mode
+                )));
+            }
+        };
+        Ok(())
+    }
+
+    /// Chooses whether counter-clockwise (the default) or clockwise winding
+    /// is front-facing, to fix models exported with flipped winding.
+    pub fn set_front_face(&mut self, ccw: bool) {
+        self.renderer.front_face = if ccw { gl::CCW } else { gl::CW };
+    }
+
+    pub fn set_fxaa_enabled(&mut self, enabled: bool) {
+        self.renderer.fxaa_enabled = enabled;
+    }
+
+    pub fn set_tone_mapping(&mut self, mode: &str) -> PyResult<()> {
+        self.renderer.tone_mapping_mode = match mode {
+            "reinhard" => 0,
+            "aces" => 1,
+            "uncharted2" => 2,
+            "none" => 3,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown tone mapping mode: {}",
+                    mode
+                )));
+            }
+        };
+        Ok(())
+    }
+
+    /// Mode is "none", "linear" (fades between `start` and `end`), "exp" or
+    /// "exp2" (both driven by `density`).
+    #[pyo3(signature = (color, density, mode, start = 10.0, end = 100.0))]
+    pub fn set_fog(
+        &mut self,
+        color: (f32, f32, f32),
+        density: f32,
+        mode: &str,
+        start: f32,
+        end: f32,
+    ) -> PyResult<()> {
+        self.renderer.fog_mode = match mode {
+            "none" => 0,
+            "linear" => 1,
+            "exp" => 2,
+            "exp2" => 3,
+            _ => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown fog mode: {}",
+                    mode
+                )));
+            }
+        };
+        self.renderer.fog_color = Vec3::new(color.0, color.1, color.2);
+        self.renderer.fog_density = density.max(0.0);
+        self.renderer.fog_start = start;
+        self.renderer.fog_end = end;
+        Ok(())
+    }
+
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.renderer.exposure = exposure;
+    }
+
+    pub fn set_bloom_enabled(&mut self, enabled: bool) {
+        self.renderer.bloom_enabled = enabled;
+    }
+
+    pub fn set_bloom_threshold(&mut self, threshold: f32) {
+        self.renderer.bloom_threshold = threshold;
+    }
+
+    pub fn set_bloom_intensity(&mut self, intensity: f32) {
+        self.renderer.bloom_intensity = intensity;
+    }
+
+    pub fn set_bloom_radius(&mut self, radius: f32) -> PyResult<()> {
+        if !radius.is_finite() || radius < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "bloom radius must be a non-negative number, got {}",
+                radius
+            )));
+        }
+        self.renderer.bloom_radius = radius;
+        Ok(())
+    }
+
+    pub fn set_bloom_mip_count(&mut self, count: usize) -> PyResult<()> {
+        self.renderer
+            .set_bloom_mip_count(count)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    pub fn bloom_mip_count(&self) -> usize {
+        self.renderer.bloom_mip_count()
+    }
+
+    /// Reflects the visible scene in glossy surfaces by ray-marching the
+    /// G-buffer. `steps` and `max_distance` trade cost against how far and
+    /// how precisely reflections are found.
+    #[pyo3(signature = (enabled, steps=48, max_distance=20.0))]
+    pub fn set_ssr_enabled(
+        &mut self,
+        enabled: bool,
+        steps: u32,
+        max_distance: f32,
+    ) -> PyResult<()> {
+        if steps == 0 || !max_distance.is_finite() || max_distance <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "SSR needs at least one step and a positive distance, got {} and {}",
+                steps, max_distance
+            )));
+        }
+        self.renderer.ssr_enabled = enabled;
+        self.renderer.ssr_steps = steps;
+        self.renderer.ssr_max_distance = max_distance;
+        Ok(())
+    }
+
+    /// Blurs the frame along camera motion. `strength` scales the distance
+    /// moved since the last frame into the blur length; 0 turns it off.
+    pub fn set_motion_blur(&mut self, strength: f32) -> PyResult<()> {
+        if !strength.is_finite() || strength < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "motion blur strength must be a non-negative number, got {}",
+                strength
+            )));
+        }
+        self.renderer.motion_blur_strength = strength;
+        Ok(())
+    }
+
+    /// Keeps surfaces `focus_distance` away from the camera sharp and blurs
+    /// the rest, more strongly with a larger `aperture`.
+    #[pyo3(signature = (focus_distance, aperture, enabled=true))]
+    pub fn set_dof(&mut self, focus_distance: f32, aperture: f32, enabled: bool) -> PyResult<()> {
+        if !focus_distance.is_finite() || focus_distance <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "focus distance must be a positive number, got {}",
+                focus_distance
+            )));
+        }
+        if !aperture.is_finite() || aperture < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "aperture must be a non-negative number, got {}",
+                aperture
+            )));
+        }
+        self.renderer.dof_focus_distance = focus_distance;
+        self.renderer.dof_aperture = aperture;
+        self.renderer.dof_enabled = enabled;
+        Ok(())
+    }
+
+    /// Darkens the frame edges. `radius` is the distance from the screen
+    /// center (0.5 reaches the edge midpoints) where darkening starts, fading
+    /// in over `softness`; `intensity` 0 disables it and 1 fades to black.
+    #[pyo3(signature = (radius=0.75, softness=0.45, intensity=0.5))]
+    pub fn set_vignette(&mut self, radius: f32, softness: f32, intensity: f32) -> PyResult<()> {
+        if !radius.is_finite() || radius < 0.0 || !softness.is_finite() || softness < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "vignette radius and softness must be non-negative, got {} and {}",
+                radius, softness
+            )));
+        }
+        if !intensity.is_finite() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "vignette intensity must be a number, got {}",
+                intensity
+            )));
+        }
+        self.renderer.vignette_radius = radius;
+        self.renderer.vignette_softness = softness;
+        self.renderer.vignette_intensity = intensity.clamp(0.0, 1.0);
+        Ok(())
+    }
+
+    /// Grades the final image through a color lookup table loaded from a
+    /// texture holding an n*n by n strip of slices (e.g. 256x16): red runs
+    /// across each slice, green down it, and blue steps between slices.
+    /// Load the texture without `srgb`. `None` removes grading.
+    pub fn set_color_lut(&mut self, py: Python, texture: Option<Py<Texture>>) -> PyResult<()> {
+        let texture = match &texture {
+            Some(texture) => {
+                let id = texture.borrow(py).id;
+                if id == 0 {
+                    return Err(pyo3::exceptions::PyValueError::new_err(
+                        "Color LUT texture has no image data",
+                    ));
+                }
+                Some(id)
+            }
+            None => None,
+        };
+        self.renderer
+            .set_color_lut(texture)
+            .map_err(pyo3::exceptions::PyValueError::new_err)
+    }
+
+    /// Splits red and blue towards the frame edges by `strength` times the
+    /// distance from the center (around 0.01 is subtle); 0 disables it.
+    pub fn set_chromatic_aberration(&mut self, strength: f32) {
+        self.renderer.chromatic_aberration = strength.max(0.0);
+    }
+
+    /// Adds animated noise of amplitude `strength` (around 0.05 is subtle)
+    /// to the final image; 0 disables it.
+    pub fn set_film_grain(&mut self, strength: f32) {
+        self.renderer.film_grain = strength.max(0.0);
+    }
+
+    /// Key names are SDL key or scancode names ("Space", "Left Shift", "A")
+    /// and are matched case-insensitively. Unknown names raise ValueError.
+    pub fn is_key_down(&self, key_name: &str) -> PyResult<bool> {
+        Ok(self.input.is_key_down(keycode_from_name(key_name)?))
+    }
+
+    pub fn is_key_pressed(&self, key_name: &str) -> PyResult<bool> {
+        Ok(self.input.is_key_pressed(keycode_from_name(key_name)?))
+    }
+
+    pub fn is_key_released(&self, key_name: &str) -> PyResult<bool> {
+        Ok(self.input.is_key_released(keycode_from_name(key_name)?))
+    }
+
+    /// Every key name the current keyboard layout produces, as accepted by
+    /// `is_key_down`.
+    pub fn key_names(&self) -> Vec<String> {
+        let mut seen = std::collections::HashSet::new();
+        SCANCODE_RANGES
+            .into_iter()
+            .flatten()
+            .filter_map(Scancode::from_i32)
+            .filter_map(Keycode::from_scancode)
+            .map(Keycode::name)
+            .filter(|name| !name.is_empty() && seen.insert(name.clone()))
+            .collect()
+    }
+
+    pub fn is_mouse_down(&self, button: &str) -> bool {
+        mouse_button_from_name(button).is_some_and(|b| self.input.is_mouse_down(b))
+    }
+
+    pub fn is_mouse_pressed(&self, button: &str) -> bool {
+        mouse_button_from_name(button).is_some_and(|b| self.input.is_mouse_pressed(b))
+    }
+
+    pub fn is_mouse_released(&self, button: &str) -> bool {
+        mouse_button_from_name(button).is_some_and(|b| self.input.is_mouse_released(b))
+    }
+
+    /// Number of currently connected game controllers.
+    pub fn gamepad_count(&self) -> usize {
+        self.controllers.len()
+    }
+
+    /// Reads an axis ("leftx", "lefty", "rightx", "righty", "lefttrigger",
+    /// "righttrigger") of the `index`-th connected controller.
+    pub fn gamepad_axis(&self, index: usize, axis: &str) -> PyResult<f32> {
+        let axis = sdl2::controller::Axis::from_string(axis).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("Unknown gamepad axis: {}", axis))
+        })?;
+        Ok(self
+            .controllers
+            .get(index)
+            .map(|c| self.input.gamepad_axis(c.instance_id(), axis))
+            .unwrap_or(0.0))
+    }
+
+    /// Reads a button (SDL names such as "a", "b", "start", "dpup") of the
+    /// `index`-th connected controller.
+    pub fn gamepad_button(&self, index: usize, button: &str) -> PyResult<bool> {
+        let button = sdl2::controller::Button::from_string(button).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("Unknown gamepad button: {}", button))
+        })?;
+        Ok(self
+            .controllers
+            .get(index)
+            .is_some_and(|c| self.input.is_gamepad_button_down(c.instance_id(), button)))
+    }
+
     pub fn get_mouse_pos(&self) -> (i32, i32) {
         (self.input.mouse_pos.x as i32, self.input.mouse_pos.y as i32)
     }
 
+    /// Returns the mouse position in normalized device coordinates: `[-1, 1]`
+    /// on both axes with y pointing up.
+    pub fn get_mouse_ndc(&self) -> (f32, f32) {
+        let ndc = self.pixel_to_ndc(self.input.mouse_pos);
+        (ndc.x, ndc.y)
+    }
+
     pub fn get_mouse_rel(&self) -> (i32, i32) {
         (self.input.mouse_rel.x as i32, self.input.mouse_rel.y as i32)
     }
 
+    pub fn get_mouse_scroll(&self) -> (f32, f32) {
+        (self.input.scroll_delta.x, self.input.scroll_delta.y)
+    }
+
     pub fn set_mouse_capture(&mut self, enabled: bool) {
         self.input.mouse_captured = enabled;
         self.sdl_context.mouse().set_relative_mouse_mode(enabled);
@@ -207,7 +810,50 @@ impl Engine {
         self.should_quit = true;
     }
 
+    pub fn set_title(&mut self, title: &str) -> PyResult<()> {
+        if let Some(win) = &mut self.window {
+            win.set_title(title)
+                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    pub fn title(&self) -> String {
+        self.window
+            .as_ref()
+            .map(|win| win.title().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Resizes the window and the render targets to `width`x`height` pixels.
+    pub fn set_size(&mut self, width: u32, height: u32) -> PyResult<()> {
+        if width == 0 || height == 0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "window size must be non-zero, got {}x{}",
+                width, height
+            )));
+        }
+        if let Some(win) = &mut self.window {
+            win.set_size(width, height)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        }
+        self.resize_framebuffers(width, height)
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        (self.renderer.width, self.renderer.height)
+    }
+
     pub fn update(&mut self) -> PyResult<bool> {
+        let now = Instant::now();
+        self.delta_time = now.duration_since(self.last_frame).as_secs_f32();
+        self.last_frame = now;
+        if self.frame_times.len() == FPS_SAMPLE_FRAMES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(self.delta_time);
+        self.frame_count += 1;
+
         self.input.prepare_update();
 
         let mut event_pump = self
@@ -215,224 +861,988 @@ impl Engine {
             .event_pump()
             .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
 
-        for event in event_pump.poll_iter() {
-            self.input.process_event(&event);
+        for event in event_pump.poll_iter() {
+            self.input.process_event(&event);
+
+            // Controllers already connected at startup also arrive as
+            // ControllerDeviceAdded, so this covers both initial and hot-plugged pads.
+            match event {
+                sdl2::event::Event::ControllerDeviceAdded { which, .. } => {
+                    if let Ok(controller) = self.controller_subsystem.open(which)
+                        && !self
+                            .controllers
+                            .iter()
+                            .any(|c| c.instance_id() == controller.instance_id())
+                    {
+                        self.controllers.push(controller);
+                    }
+                }
+                sdl2::event::Event::ControllerDeviceRemoved { which, .. } => {
+                    self.controllers.retain(|c| c.instance_id() != which);
+                }
+                // Closing the window stops the engine; the event is still
+                // queued below so Python can see why.
+                sdl2::event::Event::Quit { .. } => self.quit(),
+                _ => {}
+            }
+
+            if let sdl2::event::Event::Window {
+                win_event:
+                    sdl2::event::WindowEvent::Resized(w, h)
+                    | sdl2::event::WindowEvent::SizeChanged(w, h),
+                ..
+            } = event
+            {
+                self.resize_framebuffers(w.max(1) as u32, h.max(1) as u32)?;
+            }
+
+            Python::attach(|py| {
+                let full = format!("{:?}", event);
+                let name = if let Some(idx) = full.find('{') {
+                    full[..idx].trim().to_string()
+                } else {
+                    full.clone()
+                };
+                let details = format!("{:#?}", event);
+                let dict = PyDict::new(py);
+                let _ = dict.set_item("raw", details.clone());
+                let _ = dict.set_item("variant", name.clone());
+
+                let ts = event.get_timestamp();
+                let _ = dict.set_item("timestamp", ts);
+
+                if let Ok(py_ev) = Py::new(
+                    py,
+                    SdlEvent {
+                        name: name.clone(),
+                        timestamp: ts,
+                        details: details.clone(),
+                        fields: dict.unbind(),
+                    },
+                ) && let Ok(mut q) = self.pending_events.lock()
+                {
+                    q.push(py_ev);
+                }
+            });
+        }
+
+        if self.should_quit {
+            return Ok(false);
+        }
+
+        if self.auto_physics {
+            let dt = self.delta_time * self.time_scale;
+            self.run_physics(|world| world.advance(dt));
+        }
+
+        Python::attach(|py| {
+            if let Ok(mut state) = self.listener_state.lock()
+                && let Ok(camera) = self.camera.try_borrow_mut(py)
+            {
+                let up = camera.view_up();
+                // Camera motion drives the listener's Doppler velocity.
+                state.velocity = if self.delta_time > 0.0 {
+                    (camera.position - state.position) / self.delta_time
+                } else {
+                    Vec3::ZERO
+                };
+                state.position = camera.position;
+                state.front = camera.front;
+                state.up = up;
+                state.right = camera.front.cross(up).normalize_or_zero();
+            }
+
+            self.advance_animators(py);
+        });
+
+        let camera = Python::attach(|py| self.camera.clone_ref(py));
+        self.render_view(&camera, None);
+        for (camera, rect) in std::mem::take(&mut self.queued_views) {
+            self.render_view(&camera, Some(rect));
+        }
+
+        let sprites: Vec<SpriteDrawInfo> = Python::attach(|py| {
+            self.sprites
+                .iter()
+                .chain(&self.queued_sprites)
+                .map(|s| s.borrow(py))
+                .filter(|s| s.visible)
+                .map(|s| s.draw_info(py))
+                .collect()
+        });
+        self.queued_sprites.clear();
+        self.renderer.render_sprites(&sprites);
+
+        if !self.headless
+            && let Some(win) = &self.window
+        {
+            win.gl_swap_window();
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the last rendered frame as `width * height * 4` bytes of RGBA,
+    /// top row first.
+    pub fn read_pixels(&self) -> Vec<u8> {
+        self.renderer.read_pixels()
+    }
+
+    /// Seconds elapsed between the last two calls to `update`, unaffected by
+    /// the time scale.
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
+
+    /// Seconds since the engine was created, unaffected by the time scale.
+    pub fn elapsed_time(&self) -> f32 {
+        self.created.elapsed().as_secs_f32()
+    }
+
+    /// How many times `update` has been called.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Scales how fast physics and animation advance: 0 pauses them, 0.5 is
+    /// half speed. Rendering, input and audio carry on at normal speed.
+    pub fn set_time_scale(&mut self, scale: f32) -> PyResult<()> {
+        if !scale.is_finite() || scale < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Time scale must be a non-negative number, got {}",
+                scale
+            )));
+        }
+        self.time_scale = scale;
+        Ok(())
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Advances physics by exactly one step of `dt` seconds, ignoring the
+    /// time scale. Pair with `set_auto_physics(False)` for a deterministic,
+    /// caller-driven fixed-step loop.
+    pub fn step_physics(&mut self, dt: f32) -> PyResult<()> {
+        if !dt.is_finite() || dt <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Physics step must be a positive number of seconds, got {}",
+                dt
+            )));
+        }
+        self.run_physics(|world| world.step_with(dt));
+        Ok(())
+    }
+
+    /// Enables or disables the physics stepping done inside `update`. On by
+    /// default.
+    pub fn set_auto_physics(&mut self, enabled: bool) {
+        self.auto_physics = enabled;
+    }
+
+    /// Simulates an entity with a rigid body of `body_type` ("dynamic",
+    /// "fixed" or "kinematic"). `collider` is "box" or "sphere", sized from
+    /// the mesh bounds and the entity's scale, or "none". Dynamic bodies
+    /// write their pose back to the entity after each step; kinematic bodies
+    /// follow the entity's transform. Moving an entity directly teleports
+    /// its body and wakes it. Bodies live in world space, so parented
+    /// entities are converted through their parents' transforms.
+    #[pyo3(signature = (entity, body_type="dynamic", collider="box"))]
+    pub fn add_rigid_body(
+        &mut self,
+        py: Python,
+        entity: &Bound<'_, Entity>,
+        body_type: &str,
+        collider: &str,
+    ) -> PyResult<()> {
+        if self.body_handle(entity).is_ok() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Entity already has a rigid body",
+            ));
+        }
+        let body_type = physics::body_type_from_name(body_type).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown body type '{}', expected 'dynamic', 'fixed' or 'kinematic'",
+                body_type
+            ))
+        })?;
+
+        let e = entity.borrow();
+        let (scale, rotation, position) = e.world_transform(py);
+        let collider = match collider {
+            "none" => None,
+            "box" | "sphere" => {
+                let mesh = e.mesh.as_ref().ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err(
+                        "Entity needs a mesh to size its collider",
+                    )
+                })?;
+                let mesh = mesh.borrow(py);
+                let builder = if collider == "box" {
+                    let half = (mesh.bounds_max - mesh.bounds_min) * 0.5 * scale.abs();
+                    ColliderBuilder::cuboid(half.x, half.y, half.z)
+                        .translation((mesh.bounds_min + mesh.bounds_max) * 0.5 * scale)
+                } else {
+                    ColliderBuilder::ball(mesh.bounds_radius * scale.abs().max_element())
+                        .translation(mesh.bounds_center * scale)
+                };
+                Some(builder.build())
+            }
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown collider '{}', expected 'box', 'sphere' or 'none'",
+                    other
+                )));
+            }
+        };
+        let pose = Pose::from_parts(position, rotation);
+        drop(e);
+
+        let handle = self.physics_world.add_body(body_type, pose, collider);
+        self.rigid_bodies.push((entity.clone().unbind(), handle));
+        Ok(())
+    }
+
+    /// Stops simulating an entity. Returns whether it had a rigid body.
+    pub fn remove_rigid_body(&mut self, entity: &Bound<'_, Entity>) -> bool {
+        let Some(index) = self
+            .rigid_bodies
+            .iter()
+            .position(|(e, _)| e.as_ptr() == entity.as_ptr())
+        else {
+            return false;
+        };
+        let (_, handle) = self.rigid_bodies.remove(index);
+        self.physics_world.remove_body(handle);
+        true
+    }
+
+    pub fn is_body_sleeping(&self, entity: &Bound<'_, Entity>) -> PyResult<bool> {
+        Ok(self.rigid_body(entity)?.is_sleeping())
+    }
+
+    pub fn wake_body(&mut self, entity: &Bound<'_, Entity>) -> PyResult<()> {
+        self.rigid_body_mut(entity)?.wake_up(true);
+        Ok(())
+    }
+
+    /// Puts a body to sleep until something touches it or it is woken.
+    pub fn sleep_body(&mut self, entity: &Bound<'_, Entity>) -> PyResult<()> {
+        self.rigid_body_mut(entity)?.sleep();
+        Ok(())
+    }
+
+    pub fn get_linear_velocity(&self, entity: &Bound<'_, Entity>) -> PyResult<(f32, f32, f32)> {
+        Ok(self.rigid_body(entity)?.linvel().into())
+    }
+
+    /// Sets the linear velocity of a dynamic body, waking it up.
+    pub fn set_linear_velocity(
+        &mut self,
+        entity: &Bound<'_, Entity>,
+        x: f32,
+        y: f32,
+        z: f32,
+    ) -> PyResult<()> {
+        self.dynamic_body_mut(entity)?
+            .set_linvel(Vec3::new(x, y, z), true);
+        Ok(())
+    }
+
+    /// Angular velocity in radians per second around each world axis.
+    pub fn get_angular_velocity(&self, entity: &Bound<'_, Entity>) -> PyResult<(f32, f32, f32)> {
+        Ok(self.rigid_body(entity)?.angvel().into())
+    }
+
+    /// Sets the angular velocity of a dynamic body, waking it up.
+    pub fn set_angular_velocity(
+        &mut self,
+        entity: &Bound<'_, Entity>,
+        x: f32,
+        y: f32,
+        z: f32,
+    ) -> PyResult<()> {
+        self.dynamic_body_mut(entity)?
+            .set_angvel(Vec3::new(x, y, z), true);
+        Ok(())
+    }
+
+    /// Connects the bodies of two entities with a joint and returns a handle
+    /// for `remove_joint`. `kind` is "fixed", "revolute" (rotates around
+    /// `axis`) or "prismatic" (slides along `axis`). Anchors are in each
+    /// body's local space; `limits` bounds the angle in radians or the
+    /// distance along the axis.
+    #[pyo3(signature = (entity_a, entity_b, kind, axis=(0.0, 1.0, 0.0), anchor_a=(0.0, 0.0, 0.0), anchor_b=(0.0, 0.0, 0.0), limits=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_joint(
+        &mut self,
+        entity_a: &Bound<'_, Entity>,
+        entity_b: &Bound<'_, Entity>,
+        kind: &str,
+        axis: (f32, f32, f32),
+        anchor_a: (f32, f32, f32),
+        anchor_b: (f32, f32, f32),
+        limits: Option<(f32, f32)>,
+    ) -> PyResult<u64> {
+        let body_a = self.body_handle(entity_a)?;
+        let body_b = self.body_handle(entity_b)?;
+        if body_a == body_b {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Cannot join an entity to itself",
+            ));
+        }
+        let (anchor_a, anchor_b) = (Vec3::from(anchor_a), Vec3::from(anchor_b));
+        let axis = Vec3::from(axis).try_normalize().ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err("Joint axis must be non-zero")
+        })?;
+        if let Some((min, max)) = limits
+            && min > max
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Joint limits must have min <= max, got ({}, {})",
+                min, max
+            )));
+        }
+
+        let joint: GenericJoint = match kind {
+            "fixed" => {
+                if limits.is_some() {
+                    return Err(pyo3::exceptions::PyValueError::new_err(
+                        "Fixed joints take no limits",
+                    ));
+                }
+                FixedJointBuilder::new()
+                    .local_anchor1(anchor_a)
+                    .local_anchor2(anchor_b)
+                    .into()
+            }
+            "revolute" => {
+                let mut builder = RevoluteJointBuilder::new(axis)
+                    .local_anchor1(anchor_a)
+                    .local_anchor2(anchor_b);
+                if let Some((min, max)) = limits {
+                    builder = builder.limits([min, max]);
+                }
+                builder.into()
+            }
+            "prismatic" => {
+                let mut builder = PrismaticJointBuilder::new(axis)
+                    .local_anchor1(anchor_a)
+                    .local_anchor2(anchor_b);
+                if let Some((min, max)) = limits {
+                    builder = builder.limits([min, max]);
+                }
+                builder.into()
+            }
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown joint kind '{}', expected 'fixed', 'revolute' or 'prismatic'",
+                    other
+                )));
+            }
+        };
+
+        let handle = self.physics_world.add_joint(body_a, body_b, joint);
+        Ok(physics::joint_handle_to_id(handle))
+    }
+
+    /// Removes a joint made by `add_joint`. Returns whether it still existed.
+    pub fn remove_joint(&mut self, handle: u64) -> bool {
+        self.physics_world
+            .remove_joint(physics::joint_handle_from_id(handle))
+    }
+
+    /// Gives an entity a heightfield collider for terrain. `heights` holds
+    /// `rows * cols` samples row by row, rows running along Z and columns
+    /// along X. The grid is centered on the entity and spans `scale.x` by
+    /// `scale.z`, with heights multiplied by `scale.y`, then by the entity's
+    /// scale, so a mesh built from the same grid lines up with it. Entities
+    /// without a rigid body get a fixed one.
+    pub fn add_heightfield(
+        &mut self,
+        py: Python,
+        entity: &Bound<'_, Entity>,
+        heights: Vec<f32>,
+        rows: usize,
+        cols: usize,
+        scale: (f32, f32, f32),
+    ) -> PyResult<()> {
+        if rows < 2 || cols < 2 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Heightfield needs at least 2x2 samples, got {}x{}",
+                rows, cols
+            )));
+        }
+        if heights.len() != rows * cols {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "expected {} heights for {}x{} samples, got {}",
+                rows * cols,
+                rows,
+                cols,
+                heights.len()
+            )));
+        }
+
+        // Rapier stores the grid column-major.
+        let mut data = vec![0.0; rows * cols];
+        for (i, height) in heights.iter().enumerate() {
+            let (row, col) = (i / cols, i % cols);
+            data[row + col * rows] = *height;
+        }
+        let scale = Vec3::from(scale) * entity.borrow().world_transform(py).0;
+        let collider = ColliderBuilder::heightfield(Array2::new(rows, cols, data), scale).build();
+
+        let body = self.body_or_fixed(py, entity)?;
+        self.physics_world.add_collider(body, collider);
+        Ok(())
+    }
+
+    /// Gives an entity a triangle-mesh collider matching its mesh, scaled by
+    /// the entity's scale. Only static geometry is supported: entities
+    /// without a rigid body get a fixed one, and other body types are
+    /// rejected.
+    pub fn add_trimesh_collider(&mut self, py: Python, entity: &Bound<'_, Entity>) -> PyResult<()> {
+        if let Ok(handle) = self.body_handle(entity)
+            && !self.physics_world.rigid_body_set[handle].is_fixed()
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Trimesh colliders need a fixed rigid body",
+            ));
+        }
+        let e = entity.borrow();
+        let mesh = e
+            .mesh
+            .as_ref()
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Entity has no mesh"))?;
+        let (mut positions, triangles) = mesh.borrow(py).triangles();
+        let (scale, _, _) = e.world_transform(py);
+        for position in &mut positions {
+            *position *= scale;
+        }
+        drop(e);
+        let collider = ColliderBuilder::trimesh(positions, triangles)
+            .map_err(|err| {
+                pyo3::exceptions::PyValueError::new_err(format!(
+                    "Cannot build trimesh collider: {}",
+                    err
+                ))
+            })?
+            .build();
+
+        let body = self.body_or_fixed(py, entity)?;
+        self.physics_world.add_collider(body, collider);
+        Ok(())
+    }
+
+    /// Entities whose colliders intersect a sphere. Colliders added since
+    /// the last physics step are not seen yet.
+    pub fn overlap_sphere(
+        &self,
+        py: Python,
+        center: (f32, f32, f32),
+        radius: f32,
+    ) -> PyResult<Vec<Py<Entity>>> {
+        if !radius.is_finite() || radius <= 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Sphere radius must be positive, got {}",
+                radius
+            )));
+        }
+        let shape = SharedShape::ball(radius);
+        let pose = Pose::from_translation(Vec3::from(center));
+        let mut entities: Vec<Py<Entity>> = Vec::new();
+        for handle in self.physics_world.intersect_shape(pose, &*shape) {
+            if let Some(entity) = self.entity_for_collider(py, handle)
+                && !entities.iter().any(|e| e.as_ptr() == entity.as_ptr())
+            {
+                entities.push(entity);
+            }
+        }
+        Ok(entities)
+    }
+
+    /// Sweeps a "sphere" (of `radius`) or "box" (of `half_extents`) from
+    /// `start` to `end` and returns the first entity hit along with the
+    /// shape's center at the moment of contact, or None if the path is clear.
+    #[pyo3(signature = (shape, start, end, radius=0.5, half_extents=(0.5, 0.5, 0.5)))]
+    pub fn shape_cast(
+        &self,
+        py: Python,
+        shape: &str,
+        start: (f32, f32, f32),
+        end: (f32, f32, f32),
+        radius: f32,
+        half_extents: (f32, f32, f32),
+    ) -> PyResult<Option<ShapeHit>> {
+        let shape = match shape {
+            "sphere" if radius > 0.0 => SharedShape::ball(radius),
+            "box" if half_extents.0 > 0.0 && half_extents.1 > 0.0 && half_extents.2 > 0.0 => {
+                SharedShape::cuboid(half_extents.0, half_extents.1, half_extents.2)
+            }
+            "sphere" | "box" => {
+                return Err(pyo3::exceptions::PyValueError::new_err(
+                    "Shape size must be positive",
+                ));
+            }
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown shape '{}', expected 'sphere' or 'box'",
+                    other
+                )));
+            }
+        };
+        let (start, end) = (Vec3::from(start), Vec3::from(end));
+        let hit =
+            self.physics_world
+                .cast_shape(Pose::from_translation(start), end - start, &*shape);
+        Ok(hit.and_then(|(handle, fraction)| {
+            let entity = self.entity_for_collider(py, handle)?;
+            Some((entity, start.lerp(end, fraction).into()))
+        }))
+    }
+
+    /// Frames per second averaged over the last `FPS_SAMPLE_FRAMES` frames.
+    pub fn fps(&self) -> f32 {
+        let total: f32 = self.frame_times.iter().sum();
+        if total > 0.0 {
+            self.frame_times.len() as f32 / total
+        } else {
+            0.0
+        }
+    }
+
+    pub fn poll_events(&mut self) -> PyResult<Vec<Py<SdlEvent>>> {
+        if let Ok(mut q) = self.pending_events.lock() {
+            let events: Vec<Py<SdlEvent>> = q.drain(..).collect();
+            Ok(events)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Unprojects a window pixel into a world-space ray as (origin, direction).
+    pub fn screen_to_ray(&self, py: Python, x: i32, y: i32) -> ((f32, f32, f32), (f32, f32, f32)) {
+        let camera = self.camera.borrow(py);
+        let aspect = self.renderer.width as f32 / self.renderer.height as f32;
+        let inv_view_proj =
+            (camera.get_projection_matrix(aspect) * camera.get_view_matrix()).inverse();
+
+        let ndc = self.pixel_to_ndc(Vec2::new(x as f32, y as f32));
+
+        let near = inv_view_proj.project_point3(ndc.extend(-1.0));
+        let far = inv_view_proj.project_point3(ndc.extend(1.0));
+        let direction = (far - near).normalize_or_zero();
+
+        (
+            (near.x, near.y, near.z),
+            (direction.x, direction.y, direction.z),
+        )
+    }
+
+    pub fn move_camera(&mut self, dx: f32, dy: f32, dz: f32) {
+        Python::attach(|py| {
+            let mut camera = self.camera.borrow_mut(py);
+            camera.position += Vec3::new(dx, dy, dz);
+            self.clamp_to_camera_bounds(&mut camera.position);
+        });
+    }
+
+    /// Moves the camera along its facing direction, its right vector, and world up.
+    pub fn move_camera_local(&mut self, forward: f32, right: f32, up: f32) {
+        Python::attach(|py| {
+            let mut camera = self.camera.borrow_mut(py);
+
+            let right_dir = camera.front.cross(camera.up).normalize_or_zero();
+            let delta = camera.front * forward + right_dir * right + Vec3::Y * up;
+            camera.position += delta;
+            self.clamp_to_camera_bounds(&mut camera.position);
+        });
+    }
+
+    /// Confines camera movement to the box between `min` and `max`. The
+    /// camera is pulled inside right away if it is currently outside.
+    pub fn set_camera_bounds(
+        &mut self,
+        py: Python,
+        min: (f32, f32, f32),
+        max: (f32, f32, f32),
+    ) -> PyResult<()> {
+        let (min, max) = (Vec3::from(min), Vec3::from(max));
+        if min.cmpgt(max).any() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "camera bounds min {:?} exceeds max {:?}",
+                min, max
+            )));
+        }
+        self.camera_bounds = Some((min, max));
+        let mut camera = self.camera.borrow_mut(py);
+        self.clamp_to_camera_bounds(&mut camera.position);
+        Ok(())
+    }
+
+    pub fn unset_camera_bounds(&mut self) {
+        self.camera_bounds = None;
+    }
+
+    pub fn rotate_camera(&mut self, yaw: f32, pitch: f32) {
+        Python::attach(|py| {
+            let mut camera = self.camera.borrow_mut(py);
+
+            camera.yaw += yaw;
+            camera.pitch += pitch;
+
+            camera.pitch = camera.pitch.clamp(-89.0, 89.0);
+            camera.update_front();
+        });
+    }
+
+    /// Places the camera `distance` units from `target`, looking at it along
+    /// the direction given by `yaw` and `pitch` in degrees. Later
+    /// `rotate_camera` calls continue from the same angles.
+    pub fn orbit_camera(&mut self, target: (f32, f32, f32), yaw: f32, pitch: f32, distance: f32) {
+        Python::attach(|py| {
+            let mut camera = self.camera.borrow_mut(py);
+
+            camera.yaw = yaw;
+            camera.pitch = pitch.clamp(-89.0, 89.0);
+            camera.update_front();
+            camera.position = Vec3::from(target) - camera.front * distance.max(0.0);
+        });
+    }
+}
+
+impl Engine {
+    fn create(
+        title: &str,
+        width: u32,
+        height: u32,
+        headless: bool,
+        msaa_samples: u32,
+    ) -> PyResult<Self> {
+        if headless {
+            // Environment variables still win over these hints.
+            sdl2::hint::set("SDL_AUDIODRIVER", "dummy");
+            if cfg!(target_os = "linux")
+                && std::env::var_os("DISPLAY").is_none()
+                && std::env::var_os("WAYLAND_DISPLAY").is_none()
+            {
+                sdl2::hint::set("SDL_VIDEODRIVER", "offscreen");
+            }
+        }
+
+        let sdl_context =
+            sdl2::init().map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        let video_subsystem = sdl_context
+            .video()
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        let audio_subsystem = sdl_context
+            .audio()
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        let controller_subsystem = sdl_context
+            .game_controller()
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+
+        let gl_attr = video_subsystem.gl_attr();
+        gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
+        gl_attr.set_context_version(3, 3);
+
+        let mut window_builder = video_subsystem.window(title, width, height);
+        window_builder.opengl();
+        if headless {
+            window_builder.hidden();
+        } else {
+            window_builder.resizable().position_centered();
+        }
+        let window = window_builder
+            .build()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        let _gl_context = window
+            .gl_create_context()
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+
+        gl::load_with(|s| video_subsystem.gl_get_proc_address(s) as *const _);
+
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+        }
+
+        let mut renderer = Renderer::new(width, height)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        if headless {
+            renderer
+                .enable_offscreen_output()
+                .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        }
+        if msaa_samples > 0 {
+            renderer
+                .enable_forward_msaa(msaa_samples)
+                .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        }
+        let scene = Scene::new();
+        let camera = Python::attach(|py| {
+            Py::new(py, Camera::new(0.0, 0.0, 0.0))
+                .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+                .unwrap()
+        });
+        let physics_world = PhysicsWorld::new();
+        let input_state = InputState::new();
+
+        // Audio Setup
+        let desired_spec = sdl2::audio::AudioSpecDesired {
+            freq: Some(44100),
+            channels: Some(2),
+            samples: None,
+        };
+
+        let audio_sources = Arc::new(Mutex::new(Vec::new()));
+        let initial_sources = audio_sources.clone();
+
+        let listener_state = Arc::new(Mutex::new(ListenerState {
+            position: Vec3::ZERO,
+            velocity: Vec3::ZERO,
+            front: Vec3::NEG_Z,
+            up: Vec3::Y,
+            right: Vec3::X,
+            master_volume: 1.0,
+            doppler_factor: 1.0,
+            group_volumes: [1.0; AudioGroup::COUNT],
+            muted: false,
+        }));
+        let listener_state_clone = listener_state.clone();
+
+        let pending_events = Arc::new(Mutex::new(Vec::new()));
+        let pending_events_clone = pending_events.clone();
+
+        let device = audio_subsystem
+            .open_playback(None, &desired_spec, |_spec| {
+                AudioMixer::new(initial_sources.clone(), listener_state_clone.clone())
+            })
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+
+        device.resume();
 
-            if let sdl2::event::Event::Window {
-                win_event:
-                    sdl2::event::WindowEvent::Resized(w, h)
-                    | sdl2::event::WindowEvent::SizeChanged(w, h),
-                ..
-            } = event
-            {
-                let width = if w > 0 { w } else { 1 };
-                let height = if h > 0 { h } else { 1 };
+        let engine = Engine {
+            sdl_context,
+            video_subsystem,
+            _audio_subsystem: audio_subsystem,
+            controller_subsystem,
+            controllers: Vec::new(),
+            _audio_device: device,
+            window: Some(window),
+            _gl_context,
+            headless,
+            renderer,
+            scene,
+            camera,
+            skybox: None,
+            camera_bounds: None,
+            previous_view_projection: None,
+            light_gizmos: true,
+            outlines: Vec::new(),
+            sprites: Vec::new(),
+            queued_sprites: Vec::new(),
+            queued_views: Vec::new(),
+            physics_world,
+            auto_physics: true,
+            rigid_bodies: Vec::new(),
+            input: input_state,
+            should_quit: false,
+            created: Instant::now(),
+            frame_count: 0,
+            last_frame: Instant::now(),
+            delta_time: 0.0,
+            time_scale: 1.0,
+            frame_times: VecDeque::with_capacity(FPS_SAMPLE_FRAMES),
+            audio_sources,
+            pending_events: pending_events_clone,
+            listener_state,
+        };
 
-                self.renderer.resize(width as u32, height as u32);
+        Ok(engine)
+    }
 
-                unsafe {
-                    gl::Viewport(0, 0, width, height);
-                }
-            }
+    fn body_handle(&self, entity: &Bound<'_, Entity>) -> PyResult<RigidBodyHandle> {
+        self.rigid_bodies
+            .iter()
+            .find(|(e, _)| e.as_ptr() == entity.as_ptr())
+            .map(|(_, handle)| *handle)
+            .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("Entity has no rigid body"))
+    }
 
-            Python::attach(|py| {
-                let full = format!("{:?}", event);
-                let name = if let Some(idx) = full.find('{') {
-                    full[..idx].trim().to_string()
-                } else {
-                    full.clone()
-                };
-                let details = format!("{:#?}", event);
-                let dict = PyDict::new(py);
-                let _ = dict.set_item("raw", details.clone());
-                let _ = dict.set_item("variant", name.clone());
+    fn rigid_body(&self, entity: &Bound<'_, Entity>) -> PyResult<&RigidBody> {
+        let handle = self.body_handle(entity)?;
+        Ok(&self.physics_world.rigid_body_set[handle])
+    }
 
-                let ts = event.get_timestamp();
-                let _ = dict.set_item("timestamp", ts);
+    fn rigid_body_mut(&mut self, entity: &Bound<'_, Entity>) -> PyResult<&mut RigidBody> {
+        let handle = self.body_handle(entity)?;
+        Ok(&mut self.physics_world.rigid_body_set[handle])
+    }
 
-                if let Ok(py_ev) = Py::new(
-                    py,
-                    SdlEvent {
-                        name: name.clone(),
-                        timestamp: ts,
-                        details: details.clone(),
-                        fields: dict.unbind(),
-                    },
-                ) && let Ok(mut q) = self.pending_events.lock()
-                {
-                    q.push(py_ev);
-                }
-            });
+    fn dynamic_body_mut(&mut self, entity: &Bound<'_, Entity>) -> PyResult<&mut RigidBody> {
+        let body = self.rigid_body_mut(entity)?;
+        if !body.is_dynamic() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Entity's rigid body is not dynamic",
+            ));
         }
+        Ok(body)
+    }
 
-        if self.should_quit {
-            return Ok(false);
+    /// The entity's body, creating a fixed one at its transform if it has
+    /// none.
+    fn body_or_fixed(
+        &mut self,
+        py: Python,
+        entity: &Bound<'_, Entity>,
+    ) -> PyResult<RigidBodyHandle> {
+        if let Ok(handle) = self.body_handle(entity) {
+            return Ok(handle);
         }
+        self.add_rigid_body(py, entity, "fixed", "none")?;
+        self.body_handle(entity)
+    }
 
-        self.physics_world.step();
+    /// The entity owning the body a collider is attached to.
+    fn entity_for_collider(&self, py: Python, handle: ColliderHandle) -> Option<Py<Entity>> {
+        let body = self.physics_world.collider_set.get(handle)?.parent()?;
+        self.rigid_bodies
+            .iter()
+            .find(|(_, h)| *h == body)
+            .map(|(entity, _)| entity.clone_ref(py))
+    }
+
+    fn clear_rigid_bodies(&mut self) {
+        for (_, handle) in self.rigid_bodies.drain(..) {
+            self.physics_world.remove_body(handle);
+        }
+    }
 
+    /// Runs `step` on the physics world between pushing entity transforms
+    /// into their bodies and copying dynamic body poses back out. A body
+    /// whose entity was moved outside physics is teleported and woken so it
+    /// resettles. Poses are converted to and from world space, so parented
+    /// entities work; the small tolerance absorbs that round trip.
+    fn run_physics(&mut self, step: impl FnOnce(&mut PhysicsWorld)) {
         Python::attach(|py| {
-            if let Ok(mut state) = self.listener_state.lock()
-                && let Ok(camera) = self.camera.try_borrow_mut(py)
-            {
-                state.position = camera.position;
-                state.right = camera.front.cross(camera.up).normalize_or_zero();
+            for (entity, handle) in &self.rigid_bodies {
+                let body = &mut self.physics_world.rigid_body_set[*handle];
+                let (_, rotation, position) = entity.borrow(py).world_transform(py);
+                let pose = Pose::from_parts(position, rotation);
+                if body.body_type() == RigidBodyType::KinematicPositionBased {
+                    body.set_next_kinematic_position(pose);
+                } else if !pose
+                    .translation
+                    .abs_diff_eq(body.translation(), POSE_EPSILON)
+                    || !pose.rotation.abs_diff_eq(*body.rotation(), POSE_EPSILON)
+                {
+                    body.set_position(pose, true);
+                }
             }
 
-            self.render_shadows();
-
-            self.renderer.begin_geometry_pass();
-
-            let projection = Mat4::perspective_rh_gl(
-                self.camera.borrow(py).fov.to_radians(),
-                self.renderer.width as f32 / self.renderer.height as f32,
-                0.001,
-                1000.0,
-            );
-            let view = self.camera.borrow(py).get_view_matrix();
-
-            let shader = self.renderer.get_geometry_shader();
-            shader.use_program();
-            shader.set_mat4("view", &view);
-            shader.set_mat4("projection", &projection);
+            step(&mut self.physics_world);
 
-            shader.set_int("albedoMap", 0);
-
-            for entity_py in &self.scene.entities {
-                let entity = entity_py.borrow(py);
-                if let Some(mesh) = &entity.mesh {
-                    let mesh_ref = mesh.borrow(py);
-                    shader.set_mat4("model", &entity.transform.get_model_matrix());
-
-                    if mesh_ref.has_submeshes() {
-                        for submesh_idx in 0..mesh_ref.submesh_count() {
-                            if let Some((r, g, b, a)) = mesh_ref.get_submesh_color(submesh_idx) {
-                                let rf = r as f32 / 255.0;
-                                let gf = g as f32 / 255.0;
-                                let bf = b as f32 / 255.0;
-                                let af = a as f32 / 255.0;
-                                unsafe {
-                                    let loc = gl::GetUniformLocation(
-                                        shader.id,
-                                        std::ffi::CString::new("albedoColor").unwrap().as_ptr(),
-                                    );
-                                    gl::Uniform4f(loc, rf, gf, bf, af);
-                                }
-                            } else {
-                                unsafe {
-                                    let loc = gl::GetUniformLocation(
-                                        shader.id,
-                                        std::ffi::CString::new("albedoColor").unwrap().as_ptr(),
-                                    );
-                                    gl::Uniform4f(loc, 1.0, 1.0, 1.0, 0.5);
-                                }
-                            }
-                            mesh_ref.draw_submesh(submesh_idx);
-                        }
-                    } else {
-                        if let Some((r, g, b, a)) = mesh_ref.color {
-                            let rf = r as f32 / 255.0;
-                            let gf = g as f32 / 255.0;
-                            let bf = b as f32 / 255.0;
-                            let af = a as f32 / 255.0;
-                            unsafe {
-                                let loc = gl::GetUniformLocation(
-                                    shader.id,
-                                    std::ffi::CString::new("albedoColor").unwrap().as_ptr(),
-                                );
-                                gl::Uniform4f(loc, rf, gf, bf, af);
-                            }
-                        } else {
-                            unsafe {
-                                let loc = gl::GetUniformLocation(
-                                    shader.id,
-                                    std::ffi::CString::new("albedoColor").unwrap().as_ptr(),
-                                );
-                                gl::Uniform4f(loc, 1.0, 1.0, 1.0, 0.5);
-                            }
-                        }
-                        mesh_ref.draw();
-                    }
+            for (entity, handle) in &self.rigid_bodies {
+                let body = &self.physics_world.rigid_body_set[*handle];
+                if body.is_dynamic()
+                    && let Ok(mut e) = entity.try_borrow_mut(py)
+                {
+                    let pose = body.position();
+                    e.set_world_pose(py, pose.translation, pose.rotation);
                 }
             }
+        });
+    }
 
-            self.renderer.end_geometry_pass();
+    fn clamp_to_camera_bounds(&self, position: &mut Vec3) {
+        if let Some((min, max)) = self.camera_bounds {
+            *position = position.clamp(min, max);
+        }
+    }
 
-            self.renderer.begin_lighting_pass();
+    /// Maps a window pixel (origin top-left) to normalized device coordinates.
+    fn pixel_to_ndc(&self, pixel: Vec2) -> Vec2 {
+        Vec2::new(
+            (2.0 * pixel.x) / self.renderer.width as f32 - 1.0,
+            1.0 - (2.0 * pixel.y) / self.renderer.height as f32,
+        )
+    }
 
-            let shader = &self.renderer.lighting_shader;
-            shader.use_program();
-            shader.set_vec3("viewPos", &self.camera.borrow(py).position);
+    fn resize_framebuffers(&mut self, width: u32, height: u32) -> PyResult<()> {
+        if (width, height) == (self.renderer.width, self.renderer.height) {
+            return Ok(());
+        }
+        self.renderer
+            .resize(width, height)
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
 
-            let lights = &self.scene.lights;
-            shader.set_int("numLights", lights.len() as i32);
+        unsafe {
+            gl::Viewport(0, 0, width as i32, height as i32);
+        }
+        Ok(())
+    }
 
-            let mut directional_light_index = None;
-            let mut first_point_light_index = None;
+    fn stop_peripherals(&mut self) {
+        self._audio_device.pause();
 
-            for (i, light_py) in lights.iter().enumerate() {
-                let light = light_py.borrow(py);
-                if light.light_type == LightType::Directional && directional_light_index.is_none() {
-                    directional_light_index = Some(i);
-                } else if light.light_type == LightType::Point && first_point_light_index.is_none()
-                {
-                    first_point_light_index = Some(i);
+        Python::attach(|py| {
+            if let Ok(mut sources) = self.audio_sources.lock() {
+                for src in sources.iter_mut() {
+                    if let Ok(mut s) = src.try_borrow_mut(py) {
+                        s.playing = false;
+                    }
                 }
             }
+        });
 
-            let mut point_light_count = 0;
-            for (i, light_py) in lights.iter().enumerate() {
-                let light = light_py.borrow(py);
-                let name_pos = format!("lights[{}].Position", i);
-                let name_col = format!("lights[{}].Color", i);
-                let name_lin = format!("lights[{}].Linear", i);
-                let name_quad = format!("lights[{}].Quadratic", i);
-                let name_rad = format!("lights[{}].Radius", i);
-                let name_type = format!("lights[{}].Type", i);
-                let name_has_shadow = format!("lights[{}].HasShadow", i);
-
-                shader.set_vec3(&name_pos, &light.position);
-                shader.set_vec3(&name_col, &light.color);
-                shader.set_int(&name_type, light.light_type as i32);
-
-                let linear = 4.5 / light.radius;
-                let quadratic = 75.0 / (light.radius * light.radius);
-
-                shader.set_float(&name_lin, linear);
-                shader.set_float(&name_quad, quadratic);
-                shader.set_float(&name_rad, light.radius);
-
-                let has_shadow = if light.light_type == LightType::Directional
-                    || light.light_type == LightType::Point
-                {
-                    1
-                } else {
-                    0
-                };
-                shader.set_int(&name_has_shadow, has_shadow);
+        self.set_mouse_capture(false);
+    }
 
-                let shadow_map_index = if light.light_type == LightType::Point {
-                    let index = point_light_count;
-                    point_light_count += 1;
-                    index
-                } else {
-                    0
-                };
-                let name_shadow_index = format!("lights[{}].ShadowMapIndex", i);
-                shader.set_int(&name_shadow_index, shadow_map_index);
+    /// Runs the whole pipeline for `camera`, from shadows to the composite.
+    /// `rect` places the image in a (x, y, width, height) window rectangle,
+    /// measured in pixels from the top-left; `None` fills the window.
+    fn render_view(&mut self, camera: &Py<Camera>, rect: Option<ViewportRect>) {
+        let aspect = match rect {
+            Some((_, _, width, height)) => width as f32 / height as f32,
+            None => self.renderer.width as f32 / self.renderer.height as f32,
+        };
 
-                if light.light_type == LightType::Point {
-                    shader.set_float("farPlane", 25.0);
+        let camera_position = Python::attach(|py| {
+            let camera_view = {
+                let camera = camera.borrow(py);
+                let view = camera.get_view_matrix();
+                let projection = camera.get_projection_matrix(aspect);
+                let view_projection = projection * view;
+                // Only the main view has a history for motion blur to use.
+                let previous_view_projection = match rect {
+                    Some(_) => view_projection,
+                    None => self
+                        .previous_view_projection
+                        .replace(view_projection)
+                        .unwrap_or(view_projection),
+                };
+                CameraView {
+                    view,
+                    projection,
+                    position: camera.position,
+                    previous_view_projection,
+                    time: self.elapsed_time(),
                 }
+            };
+            let (view, projection) = (&camera_view.view, &camera_view.projection);
+
+            if self.renderer.forward_rendering() {
+                self.render_forward_opaque(py, &camera_view);
+            } else {
+                self.render_shadows(camera, aspect);
+                self.render_deferred(py, &camera_view);
             }
 
-            self.renderer.render_quad();
-
-            self.renderer.blit_depth_from_gbuffer_to_hdr();
-
             for light_py in &self.scene.lights {
                 let light = light_py.borrow(py);
-                if light.light_type == LightType::Point {
+                if self.light_gizmos && light.light_type == LightType::Point && light.show_gizmo {
                     let visual_scale = light.radius * 0.2;
                     let model = Mat4::from_scale_rotation_translation(
                         Vec3::splat(visual_scale),
@@ -441,132 +1851,449 @@ impl Engine {
                     );
 
                     self.renderer
-                        .render_sphere_at(&model, &view, &projection, &light.color);
+                        .render_sphere_at(&model, view, projection, &light.color);
                 }
             }
+
+            if let Some(skybox) = &self.skybox {
+                self.renderer
+                    .render_skybox(skybox.borrow(py).id, view, projection);
+            }
+
+            self.render_transparent(py, &camera_view);
+            self.render_outlines(py, &camera_view);
+            self.renderer.resolve_msaa();
+
+            self.renderer
+                .apply_ssr(view, projection, &camera_view.position);
+            camera_view.position
         });
 
+        self.renderer.apply_motion_blur();
+        self.renderer.apply_dof(&camera_position);
+
         // Extract bright areas for bloom from HDR buffer (bright-pass)
         self.renderer.begin_bloom_extract_pass();
         self.renderer.render_quad();
         self.renderer.end_bloom_extract_pass();
 
-        // Apply separable Gaussian blur to the bright-pass to produce bloom texture.
-        // Use N iterations (each iteration is one horizontal or vertical pass).
-        // 10 iterations is a common choice (5 horizontal + 5 vertical).
-        self.renderer.apply_gaussian_blur(10);
+        // Blur the bright-pass down and back up the bloom mip chain.
+        self.renderer.apply_bloom_blur();
 
-        self.renderer.begin_composite_pass();
+        self.renderer.output_viewport = rect;
+        self.renderer.begin_composite_pass(self.elapsed_time());
         self.renderer.render_quad();
 
-        if let Some(win) = &self.window {
-            win.gl_swap_window();
+        self.renderer.apply_fxaa();
+        self.renderer.output_viewport = None;
+    }
+
+    /// Fills the G-buffer with the opaque entities, lights it into the HDR
+    /// buffer and adds emissive surfaces on top.
+    fn render_deferred(&self, py: Python, camera: &CameraView) {
+        let (view, projection) = (&camera.view, &camera.projection);
+        self.renderer.begin_geometry_pass();
+
+        let view_projection = *projection * *view;
+        let frustum = Frustum::from_matrix(&view_projection);
+        let previous_view_projection = camera.previous_view_projection;
+        let time = camera.time;
+
+        let geometry_shader = self.renderer.get_geometry_shader();
+        geometry_shader.use_program();
+        geometry_shader.set_mat4("view", view);
+        geometry_shader.set_mat4("projection", projection);
+        geometry_shader.set_mat4("previousViewProjection", &previous_view_projection);
+        let camera_position = camera.position;
+        geometry_shader.set_vec3("viewPos", &camera_position);
+
+        // Resolve everything the draw loop needs up front so the hot loop
+        // below only touches GL state, not Python objects.
+        let draw_list: Vec<DrawItem> = self
+            .scene
+            .entities
+            .iter()
+            .filter_map(|entity_py| {
+                let entity = entity_py.borrow(py);
+                if entity.transparent {
+                    return None;
+                }
+                DrawItem::collect(py, &entity, &frustum)
+            })
+            .collect();
+
+        for item in &draw_list {
+            let custom_shader = item.shader.as_ref().map(|s| s.borrow(py));
+            let shader = match custom_shader.as_deref() {
+                Some(custom) => {
+                    custom.use_program();
+                    custom.set_mat4("view", view);
+                    custom.set_mat4("projection", projection);
+                    custom.set_mat4("previousViewProjection", &previous_view_projection);
+                    custom.set_int("albedoMap", 0);
+                    custom.set_int("metallicRoughnessMap", 1);
+                    custom.set_int("normalMap", 2);
+                    custom.set_int("heightMap", 3);
+                    custom.set_vec3("viewPos", &camera_position);
+                    custom
+                }
+                None => geometry_shader,
+            };
+            shader.set_mat4("model", &item.model);
+            shader.set_vec2("uvScale", &item.mesh.uv_scale);
+            shader.set_vec2("uvOffset", &item.mesh.uv_offset_at(time));
+            animation::bind_bones(shader, item.bones.as_deref());
+
+            self.renderer.bind_material(shader, item.material.as_ref());
+
+            let custom_depth = !item.depth_test || !item.depth_write;
+            if custom_depth {
+                self.renderer
+                    .set_depth_state(item.depth_test, item.depth_write);
+            }
+
+            if item.material.is_some() {
+                item.mesh.draw();
+            } else if !item.mesh.submeshes.is_empty() {
+                for submesh in &item.mesh.submeshes {
+                    shader.set_vec4("albedoColor", &albedo_color(submesh.color));
+                    mesh::draw_submesh(item.mesh.vao, submesh);
+                }
+            } else {
+                shader.set_vec4("albedoColor", &albedo_color(item.mesh.color));
+                if item.mesh.texture_id != 0 {
+                    unsafe {
+                        gl::ActiveTexture(gl::TEXTURE0);
+                        gl::BindTexture(gl::TEXTURE_2D, item.mesh.texture_id);
+                    }
+                }
+                item.mesh.draw();
+            }
+
+            if custom_depth {
+                self.renderer.set_depth_state(true, true);
+            }
+            if custom_shader.is_some() {
+                geometry_shader.use_program();
+            }
         }
 
-        Ok(true)
-    }
+        self.renderer.end_geometry_pass();
 
-    pub fn poll_events(&mut self) -> PyResult<Vec<Py<SdlEvent>>> {
-        if let Ok(mut q) = self.pending_events.lock() {
-            let events: Vec<Py<SdlEvent>> = q.drain(..).collect();
-            Ok(events)
-        } else {
-            Ok(Vec::new())
+        self.renderer.begin_lighting_pass();
+
+        let shader = &self.renderer.lighting_shader;
+        shader.use_program();
+        shader.set_vec3("viewPos", &camera.position);
+
+        let lights = &self.scene.lights;
+        shader.set_int("numLights", lights.len() as i32);
+
+        let shadow_directional = shadow_directional_index(py, lights);
+
+        let mut point_light_count = 0;
+        for (i, light_py) in lights.iter().enumerate() {
+            let light = light_py.borrow(py);
+            let name_pos = format!("lights[{}].Position", i);
+            let name_col = format!("lights[{}].Color", i);
+            let name_lin = format!("lights[{}].Linear", i);
+            let name_quad = format!("lights[{}].Quadratic", i);
+            let name_rad = format!("lights[{}].Radius", i);
+            let name_type = format!("lights[{}].Type", i);
+            let name_has_shadow = format!("lights[{}].HasShadow", i);
+
+            shader.set_vec3(&name_pos, &light.position);
+            shader.set_vec3(&name_col, &light.color);
+            shader.set_int(&name_type, light.light_type as i32);
+
+            let linear = 4.5 / light.radius;
+            let quadratic = 75.0 / (light.radius * light.radius);
+
+            shader.set_float(&name_lin, linear);
+            shader.set_float(&name_quad, quadratic);
+            shader.set_float(&name_rad, light.radius);
+
+            // Only one directional light gets the cascaded shadow map.
+            let has_shadow = match light.light_type {
+                LightType::Directional => shadow_directional == Some(i),
+                LightType::Point => {
+                    light.cast_shadows && point_light_count < self.renderer.point_shadow_maps.len()
+                }
+            };
+            shader.set_int(&name_has_shadow, has_shadow as i32);
+            shader.set_float(&format!("lights[{}].ShadowBias", i), light.shadow_bias);
+
+            let shadow_map_index = if light.light_type == LightType::Point && has_shadow {
+                let index = point_light_count;
+                point_light_count += 1;
+                index
+            } else {
+                0
+            };
+            let name_shadow_index = format!("lights[{}].ShadowMapIndex", i);
+            shader.set_int(&name_shadow_index, shadow_map_index as i32);
+
+            if light.light_type == LightType::Point {
+                shader.set_float(&format!("lights[{}].FarPlane", i), light.shadow_far_plane());
+            }
+        }
+
+        self.renderer.render_quad();
+
+        self.renderer.blit_depth_from_gbuffer_to_hdr();
+
+        // Entities with a custom shader may move their vertices, so the
+        // emissive pass couldn't line up with them.
+        let emissive_items: Vec<(&DrawItem, Vec3)> = draw_list
+            .iter()
+            .filter(|item| item.shader.is_none())
+            .filter_map(|item| Some((item, item.material?.emissive)))
+            .filter(|(_, emissive)| *emissive != Vec3::ZERO)
+            .collect();
+        if !emissive_items.is_empty() {
+            self.renderer
+                .begin_emissive_pass(view, projection, &camera.position);
+            for (item, emissive) in &emissive_items {
+                self.renderer.draw_emissive_mesh(
+                    &item.mesh,
+                    &item.model,
+                    emissive,
+                    item.bones.as_deref(),
+                );
+            }
+            self.renderer.end_emissive_pass();
         }
     }
 
-    pub fn move_camera(&mut self, dx: f32, dy: f32, dz: f32) {
-        Python::attach(|py| {
-            self.camera.borrow_mut(py).position.x += dx;
-            self.camera.borrow_mut(py).position.y += dy;
-            self.camera.borrow_mut(py).position.z += dz;
-        });
+    /// Shades the opaque entities straight into the multisampled buffer with
+    /// the forward shader. Custom entity shaders target the G-buffer, so they
+    /// are ignored here.
+    fn render_forward_opaque(&self, py: Python, camera: &CameraView) {
+        let (view, projection) = (&camera.view, &camera.projection);
+        let camera_pos = camera.position;
+        let frustum = Frustum::from_matrix(&(*projection * *view));
+
+        self.renderer
+            .begin_forward_pass(view, projection, &camera_pos);
+        self.upload_forward_lights(py);
+
+        for entity_py in &self.scene.entities {
+            let entity = entity_py.borrow(py);
+            if entity.transparent {
+                continue;
+            }
+            let Some(item) = DrawItem::collect(py, &entity, &frustum) else {
+                continue;
+            };
+
+            let custom_depth = !item.depth_test || !item.depth_write;
+            if custom_depth {
+                self.renderer
+                    .set_depth_state(item.depth_test, item.depth_write);
+            }
+            animation::bind_bones(&self.renderer.forward_shader, item.bones.as_deref());
+            self.renderer.draw_forward_mesh(
+                &item.mesh,
+                item.material.as_ref(),
+                &item.model,
+                1.0,
+                camera.time,
+            );
+            if custom_depth {
+                self.renderer.set_depth_state(true, true);
+            }
+        }
+
+        self.renderer.end_forward_pass();
     }
 
-    pub fn rotate_camera(&mut self, yaw: f32, pitch: f32) {
-        Python::attach(|py| {
-            let mut camera = self.camera.borrow_mut(py);
+    /// Blends transparent entities over the lit scene, farthest first.
+    fn render_transparent(&self, py: Python, camera: &CameraView) {
+        let (view, projection) = (&camera.view, &camera.projection);
+        let camera_pos = camera.position;
+        let frustum = Frustum::from_matrix(&(*projection * *view));
+
+        let mut transparent: Vec<(f32, DrawItem)> = self
+            .scene
+            .entities
+            .iter()
+            .filter_map(|entity_py| {
+                let entity = entity_py.borrow(py);
+                if !entity.transparent {
+                    return None;
+                }
+                let item = DrawItem::collect(py, &entity, &frustum)?;
+                let distance = item.model.w_axis.truncate().distance_squared(camera_pos);
+                Some((distance, item))
+            })
+            .collect();
 
-            camera.yaw += yaw;
-            camera.pitch += pitch;
+        if transparent.is_empty() {
+            return;
+        }
+        transparent.sort_by(|a, b| b.0.total_cmp(&a.0));
 
-            camera.pitch = camera.pitch.clamp(-89.0, 89.0);
+        self.renderer
+            .begin_transparent_pass(view, projection, &camera_pos);
 
-            let front_x = camera.yaw.to_radians().cos() * camera.pitch.to_radians().cos();
-            let front_y = camera.pitch.to_radians().sin();
-            let front_z = camera.yaw.to_radians().sin() * camera.pitch.to_radians().cos();
-            camera.front = Vec3::new(front_x, front_y, front_z).normalize();
-        });
+        self.upload_forward_lights(py);
+
+        for (_, item) in &transparent {
+            animation::bind_bones(&self.renderer.forward_shader, item.bones.as_deref());
+            self.renderer.draw_forward_mesh(
+                &item.mesh,
+                item.material.as_ref(),
+                &item.model,
+                item.opacity,
+                camera.time,
+            );
+        }
+
+        self.renderer.end_transparent_pass();
     }
-}
 
-impl Engine {
-    fn stop_peripherals(&mut self) {
-        self._audio_device.pause();
+    fn upload_forward_lights(&self, py: Python) {
+        let shader = &self.renderer.forward_shader;
+        let lights = &self.scene.lights;
+        shader.set_int("numLights", lights.len() as i32);
+        for (i, light_py) in lights.iter().enumerate() {
+            let light = light_py.borrow(py);
+            shader.set_vec3(&format!("lights[{}].Position", i), &light.position);
+            shader.set_vec3(&format!("lights[{}].Color", i), &light.color);
+            shader.set_float(&format!("lights[{}].Radius", i), light.radius);
+            shader.set_int(&format!("lights[{}].Type", i), light.light_type as i32);
+        }
+    }
 
-        Python::attach(|py| {
-            if let Ok(mut sources) = self.audio_sources.lock() {
-                for src in sources.iter_mut() {
-                    if let Ok(mut s) = src.try_borrow_mut(py) {
-                        s.playing = false;
-                    }
-                }
-            }
-        });
+    /// Marks every outlined entity in the stencil buffer, then draws each one
+    /// enlarged about its bounds center so only the rim outside the marks shows.
+    fn render_outlines(&self, py: Python, camera: &CameraView) {
+        if self.outlines.is_empty() {
+            return;
+        }
+        let (view, projection) = (&camera.view, &camera.projection);
+        let frustum = Frustum::from_matrix(&(*projection * *view));
+
+        let items: Vec<(DrawItem, Mat4, Vec3)> = self
+            .outlines
+            .iter()
+            .filter_map(|outline| {
+                let entity = outline.entity.borrow(py);
+                let item = DrawItem::collect(py, &entity, &frustum)?;
+                let mesh = entity.mesh.as_ref()?.borrow(py);
+                let (_, radius) = mesh.world_bounding_sphere(&item.model);
+                let grow = 1.0 + outline.width / radius.max(f32::EPSILON);
+                let center = mesh.bounds_center;
+                let shell = item.model
+                    * Mat4::from_translation(center)
+                    * Mat4::from_scale(Vec3::splat(grow))
+                    * Mat4::from_translation(-center);
+                Some((item, shell, outline.color))
+            })
+            .collect();
 
-        self.set_mouse_capture(false);
+        self.renderer.begin_outline_pass(view, projection);
+        for (item, _, _) in &items {
+            self.renderer
+                .draw_outline_mask(&item.mesh, &item.model, item.bones.as_deref());
+        }
+        self.renderer.begin_outline_shells();
+        for (item, shell, color) in &items {
+            self.renderer
+                .draw_outline_shell(&item.mesh, shell, item.bones.as_deref(), color);
+        }
+        self.renderer.end_outline_pass();
     }
 
-    fn render_shadows(&mut self) {
-        Python::attach(|py| {
-            let lights = &self.scene.lights;
-            let mut directional_light_index = None;
-            let mut first_point_light_index = None;
+    /// Shrinks the point shadow slots so there are never more than the scene
+    /// has point lights.
+    fn sync_point_light_count(&mut self, py: Python) -> PyResult<()> {
+        let point_lights = self
+            .scene
+            .lights
+            .iter()
+            .filter(|l| l.borrow(py).light_type == LightType::Point)
+            .count();
+
+        if point_lights < self.renderer.point_shadow_maps.len() {
+            self.configure_point_lights(point_lights)?;
+        }
+        Ok(())
+    }
 
-            for (i, light_py) in lights.iter().enumerate() {
-                let light = light_py.borrow(py);
-                if light.light_type == LightType::Directional && directional_light_index.is_none() {
-                    directional_light_index = Some(i);
-                } else if light.light_type == LightType::Point && first_point_light_index.is_none()
-                {
-                    first_point_light_index = Some(i);
-                }
+    /// Steps every entity's animator so this frame draws the new pose.
+    fn advance_animators(&self, py: Python) {
+        for entity_py in &self.scene.entities {
+            if let Some(animator) = &entity_py.borrow(py).animator {
+                animator
+                    .borrow_mut(py)
+                    .update(self.delta_time * self.time_scale);
             }
+        }
+    }
 
-            if let Some(dir_index) = directional_light_index {
+    /// Fits the shadow cascades to `camera` viewed at `aspect`, then renders
+    /// every shadow map.
+    fn render_shadows(&mut self, camera: &Py<Camera>, aspect: f32) {
+        Python::attach(|py| {
+            let lights = &self.scene.lights;
+            if let Some(dir_index) = shadow_directional_index(py, lights) {
                 let light_py = &lights[dir_index];
                 let light = light_py.borrow(py);
                 let light_direction = -light.position.normalize();
-                let light_pos = light_direction * 30.0;
-
-                self.renderer.begin_directional_shadow_pass();
 
-                let light_projection =
-                    Mat4::orthographic_rh_gl(-20.0, 20.0, -20.0, 20.0, 1.0, 50.0);
-                let light_view = Mat4::look_at_rh(light_pos, Vec3::ZERO, Vec3::Y);
-                let light_space_matrix = light_projection * light_view;
+                let camera = camera.borrow(py);
+                let splits = self.renderer.cascade_splits();
+                let mut cascade_matrices = Vec::with_capacity(splits.len());
 
                 let shader = &self.renderer.directional_shadow_shader;
-                shader.use_program();
-                shader.set_mat4("lightSpaceMatrix", &light_space_matrix);
-
-                for entity_py in &self.scene.entities {
-                    let entity = entity_py.borrow(py);
-                    if let Some(mesh) = &entity.mesh {
-                        shader.set_mat4("model", &entity.transform.get_model_matrix());
-                        mesh.borrow(py).draw();
+                for (cascade, &split_far) in splits.iter().enumerate() {
+                    let split_near = match cascade {
+                        0 => Camera::NEAR_PLANE,
+                        _ => splits[cascade - 1],
+                    };
+                    let corners = camera.frustum_corners(aspect, split_near, split_far);
+                    let light_space_matrix = cascades::cascade_matrix(
+                        &corners,
+                        light_direction,
+                        self.renderer.directional_shadow_size,
+                    );
+                    cascade_matrices.push(light_space_matrix);
+
+                    self.renderer.begin_directional_shadow_pass(cascade);
+                    shader.set_mat4("lightSpaceMatrix", &light_space_matrix);
+
+                    let frustum = Frustum::from_matrix(&light_space_matrix);
+                    for entity_py in &self.scene.entities {
+                        let entity = entity_py.borrow(py);
+                        if let Some(mesh) = &entity.mesh {
+                            let mesh_ref = mesh.borrow(py);
+                            let model = entity.get_world_matrix(py);
+                            let (center, radius) = mesh_ref.world_bounding_sphere(&model);
+                            if frustum.intersects_sphere(center, radius) {
+                                shader.set_mat4("model", &model);
+                                let animator = entity.animator.as_ref().map(|a| a.borrow(py));
+                                animation::bind_bones(shader, animator.as_ref().map(|a| a.bones()));
+                                mesh_ref.draw();
+                            }
+                        }
                     }
-                }
 
-                self.renderer.end_directional_shadow_pass();
+                    self.renderer.end_directional_shadow_pass();
+                }
 
                 let lighting_shader = &self.renderer.lighting_shader;
                 lighting_shader.use_program();
-                lighting_shader.set_mat4("directionalLightSpaceMatrix", &light_space_matrix);
+                lighting_shader.set_int("cascadeCount", splits.len() as i32);
+                lighting_shader.set_mat4_array("cascadeMatrices", &cascade_matrices);
+                for (cascade, split) in splits.iter().enumerate() {
+                    lighting_shader.set_float(&format!("cascadeSplits[{}]", cascade), *split);
+                }
+                lighting_shader.set_mat4("cameraView", &camera.get_view_matrix());
                 lighting_shader.set_vec3("directionalLightDir", &light_direction);
                 unsafe {
                     gl::ActiveTexture(gl::TEXTURE3);
-                    gl::BindTexture(gl::TEXTURE_2D, self.renderer.directional_shadow_map);
+                    gl::BindTexture(gl::TEXTURE_2D_ARRAY, self.renderer.directional_shadow_map);
                 }
             }
 
@@ -574,9 +2301,10 @@ impl Engine {
             for light_py in lights.iter() {
                 let light = light_py.borrow(py);
                 if light.light_type == LightType::Point
+                    && light.cast_shadows
                     && point_light_shadow_index < self.renderer.point_shadow_maps.len()
                 {
-                    let far_plane = 25.0;
+                    let far_plane = light.shadow_far_plane();
                     let shadow_transforms =
                         self.get_point_light_transforms(light.position, far_plane);
 
@@ -590,11 +2318,22 @@ impl Engine {
                         shader.set_vec3("lightPos", &light.position);
                         shader.set_float("farPlane", far_plane);
 
+                        let frustum = Frustum::from_matrix(mat);
                         for entity_py in &self.scene.entities {
                             let entity = entity_py.borrow(py);
                             if let Some(mesh) = &entity.mesh {
-                                shader.set_mat4("model", &entity.transform.get_model_matrix());
-                                mesh.borrow(py).draw();
+                                let mesh_ref = mesh.borrow(py);
+                                let model = entity.get_world_matrix(py);
+                                let (center, radius) = mesh_ref.world_bounding_sphere(&model);
+                                if frustum.intersects_sphere(center, radius) {
+                                    shader.set_mat4("model", &model);
+                                    let animator = entity.animator.as_ref().map(|a| a.borrow(py));
+                                    animation::bind_bones(
+                                        shader,
+                                        animator.as_ref().map(|a| a.bones()),
+                                    );
+                                    mesh_ref.draw();
+                                }
                             }
                         }
 
@@ -604,7 +2343,11 @@ impl Engine {
                     let lighting_shader = &self.renderer.lighting_shader;
                     lighting_shader.use_program();
                     unsafe {
-                        gl::ActiveTexture(gl::TEXTURE4 + point_light_shadow_index as u32);
+                        gl::ActiveTexture(
+                            gl::TEXTURE0
+                                + renderer::POINT_SHADOW_UNIT
+                                + point_light_shadow_index as u32,
+                        );
                         gl::BindTexture(
                             gl::TEXTURE_CUBE_MAP,
                             self.renderer.point_shadow_maps[point_light_shadow_index],
@@ -618,7 +2361,12 @@ impl Engine {
     }
 
     fn get_point_light_transforms(&self, light_pos: Vec3, far_plane: f32) -> [Mat4; 6] {
-        let shadow_proj = Mat4::perspective_rh_gl(90.0f32.to_radians(), 1.0, 0.1, far_plane);
+        let shadow_proj = Mat4::perspective_rh_gl(
+            90.0f32.to_radians(),
+            1.0,
+            Light::SHADOW_NEAR_PLANE,
+            far_plane,
+        );
 
         [
             shadow_proj * Mat4::look_at_rh(light_pos, light_pos + Vec3::X, -Vec3::Y),
@@ -630,3 +2378,116 @@ impl Engine {
         ]
     }
 }
+
+/// One entity's draw state, resolved from its Python objects once per pass.
+struct DrawItem {
+    model: Mat4,
+    mesh: MeshDrawInfo,
+    material: Option<MaterialParams>,
+    shader: Option<Py<Shader>>,
+    bones: Option<Vec<Mat4>>,
+    opacity: f32,
+    depth_test: bool,
+    depth_write: bool,
+}
+
+impl DrawItem {
+    /// Returns `None` when the entity has no mesh or lies outside `frustum`.
+    fn collect(py: Python, entity: &Entity, frustum: &Frustum) -> Option<Self> {
+        let mesh = entity.mesh.as_ref()?.borrow(py);
+        let model = entity.get_world_matrix(py);
+        let (center, radius) = mesh.world_bounding_sphere(&model);
+        if !frustum.intersects_sphere(center, radius) {
+            return None;
+        }
+
+        // An entity material overrides the mesh material, and either one
+        // replaces the per-submesh colors loaded from the model file.
+        let material = entity
+            .material
+            .as_ref()
+            .or(mesh.material.as_ref())
+            .map(|m| m.borrow(py).resolve(py));
+
+        Some(DrawItem {
+            model,
+            mesh: mesh.draw_info(py),
+            material,
+            shader: entity.shader.as_ref().map(|s| s.clone_ref(py)),
+            bones: entity
+                .animator
+                .as_ref()
+                .map(|a| a.borrow(py).bones().to_vec()),
+            opacity: entity.opacity,
+            depth_test: entity.depth_test,
+            depth_write: entity.depth_write,
+        })
+    }
+}
+
+/// What one camera is rendered with this frame.
+struct CameraView {
+    view: Mat4,
+    projection: Mat4,
+    position: Vec3,
+    /// Last frame's view-projection for the velocity buffer; equal to this
+    /// frame's for views without a history.
+    previous_view_projection: Mat4,
+    /// Engine time in seconds, which scrolls animated textures.
+    time: f32,
+}
+
+struct Outline {
+    entity: Py<Entity>,
+    color: Vec3,
+    width: f32,
+}
+
+/// The directional light that gets the cascaded shadow map: the first one
+/// with `cast_shadows` set.
+fn shadow_directional_index(py: Python, lights: &[Py<Light>]) -> Option<usize> {
+    lights.iter().position(|light_py| {
+        let light = light_py.borrow(py);
+        light.light_type == LightType::Directional && light.cast_shadows
+    })
+}
+
+fn albedo_color(color: Option<(u8, u8, u8, u8)>) -> Vec4 {
+    match color {
+        Some((r, g, b, a)) => Vec4::new(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0,
+        ),
+        None => Vec4::new(1.0, 1.0, 1.0, 0.5),
+    }
+}
+
+/// SDL scancode values assigned to a physical key; the gaps are unused.
+const SCANCODE_RANGES: [std::ops::RangeInclusive<i32>; 5] =
+    [4..=129, 133..=164, 176..=221, 224..=231, 257..=290];
+
+/// Looks a key up by SDL key name, falling back to its scancode name.
+fn keycode_from_name(name: &str) -> PyResult<Keycode> {
+    // SDL compares multi-character names case-insensitively, but maps a single
+    // character straight to its code, and letter keycodes are lowercase.
+    let lower = name.to_ascii_lowercase();
+    Keycode::from_name(&lower)
+        .or_else(|| Scancode::from_name(&lower).and_then(Keycode::from_scancode))
+        .ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown key name: {:?} (see Engine.key_names())",
+                name
+            ))
+        })
+}
+
+fn mouse_button_from_name(name: &str) -> Option<sdl2::mouse::MouseButton> {
+    match name {
+        "Left" => Some(sdl2::mouse::MouseButton::Left),
+        "Right" => Some(sdl2::mouse::MouseButton::Right),
+        "Middle" => Some(sdl2::mouse::MouseButton::Middle),
+        _ => None,
+    }
+}
@@ -1,24 +1,44 @@
 use glam::Vec2;
+use sdl2::controller::{Axis, Button};
 use sdl2::event::Event;
-use sdl2::mouse::MouseButton;
-use std::collections::HashSet;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::{MouseButton, MouseWheelDirection};
+use std::collections::{HashMap, HashSet};
+
+/// Normalized stick deflection below which an axis reads as zero.
+pub const GAMEPAD_DEADZONE: f32 = 0.15;
+
+#[derive(Default)]
+pub struct GamepadState {
+    pub axes: HashMap<Axis, f32>,
+    pub buttons: HashSet<Button>,
+}
 
 pub struct InputState {
-    pub pressed_keys: HashSet<String>,
+    pub pressed_keys: HashSet<Keycode>,
+    pub prev_pressed_keys: HashSet<Keycode>,
     pub mouse_buttons: HashSet<MouseButton>,
+    pub prev_mouse_buttons: HashSet<MouseButton>,
     pub mouse_pos: Vec2,
     pub mouse_rel: Vec2,
+    pub scroll_delta: Vec2,
     pub mouse_captured: bool,
+    /// Keyed by joystick instance id.
+    pub gamepads: HashMap<u32, GamepadState>,
 }
 
 impl InputState {
     pub fn new() -> Self {
         InputState {
             pressed_keys: HashSet::new(),
+            prev_pressed_keys: HashSet::new(),
             mouse_buttons: HashSet::new(),
+            prev_mouse_buttons: HashSet::new(),
             mouse_pos: Vec2::ZERO,
             mouse_rel: Vec2::ZERO,
+            scroll_delta: Vec2::ZERO,
             mouse_captured: false,
+            gamepads: HashMap::new(),
         }
     }
 
@@ -27,12 +47,12 @@ impl InputState {
             Event::KeyDown {
                 keycode: Some(k), ..
             } => {
-                self.pressed_keys.insert(k.name());
+                self.pressed_keys.insert(*k);
             }
             Event::KeyUp {
                 keycode: Some(k), ..
             } => {
-                self.pressed_keys.remove(&k.name());
+                self.pressed_keys.remove(k);
             }
             Event::MouseButtonDown { mouse_btn, .. } => {
                 self.mouse_buttons.insert(*mouse_btn);
@@ -46,21 +66,99 @@ impl InputState {
                 self.mouse_pos = Vec2::new(*x as f32, *y as f32);
                 self.mouse_rel += Vec2::new(*xrel as f32, *yrel as f32);
             }
+            Event::MouseWheel {
+                precise_x,
+                precise_y,
+                direction,
+                ..
+            } => {
+                let sign = if *direction == MouseWheelDirection::Flipped {
+                    -1.0
+                } else {
+                    1.0
+                };
+                self.scroll_delta += Vec2::new(*precise_x, *precise_y) * sign;
+            }
+            Event::ControllerAxisMotion {
+                which, axis, value, ..
+            } => {
+                let normalized = (*value as f32 / i16::MAX as f32).clamp(-1.0, 1.0);
+                self.gamepads
+                    .entry(*which)
+                    .or_default()
+                    .axes
+                    .insert(*axis, normalized);
+            }
+            Event::ControllerButtonDown { which, button, .. } => {
+                self.gamepads
+                    .entry(*which)
+                    .or_default()
+                    .buttons
+                    .insert(*button);
+            }
+            Event::ControllerButtonUp { which, button, .. } => {
+                if let Some(pad) = self.gamepads.get_mut(which) {
+                    pad.buttons.remove(button);
+                }
+            }
+            Event::ControllerDeviceRemoved { which, .. } => {
+                self.gamepads.remove(which);
+            }
             _ => {}
         }
     }
 
     pub fn prepare_update(&mut self) {
+        self.prev_pressed_keys.clone_from(&self.pressed_keys);
+        self.prev_mouse_buttons.clone_from(&self.mouse_buttons);
         self.mouse_rel = Vec2::ZERO;
+        self.scroll_delta = Vec2::ZERO;
+    }
+
+    pub fn is_key_down(&self, key: Keycode) -> bool {
+        self.pressed_keys.contains(&key)
     }
 
-    pub fn is_key_down(&self, key: &str) -> bool {
-        self.pressed_keys.contains(key)
+    pub fn is_key_pressed(&self, key: Keycode) -> bool {
+        self.pressed_keys.contains(&key) && !self.prev_pressed_keys.contains(&key)
+    }
+
+    pub fn is_key_released(&self, key: Keycode) -> bool {
+        !self.pressed_keys.contains(&key) && self.prev_pressed_keys.contains(&key)
+    }
+
+    /// Axis value in [-1, 1] with the deadzone removed and the remaining range rescaled.
+    pub fn gamepad_axis(&self, instance_id: u32, axis: Axis) -> f32 {
+        let raw = self
+            .gamepads
+            .get(&instance_id)
+            .and_then(|pad| pad.axes.get(&axis))
+            .copied()
+            .unwrap_or(0.0);
+        if raw.abs() < GAMEPAD_DEADZONE {
+            0.0
+        } else {
+            raw.signum() * (raw.abs() - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE)
+        }
+    }
+
+    pub fn is_gamepad_button_down(&self, instance_id: u32, button: Button) -> bool {
+        self.gamepads
+            .get(&instance_id)
+            .is_some_and(|pad| pad.buttons.contains(&button))
     }
 
     pub fn is_mouse_down(&self, btn: MouseButton) -> bool {
         self.mouse_buttons.contains(&btn)
     }
+
+    pub fn is_mouse_pressed(&self, btn: MouseButton) -> bool {
+        self.mouse_buttons.contains(&btn) && !self.prev_mouse_buttons.contains(&btn)
+    }
+
+    pub fn is_mouse_released(&self, btn: MouseButton) -> bool {
+        !self.mouse_buttons.contains(&btn) && self.prev_mouse_buttons.contains(&btn)
+    }
 }
 
 impl Default for InputState {
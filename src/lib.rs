@@ -26,6 +26,31 @@ fn pie(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_submodule(&texture_mod)?;
     sys_modules.set_item("pie.texture", &texture_mod)?;
 
+    let material_mod = PyModule::new(py, "pie.material")?;
+    material_mod.add_class::<renderer::material::Material>()?;
+    m.add_submodule(&material_mod)?;
+    sys_modules.set_item("pie.material", &material_mod)?;
+
+    let shader_mod = PyModule::new(py, "pie.shader")?;
+    shader_mod.add_class::<renderer::shader::Shader>()?;
+    m.add_submodule(&shader_mod)?;
+    sys_modules.set_item("pie.shader", &shader_mod)?;
+
+    let animation_mod = PyModule::new(py, "pie.animation")?;
+    animation_mod.add_class::<renderer::animation::Animator>()?;
+    m.add_submodule(&animation_mod)?;
+    sys_modules.set_item("pie.animation", &animation_mod)?;
+
+    let sprite_mod = PyModule::new(py, "pie.sprite")?;
+    sprite_mod.add_class::<renderer::sprite::Sprite>()?;
+    m.add_submodule(&sprite_mod)?;
+    sys_modules.set_item("pie.sprite", &sprite_mod)?;
+
+    let skybox_mod = PyModule::new(py, "pie.skybox")?;
+    skybox_mod.add_class::<renderer::skybox::Skybox>()?;
+    m.add_submodule(&skybox_mod)?;
+    sys_modules.set_item("pie.skybox", &skybox_mod)?;
+
     let scene_mod = PyModule::new(py, "pie.scene")?;
     scene_mod.add_class::<scene::Scene>()?;
     m.add_submodule(&scene_mod)?;
@@ -34,6 +59,7 @@ fn pie(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     let entity_mod = PyModule::new(py, "pie.entity")?;
     entity_mod.add_class::<scene::Entity>()?;
     entity_mod.add_class::<scene::Camera>()?;
+    entity_mod.add_class::<scene::ProjectionMode>()?;
     m.add_submodule(&entity_mod)?;
     sys_modules.set_item("pie.entity", &entity_mod)?;
 
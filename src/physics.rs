@@ -1,4 +1,5 @@
 use glam::Vec3;
+use rapier3d::parry::query::ShapeCastOptions;
 use rapier3d::prelude::*;
 
 pub struct PhysicsWorld {
@@ -13,8 +14,14 @@ pub struct PhysicsWorld {
     pub impulse_joint_set: ImpulseJointSet,
     pub multibody_joint_set: MultibodyJointSet,
     pub ccd_solver: CCDSolver,
+    /// Simulation time not yet consumed by a fixed step.
+    accumulator: f32,
 }
 
+/// Caps the steps taken per `advance` so a long frame can't stall the next
+/// one catching up.
+const MAX_STEPS_PER_ADVANCE: u32 = 8;
+
 impl PhysicsWorld {
     pub fn new() -> Self {
         PhysicsWorld {
@@ -29,7 +36,114 @@ impl PhysicsWorld {
             impulse_joint_set: ImpulseJointSet::new(),
             multibody_joint_set: MultibodyJointSet::new(),
             ccd_solver: CCDSolver::new(),
+            accumulator: 0.0,
+        }
+    }
+
+    /// Runs as many fixed `integration_parameters.dt` steps as fit in the
+    /// accumulated time, carrying the remainder over to the next call.
+    pub fn advance(&mut self, dt: f32) {
+        let step = self.integration_parameters.dt;
+        self.accumulator += dt;
+        let mut steps = 0;
+        while self.accumulator >= step && steps < MAX_STEPS_PER_ADVANCE {
+            self.step();
+            self.accumulator -= step;
+            steps += 1;
+        }
+        if steps == MAX_STEPS_PER_ADVANCE {
+            self.accumulator = self.accumulator.min(step);
+        }
+    }
+
+    /// Runs exactly one step of `dt` seconds. The fixed step used by
+    /// `advance` is left unchanged.
+    pub fn step_with(&mut self, dt: f32) {
+        let fixed_step = std::mem::replace(&mut self.integration_parameters.dt, dt);
+        self.step();
+        self.integration_parameters.dt = fixed_step;
+    }
+
+    /// Inserts a body of `body_type` at `pose`, with `collider` attached to
+    /// it when given.
+    pub fn add_body(
+        &mut self,
+        body_type: RigidBodyType,
+        pose: Pose,
+        collider: Option<Collider>,
+    ) -> RigidBodyHandle {
+        let body = RigidBodyBuilder::new(body_type).pose(pose).build();
+        let handle = self.rigid_body_set.insert(body);
+        if let Some(collider) = collider {
+            self.add_collider(handle, collider);
         }
+        handle
+    }
+
+    pub fn add_collider(&mut self, body: RigidBodyHandle, collider: Collider) -> ColliderHandle {
+        self.collider_set
+            .insert_with_parent(collider, body, &mut self.rigid_body_set)
+    }
+
+    /// Removes a body along with its colliders and joints.
+    pub fn remove_body(&mut self, handle: RigidBodyHandle) {
+        self.rigid_body_set.remove(
+            handle,
+            &mut self.island_manager,
+            &mut self.collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            true,
+        );
+    }
+
+    pub fn add_joint(
+        &mut self,
+        body_a: RigidBodyHandle,
+        body_b: RigidBodyHandle,
+        joint: impl Into<GenericJoint>,
+    ) -> ImpulseJointHandle {
+        self.impulse_joint_set.insert(body_a, body_b, joint, true)
+    }
+
+    pub fn remove_joint(&mut self, handle: ImpulseJointHandle) -> bool {
+        self.impulse_joint_set.remove(handle, true).is_some()
+    }
+
+    /// Colliders intersecting `shape` placed at `pose`. Only sees colliders
+    /// that existed at the last step.
+    pub fn intersect_shape(&self, pose: Pose, shape: &dyn Shape) -> Vec<ColliderHandle> {
+        self.query_pipeline()
+            .intersect_shape(pose, shape)
+            .map(|(handle, _)| handle)
+            .collect()
+    }
+
+    /// Sweeps `shape` from `pose` along `motion` and returns the first
+    /// collider hit with the fraction of `motion` travelled before the hit.
+    pub fn cast_shape(
+        &self,
+        pose: Pose,
+        motion: Vec3,
+        shape: &dyn Shape,
+    ) -> Option<(ColliderHandle, f32)> {
+        self.query_pipeline()
+            .cast_shape(
+                &pose,
+                motion,
+                shape,
+                ShapeCastOptions::with_max_time_of_impact(1.0),
+            )
+            .map(|(handle, hit)| (handle, hit.time_of_impact))
+    }
+
+    fn query_pipeline(&self) -> QueryPipeline<'_> {
+        self.broad_phase.as_query_pipeline(
+            self.narrow_phase.query_dispatcher(),
+            &self.rigid_body_set,
+            &self.collider_set,
+            QueryFilter::default(),
+        )
     }
 
     pub fn step(&mut self) {
@@ -58,3 +172,23 @@ impl Default for PhysicsWorld {
         Self::new()
     }
 }
+
+/// Parses the body types accepted from Python.
+pub fn body_type_from_name(name: &str) -> Option<RigidBodyType> {
+    match name {
+        "dynamic" => Some(RigidBodyType::Dynamic),
+        "fixed" => Some(RigidBodyType::Fixed),
+        "kinematic" => Some(RigidBodyType::KinematicPositionBased),
+        _ => None,
+    }
+}
+
+/// Packs a joint handle into the integer handed to Python.
+pub fn joint_handle_to_id(handle: ImpulseJointHandle) -> u64 {
+    let (index, generation) = handle.into_raw_parts();
+    ((generation as u64) << 32) | index as u64
+}
+
+pub fn joint_handle_from_id(id: u64) -> ImpulseJointHandle {
+    ImpulseJointHandle::from_raw_parts(id as u32, (id >> 32) as u32)
+}
@@ -0,0 +1,374 @@
+use crate::renderer::mesh::Mesh;
+use crate::renderer::shader::Shader;
+use glam::{Mat4, Quat, Vec3};
+use gltf::animation::Interpolation;
+use gltf::animation::util::ReadOutputs;
+use pyo3::prelude::*;
+use std::rc::Rc;
+
+/// Size of the `bones` uniform array in the skinning shaders.
+pub const MAX_BONES: usize = 100;
+
+struct SkeletonNode {
+    parent: Option<usize>,
+    translation: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+}
+
+enum ChannelValues {
+    Translation(Vec<Vec3>),
+    Rotation(Vec<Quat>),
+    Scale(Vec<Vec3>),
+}
+
+struct Channel {
+    node: usize,
+    times: Vec<f32>,
+    values: ChannelValues,
+    step: bool,
+}
+
+struct AnimationClip {
+    name: String,
+    duration: f32,
+    channels: Vec<Channel>,
+}
+
+/// The node hierarchy, joints and animation clips of a skinned glTF file.
+pub struct Skeleton {
+    nodes: Vec<SkeletonNode>,
+    // Node indices ordered so every parent comes before its children.
+    order: Vec<usize>,
+    // Joint node and inverse bind matrix for each entry of the bone palette.
+    joints: Vec<(usize, Mat4)>,
+    clips: Vec<AnimationClip>,
+}
+
+impl Skeleton {
+    /// Reads the node hierarchy and every animation in the document.
+    /// `joints` is the combined joint list of all skins, in palette order.
+    pub fn from_gltf(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        joints: Vec<(usize, Mat4)>,
+    ) -> Self {
+        let children: Vec<Vec<usize>> = document
+            .nodes()
+            .map(|node| node.children().map(|c| c.index()).collect())
+            .collect();
+        let mut nodes: Vec<SkeletonNode> = document
+            .nodes()
+            .map(|node| {
+                let (t, r, s) = node.transform().decomposed();
+                SkeletonNode {
+                    parent: None,
+                    translation: Vec3::from_array(t),
+                    rotation: Quat::from_array(r),
+                    scale: Vec3::from_array(s),
+                }
+            })
+            .collect();
+        for (parent, node_children) in children.iter().enumerate() {
+            for &child in node_children {
+                nodes[child].parent = Some(parent);
+            }
+        }
+
+        let mut order = Vec::with_capacity(nodes.len());
+        let mut stack: Vec<usize> = (0..nodes.len())
+            .filter(|&i| nodes[i].parent.is_none())
+            .collect();
+        while let Some(index) = stack.pop() {
+            order.push(index);
+            stack.extend(&children[index]);
+        }
+
+        let clips = document
+            .animations()
+            .enumerate()
+            .map(|(i, animation)| read_clip(&animation, i, buffers))
+            .collect();
+
+        Skeleton {
+            nodes,
+            order,
+            joints,
+            clips,
+        }
+    }
+
+    /// Computes the bone palette for `clip` at `time` seconds, or the rest
+    /// pose when no clip is given.
+    fn pose(&self, clip: Option<&AnimationClip>, time: f32) -> Vec<Mat4> {
+        let mut translations: Vec<Vec3> = self.nodes.iter().map(|n| n.translation).collect();
+        let mut rotations: Vec<Quat> = self.nodes.iter().map(|n| n.rotation).collect();
+        let mut scales: Vec<Vec3> = self.nodes.iter().map(|n| n.scale).collect();
+
+        if let Some(clip) = clip {
+            for channel in &clip.channels {
+                let Some((i, j, t)) = keyframe(&channel.times, time, channel.step) else {
+                    continue;
+                };
+                match &channel.values {
+                    ChannelValues::Translation(v) => {
+                        translations[channel.node] = v[i].lerp(v[j], t);
+                    }
+                    ChannelValues::Rotation(v) => {
+                        rotations[channel.node] = v[i].slerp(v[j], t);
+                    }
+                    ChannelValues::Scale(v) => {
+                        scales[channel.node] = v[i].lerp(v[j], t);
+                    }
+                }
+            }
+        }
+
+        let mut globals = vec![Mat4::IDENTITY; self.nodes.len()];
+        for &index in &self.order {
+            let local = Mat4::from_scale_rotation_translation(
+                scales[index],
+                rotations[index],
+                translations[index],
+            );
+            globals[index] = match self.nodes[index].parent {
+                Some(parent) => globals[parent] * local,
+                None => local,
+            };
+        }
+
+        self.joints
+            .iter()
+            .map(|(node, inverse_bind)| globals[*node] * *inverse_bind)
+            .collect()
+    }
+}
+
+fn read_clip(
+    animation: &gltf::Animation,
+    index: usize,
+    buffers: &[gltf::buffer::Data],
+) -> AnimationClip {
+    let mut channels = Vec::new();
+    let mut duration = 0.0f32;
+
+    for channel in animation.channels() {
+        let reader = channel.reader(|buffer| Some(&buffers[buffer.index()]));
+        let (Some(inputs), Some(outputs)) = (reader.read_inputs(), reader.read_outputs()) else {
+            continue;
+        };
+        let times: Vec<f32> = inputs.collect();
+        let interpolation = channel.sampler().interpolation();
+
+        let cubic = interpolation == Interpolation::CubicSpline;
+        let values = match outputs {
+            ReadOutputs::Translations(v) => ChannelValues::Translation(keyframe_values(
+                v.map(Vec3::from_array).collect(),
+                times.len(),
+                cubic,
+            )),
+            ReadOutputs::Rotations(v) => ChannelValues::Rotation(keyframe_values(
+                v.into_f32()
+                    .map(|r| Quat::from_array(r).normalize())
+                    .collect(),
+                times.len(),
+                cubic,
+            )),
+            ReadOutputs::Scales(v) => ChannelValues::Scale(keyframe_values(
+                v.map(Vec3::from_array).collect(),
+                times.len(),
+                cubic,
+            )),
+            ReadOutputs::MorphTargetWeights(_) => continue,
+        };
+
+        let value_count = match &values {
+            ChannelValues::Translation(v) | ChannelValues::Scale(v) => v.len(),
+            ChannelValues::Rotation(v) => v.len(),
+        };
+        if times.is_empty() || value_count != times.len() {
+            continue;
+        }
+
+        duration = duration.max(times.last().copied().unwrap_or(0.0));
+        channels.push(Channel {
+            node: channel.target().node().index(),
+            times,
+            values,
+            step: interpolation == Interpolation::Step,
+        });
+    }
+
+    AnimationClip {
+        name: animation
+            .name()
+            .map(str::to_owned)
+            .unwrap_or_else(|| format!("animation_{}", index)),
+        duration,
+        channels,
+    }
+}
+
+/// Cubic spline samplers store an in-tangent, value and out-tangent per
+/// keyframe; only the values are kept and the clip is interpolated linearly.
+fn keyframe_values<T: Copy>(values: Vec<T>, keys: usize, cubic: bool) -> Vec<T> {
+    if cubic && values.len() == keys * 3 {
+        values.chunks(3).map(|c| c[1]).collect()
+    } else {
+        values
+    }
+}
+
+/// Finds the keyframes surrounding `time` and the blend factor between them.
+fn keyframe(times: &[f32], time: f32, step: bool) -> Option<(usize, usize, f32)> {
+    let last = times.len().checked_sub(1)?;
+    let next = times.partition_point(|&t| t <= time);
+    if next == 0 {
+        return Some((0, 0, 0.0));
+    }
+    if next > last {
+        return Some((last, last, 0.0));
+    }
+    let prev = next - 1;
+    if step {
+        return Some((prev, prev, 0.0));
+    }
+    let span = times[next] - times[prev];
+    let t = if span > 0.0 {
+        (time - times[prev]) / span
+    } else {
+        0.0
+    };
+    Some((prev, next, t))
+}
+
+/// Plays the animation clips of a skinned mesh. Attach it to an entity with
+/// `Entity.set_animator` and the engine advances it every frame.
+#[pyclass(unsendable)]
+pub struct Animator {
+    skeleton: Rc<Skeleton>,
+    clip: Option<usize>,
+    #[pyo3(get, set)]
+    pub time: f32,
+    #[pyo3(get, set)]
+    pub speed: f32,
+    #[pyo3(get, set)]
+    pub looping: bool,
+    #[pyo3(get)]
+    pub playing: bool,
+    bones: Vec<Mat4>,
+}
+
+#[pymethods]
+impl Animator {
+    #[new]
+    pub fn new(mesh: PyRef<Mesh>) -> PyResult<Self> {
+        let skeleton = mesh.skeleton.clone().ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err("Mesh has no skeleton to animate")
+        })?;
+        let bones = skeleton.pose(None, 0.0);
+        Ok(Animator {
+            skeleton,
+            clip: None,
+            time: 0.0,
+            speed: 1.0,
+            looping: true,
+            playing: false,
+            bones,
+        })
+    }
+
+    /// Names of the clips in the source file, in file order.
+    #[getter]
+    pub fn clips(&self) -> Vec<String> {
+        self.skeleton.clips.iter().map(|c| c.name.clone()).collect()
+    }
+
+    #[getter]
+    pub fn clip(&self) -> Option<String> {
+        self.clip.map(|i| self.skeleton.clips[i].name.clone())
+    }
+
+    #[getter]
+    pub fn duration(&self) -> f32 {
+        self.clip
+            .map(|i| self.skeleton.clips[i].duration)
+            .unwrap_or(0.0)
+    }
+
+    /// Starts `name` from the beginning.
+    #[pyo3(signature = (name, looping=true))]
+    pub fn play(&mut self, name: &str, looping: bool) -> PyResult<()> {
+        let index = self
+            .skeleton
+            .clips
+            .iter()
+            .position(|c| c.name == name)
+            .ok_or_else(|| {
+                pyo3::exceptions::PyValueError::new_err(format!("Unknown animation: {}", name))
+            })?;
+        self.clip = Some(index);
+        self.time = 0.0;
+        self.looping = looping;
+        self.playing = true;
+        self.refresh();
+        Ok(())
+    }
+
+    /// Freezes the current pose.
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn resume(&mut self) {
+        self.playing = self.clip.is_some();
+    }
+
+    /// Stops playback and returns to the rest pose.
+    pub fn stop(&mut self) {
+        self.clip = None;
+        self.time = 0.0;
+        self.playing = false;
+        self.refresh();
+    }
+
+    /// Advances the current clip by `delta_time` seconds scaled by `speed`.
+    pub fn update(&mut self, delta_time: f32) {
+        if !self.playing {
+            return;
+        }
+        let duration = self.duration();
+        self.time += delta_time * self.speed;
+        if self.looping && duration > 0.0 {
+            self.time = self.time.rem_euclid(duration);
+        } else if self.time >= duration || self.time < 0.0 {
+            self.time = self.time.clamp(0.0, duration);
+            self.playing = false;
+        }
+        self.refresh();
+    }
+}
+
+impl Animator {
+    /// The bone palette for the current pose.
+    pub fn bones(&self) -> &[Mat4] {
+        &self.bones
+    }
+
+    fn refresh(&mut self) {
+        let clip = self.clip.map(|i| &self.skeleton.clips[i]);
+        self.bones = self.skeleton.pose(clip, self.time);
+    }
+}
+
+/// Uploads a bone palette to `shader`, or turns skinning off when `bones` is
+/// `None`.
+pub fn bind_bones(shader: &Shader, bones: Option<&[Mat4]>) {
+    match bones {
+        Some(bones) => {
+            shader.set_bool("skinned", true);
+            shader.set_mat4_array("bones", &bones[..bones.len().min(MAX_BONES)]);
+        }
+        None => shader.set_bool("skinned", false),
+    }
+}
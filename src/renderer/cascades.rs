@@ -0,0 +1,57 @@
+use glam::{Mat4, Vec3, Vec4};
+
+/// Size of the cascade uniform arrays in `lighting.fsh`.
+pub const MAX_CASCADES: usize = 4;
+
+/// How far beyond a cascade, towards the light, occluders still cast into it.
+const CASTER_MARGIN: f32 = 50.0;
+
+/// Splits `[near, far]` into `count` ranges, blending logarithmic and uniform
+/// spacing by `lambda` (1 is fully logarithmic). Returns the far distance of
+/// each cascade.
+pub fn split_distances(near: f32, far: f32, count: usize, lambda: f32) -> Vec<f32> {
+    (1..=count)
+        .map(|i| {
+            let p = i as f32 / count as f32;
+            let log = near * (far / near).powf(p);
+            let uniform = near + (far - near) * p;
+            lambda * log + (1.0 - lambda) * uniform
+        })
+        .collect()
+}
+
+/// Builds an orthographic light-space matrix enclosing `corners`. The box is
+/// sized from a bounding sphere and snapped to whole shadow texels so shadow
+/// edges don't shimmer as the camera moves or turns.
+pub fn cascade_matrix(corners: &[Vec3; 8], toward_light: Vec3, size: u32) -> Mat4 {
+    let center = corners.iter().copied().sum::<Vec3>() / 8.0;
+    let radius = corners
+        .iter()
+        .map(|c| c.distance(center))
+        .fold(0.0f32, f32::max)
+        .ceil();
+
+    let up = if toward_light.y.abs() > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    };
+    let eye = center + toward_light * (radius + CASTER_MARGIN);
+    let view = Mat4::look_at_rh(eye, center, up);
+    let mut projection = Mat4::orthographic_rh_gl(
+        -radius,
+        radius,
+        -radius,
+        radius,
+        0.0,
+        2.0 * radius + CASTER_MARGIN,
+    );
+
+    let half_size = size as f32 / 2.0;
+    let origin = (projection * view) * Vec4::W * half_size;
+    let offset = (origin.round() - origin) / half_size;
+    projection.w_axis.x += offset.x;
+    projection.w_axis.y += offset.y;
+
+    projection * view
+}
@@ -0,0 +1,29 @@
+use glam::{Mat4, Vec3, Vec4};
+
+/// Clip planes of a view-projection matrix, pointing inwards.
+pub struct Frustum {
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    pub fn from_matrix(view_projection: &Mat4) -> Self {
+        let r0 = view_projection.row(0);
+        let r1 = view_projection.row(1);
+        let r2 = view_projection.row(2);
+        let r3 = view_projection.row(3);
+
+        let planes = [r3 + r0, r3 - r0, r3 + r1, r3 - r1, r3 + r2, r3 - r2].map(|p| {
+            let length = p.truncate().length();
+            if length > 0.0 { p / length } else { p }
+        });
+
+        Frustum { planes }
+    }
+
+    /// True unless the sphere lies entirely outside one of the planes.
+    pub fn intersects_sphere(&self, center: Vec3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.truncate().dot(center) + plane.w >= -radius)
+    }
+}
@@ -0,0 +1,176 @@
+use crate::renderer::texture::Texture;
+use glam::Vec3;
+use pyo3::prelude::*;
+
+#[pyclass]
+pub struct Material {
+    pub albedo: Vec3,
+    #[pyo3(get, set)]
+    pub metallic: f32,
+    #[pyo3(get, set)]
+    pub roughness: f32,
+    /// Light the surface gives off on its own, in linear HDR units. It glows
+    /// and blooms but does not light other surfaces.
+    pub emissive: Vec3,
+    /// Tints the reflectance of non-metals, which is otherwise a white 4%.
+    /// Metals take their specular color from the albedo instead.
+    pub specular_color: Vec3,
+    #[pyo3(get)]
+    pub albedo_texture: Option<Py<Texture>>,
+    #[pyo3(get)]
+    pub metallic_roughness_texture: Option<Py<Texture>>,
+    #[pyo3(get)]
+    pub normal_texture: Option<Py<Texture>>,
+    #[pyo3(get)]
+    pub height_texture: Option<Py<Texture>>,
+    /// Depth of the parallax effect in UV units; white texels are the top.
+    #[pyo3(get, set)]
+    pub height_scale: f32,
+    /// Ray-march layers used by parallax occlusion mapping. More layers
+    /// remove stair-stepping at grazing angles but cost more per pixel.
+    #[pyo3(get, set)]
+    pub parallax_steps: u32,
+}
+
+#[pymethods]
+impl Material {
+    #[new]
+    pub fn new(r: f32, g: f32, b: f32, metallic: f32, roughness: f32) -> Self {
+        Material {
+            albedo: Vec3::new(r, g, b),
+            metallic: metallic.clamp(0.0, 1.0),
+            roughness: roughness.clamp(0.0, 1.0),
+            emissive: Vec3::ZERO,
+            specular_color: Vec3::ONE,
+            albedo_texture: None,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            height_texture: None,
+            height_scale: 0.05,
+            parallax_steps: 16,
+        }
+    }
+
+    #[getter]
+    pub fn get_albedo(&self) -> (f32, f32, f32) {
+        (self.albedo.x, self.albedo.y, self.albedo.z)
+    }
+
+    #[setter]
+    pub fn set_albedo(&mut self, albedo: (f32, f32, f32)) {
+        self.albedo = Vec3::new(albedo.0, albedo.1, albedo.2);
+    }
+
+    #[getter]
+    pub fn get_emissive(&self) -> (f32, f32, f32) {
+        self.emissive.into()
+    }
+
+    #[setter]
+    pub fn set_emissive(&mut self, emissive: (f32, f32, f32)) {
+        self.emissive = Vec3::from(emissive).max(Vec3::ZERO);
+    }
+
+    #[getter]
+    pub fn get_specular_color(&self) -> (f32, f32, f32) {
+        self.specular_color.into()
+    }
+
+    #[setter]
+    pub fn set_specular_color(&mut self, color: (f32, f32, f32)) -> PyResult<()> {
+        let color = Vec3::from(color);
+        if !color.is_finite() {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Specular color must be finite, got {}",
+                color
+            )));
+        }
+        self.specular_color = color.clamp(Vec3::ZERO, Vec3::ONE);
+        Ok(())
+    }
+
+    /// Blinn-Phong style specular exponent, mapped onto `roughness`: higher
+    /// values give smaller, sharper highlights.
+    #[getter]
+    pub fn get_shininess(&self) -> f32 {
+        let roughness = self.roughness.max(0.01);
+        2.0 / (roughness * roughness) - 2.0
+    }
+
+    #[setter]
+    pub fn set_shininess(&mut self, shininess: f32) -> PyResult<()> {
+        if !shininess.is_finite() || shininess < 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Shininess must be a non-negative number, got {}",
+                shininess
+            )));
+        }
+        self.roughness = (2.0 / (shininess + 2.0)).sqrt();
+        Ok(())
+    }
+
+    pub fn set_albedo_texture(&mut self, texture: Option<Py<Texture>>) {
+        self.albedo_texture = texture;
+    }
+
+    /// Roughness is read from the green channel and metallic from the blue
+    /// channel, matching the glTF convention.
+    pub fn set_metallic_roughness_texture(&mut self, texture: Option<Py<Texture>>) {
+        self.metallic_roughness_texture = texture;
+    }
+
+    /// Tangent-space normal map (OpenGL convention, +Y up). It perturbs the
+    /// surface normal along the mesh tangents during the geometry pass.
+    pub fn set_normal_texture(&mut self, texture: Option<Py<Texture>>) {
+        self.normal_texture = texture;
+    }
+
+    /// Height map for parallax occlusion mapping, read from the red channel.
+    /// Setting one offsets every texture lookup along the view direction.
+    pub fn set_height_texture(&mut self, texture: Option<Py<Texture>>) {
+        self.height_texture = texture;
+    }
+}
+
+/// Material values with textures resolved to GL ids (0 when unset), so they
+/// can be bound without borrowing the Python objects.
+#[derive(Clone, Copy)]
+pub struct MaterialParams {
+    pub albedo: Vec3,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: Vec3,
+    pub specular_color: Vec3,
+    pub albedo_map: u32,
+    pub metallic_roughness_map: u32,
+    pub normal_map: u32,
+    pub height_map: u32,
+    pub height_scale: f32,
+    pub parallax_steps: u32,
+}
+
+impl Material {
+    pub fn resolve(&self, py: Python) -> MaterialParams {
+        let texture_id =
+            |texture: &Option<Py<Texture>>| texture.as_ref().map(|t| t.borrow(py).id).unwrap_or(0);
+        MaterialParams {
+            albedo: self.albedo,
+            metallic: self.metallic,
+            roughness: self.roughness,
+            emissive: self.emissive,
+            specular_color: self.specular_color,
+            albedo_map: texture_id(&self.albedo_texture),
+            metallic_roughness_map: texture_id(&self.metallic_roughness_texture),
+            normal_map: texture_id(&self.normal_texture),
+            height_map: texture_id(&self.height_texture),
+            height_scale: self.height_scale.max(0.0),
+            parallax_steps: self.parallax_steps.clamp(1, 128),
+        }
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material::new(1.0, 1.0, 1.0, 0.0, 0.5)
+    }
+}
@@ -1,21 +1,51 @@
-use crate::renderer::texture::{Texture, create_white_texture};
+use crate::renderer::animation::{MAX_BONES, Skeleton};
+use crate::renderer::material::Material;
+use crate::renderer::texture::{
+    Texture, TextureFilter, TextureWrap, upload_rgba_texture, white_texture,
+};
 use gl;
-use glam::{Vec2, Vec3};
+use glam::{Mat3, Mat4, Vec2, Vec3};
 use pyo3::Python;
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::mem;
 use std::path::Path;
 use std::ptr;
+use std::rc::Rc;
 use tobj;
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct Vertex {
     pub position: Vec3,
     pub normal: Vec3,
     pub tex_coords: Vec2,
+    pub tangent: Vec3,
+    /// Indices into the bone palette, used when `weights` sum above zero.
+    pub joints: [u32; 4],
+    pub weights: [f32; 4],
+    /// Baked ambient occlusion; scales the ambient term, 1.0 = unoccluded.
+    pub ao: f32,
 }
 
+impl Vertex {
+    /// An unskinned, unoccluded vertex; tangents are filled in by
+    /// `compute_tangents`.
+    pub fn new(position: Vec3, normal: Vec3, tex_coords: Vec2) -> Self {
+        Self {
+            position,
+            normal,
+            tex_coords,
+            tangent: Vec3::ZERO,
+            joints: [0; 4],
+            weights: [0.0; 4],
+            ao: 1.0,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct SubMesh {
     pub start_index: i32,
     pub index_count: i32,
@@ -32,11 +62,96 @@ pub struct Mesh {
 
     #[pyo3(get)]
     pub texture_id: u32,
+    // Keeps the texture alive (and its GL id valid) while the mesh uses it.
+    texture: Option<Py<Texture>>,
 
     #[pyo3(get)]
     pub color: Option<(u8, u8, u8, u8)>,
 
+    #[pyo3(get)]
+    pub material: Option<Py<Material>>,
+
+    /// Multiplies texture coordinates, so values above 1 tile the texture.
+    pub uv_scale: Vec2,
+    /// Added to the scaled texture coordinates.
+    pub uv_offset: Vec2,
+    /// Texture-space units per second that `uv_offset` moves by, following
+    /// the engine's elapsed time.
+    pub uv_scroll: Vec2,
+
+    /// Local-space axis-aligned bounds.
+    pub bounds_min: Vec3,
+    pub bounds_max: Vec3,
+    /// Local-space bounding sphere used for frustum culling.
+    pub bounds_center: Vec3,
+    pub bounds_radius: f32,
+
     pub submeshes: Vec<SubMesh>,
+    /// Submesh textures loaded along with the model, freed with the mesh.
+    /// Never includes the shared white texture.
+    owned_textures: Vec<u32>,
+
+    /// Joint hierarchy and clips for meshes loaded from skinned glTF files.
+    pub skeleton: Option<Rc<Skeleton>>,
+
+    /// CPU copy of the geometry, kept only for meshes built with `dynamic`.
+    dynamic_data: Option<DynamicData>,
+
+    /// How the mesh was created, so saved scenes can rebuild it. `None` for
+    /// meshes built from raw data.
+    pub source: Option<MeshSource>,
+}
+
+/// A primitive or file a mesh can be rebuilt from.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MeshSource {
+    Cube,
+    Plane,
+    Icosphere {
+        subdivisions: u32,
+    },
+    Cylinder {
+        segments: u32,
+    },
+    Cone {
+        segments: u32,
+    },
+    Torus {
+        major_segments: u32,
+        minor_segments: u32,
+    },
+    Obj {
+        path: String,
+        filter: String,
+    },
+    Gltf {
+        path: String,
+    },
+}
+
+impl MeshSource {
+    /// Builds a fresh mesh from this source.
+    pub fn load(&self) -> PyResult<Mesh> {
+        Ok(match self {
+            MeshSource::Cube => Mesh::cube(),
+            MeshSource::Plane => Mesh::plane(),
+            MeshSource::Icosphere { subdivisions } => Mesh::icosphere(*subdivisions),
+            MeshSource::Cylinder { segments } => Mesh::cylinder(*segments),
+            MeshSource::Cone { segments } => Mesh::cone(*segments),
+            MeshSource::Torus {
+                major_segments,
+                minor_segments,
+            } => Mesh::torus(*major_segments, *minor_segments),
+            MeshSource::Obj { path, filter } => Mesh::from_obj(path, filter)?,
+            MeshSource::Gltf { path } => Mesh::from_gltf(path),
+        })
+    }
+}
+
+struct DynamicData {
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
 }
 
 #[pymethods]
@@ -63,117 +178,537 @@ impl Mesh {
             if let (Some(r), Some(g), Some(b), Some(a)) = (r, g, b, a) {
                 self.color = Some((r, g, b, a));
             }
+            self.texture_id = texture.borrow(py).id;
         });
+        self.texture = Some(texture);
     }
 
     pub fn clear_texture(&mut self) {
         self.color = None;
+        self.texture_id = 0;
+        self.texture = None;
     }
 
-    #[staticmethod]
-    pub fn from_obj(file_path: &str) -> Self {
-        match tobj::load_obj(
-            file_path,
-            &tobj::LoadOptions {
-                triangulate: true,
-                single_index: true,
-                ..Default::default()
-            },
-        ) {
-            Ok((models, materials)) => {
-                let mut all_vertices: Vec<Vertex> = Vec::new();
-                let mut all_indices: Vec<u32> = Vec::new();
-                let mut submeshes: Vec<SubMesh> = Vec::new();
-
-                let obj_dir = Path::new(file_path)
-                    .parent()
-                    .unwrap_or_else(|| Path::new("."));
-
-                let loaded_materials = match materials {
-                    Ok(mats) if !mats.is_empty() => {
-                        println!("Loaded {} materials from OBJ", mats.len());
-                        Some(mats)
-                    }
-                    _ => None,
-                };
+    pub fn set_material(&mut self, material: Option<Py<Material>>) {
+        self.material = material;
+    }
 
-                let material_textures = if let Some(ref mats) = loaded_materials {
-                    let mut tex_map = HashMap::new();
-                    for (idx, mat) in mats.iter().enumerate() {
-                        if let Some(ref diffuse_texture) = mat.diffuse_texture {
-                            let texture_path = obj_dir.join(diffuse_texture);
-                            if let Some(tex_id) = load_texture_from_path(&texture_path) {
-                                tex_map.insert(idx, tex_id);
+    #[getter]
+    pub fn get_uv_scale(&self) -> (f32, f32) {
+        (self.uv_scale.x, self.uv_scale.y)
+    }
+
+    #[setter]
+    pub fn set_uv_scale(&mut self, uv_scale: (f32, f32)) {
+        self.uv_scale = Vec2::new(uv_scale.0, uv_scale.1);
+    }
+
+    #[getter]
+    pub fn get_uv_offset(&self) -> (f32, f32) {
+        self.uv_offset.into()
+    }
+
+    #[setter]
+    pub fn set_uv_offset(&mut self, uv_offset: (f32, f32)) {
+        self.uv_offset = Vec2::from(uv_offset);
+    }
+
+    #[getter]
+    pub fn get_uv_scroll(&self) -> (f32, f32) {
+        self.uv_scroll.into()
+    }
+
+    #[setter(uv_scroll)]
+    pub fn set_uv_scroll_py(&mut self, speed: (f32, f32)) {
+        self.set_uv_scroll(speed.0, speed.1);
+    }
+
+    /// Scrolls the texture by `speed_u`, `speed_v` texture widths per second,
+    /// for conveyor belts, water and lava. Zero speeds stop it.
+    pub fn set_uv_scroll(&mut self, speed_u: f32, speed_v: f32) {
+        self.uv_scroll = Vec2::new(speed_u, speed_v);
+    }
+
+    /// Replaces the vertex positions (xyz) of a dynamic mesh in place.
+    /// Normals are recomputed from the triangles unless given.
+    #[pyo3(signature = (positions, normals=None))]
+    pub fn update_vertices(
+        &mut self,
+        positions: Vec<f32>,
+        normals: Option<Vec<f32>>,
+    ) -> PyResult<()> {
+        if self.is_deleted() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "mesh has been deleted",
+            ));
+        }
+        let Some(data) = self.dynamic_data.as_mut() else {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "mesh was not created with dynamic=True",
+            ));
+        };
+        let expected = data.vertices.len() * 3;
+        if positions.len() != expected {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "expected {} position components, got {}",
+                expected,
+                positions.len()
+            )));
+        }
+        if let Some(normals) = &normals
+            && normals.len() != expected
+        {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "expected {} normal components, got {}",
+                expected,
+                normals.len()
+            )));
+        }
+
+        for (vertex, position) in data.vertices.iter_mut().zip(positions.chunks_exact(3)) {
+            vertex.position = Vec3::from_slice(position);
+        }
+        match &normals {
+            Some(normals) => {
+                for (vertex, normal) in data.vertices.iter_mut().zip(normals.chunks_exact(3)) {
+                    vertex.normal = Vec3::from_slice(normal);
+                }
+            }
+            None => compute_normals(&mut data.vertices, &data.indices),
+        }
+        compute_tangents(&mut data.vertices, &data.indices);
+
+        (self.bounds_min, self.bounds_max) = compute_aabb(&data.vertices);
+        (self.bounds_center, self.bounds_radius) = compute_bounding_sphere(&data.vertices);
+
+        if data.vertices.is_empty() {
+            return Ok(());
+        }
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferSubData(
+                gl::ARRAY_BUFFER,
+                0,
+                (data.vertices.len() * mem::size_of::<Vertex>()) as isize,
+                data.vertices.as_ptr() as *const _,
+            );
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+        Ok(())
+    }
+
+    /// Local-space `(min, max)` corners of the axis-aligned bounding box.
+    pub fn bounds(&self) -> ((f32, f32, f32), (f32, f32, f32)) {
+        (self.bounds_min.into(), self.bounds_max.into())
+    }
+
+    /// Frees the GL buffers, and any textures loaded from the model file, now
+    /// instead of when the mesh is garbage collected. A deleted mesh draws
+    /// nothing.
+    pub fn delete(&mut self) {
+        if self.is_deleted() {
+            return;
+        }
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteBuffers(1, &self.ebo);
+            if !self.owned_textures.is_empty() {
+                gl::DeleteTextures(
+                    self.owned_textures.len() as i32,
+                    self.owned_textures.as_ptr(),
+                );
+            }
+        }
+        self.owned_textures.clear();
+        self.vao = 0;
+        self.vbo = 0;
+        self.ebo = 0;
+        self.index_count = 0;
+        self.submeshes.clear();
+        self.dynamic_data = None;
+    }
+
+    #[getter]
+    pub fn is_deleted(&self) -> bool {
+        self.vao == 0
+    }
+
+    /// Local-space `(center, radius)` of a sphere enclosing every vertex.
+    pub fn bounding_sphere(&self) -> ((f32, f32, f32), f32) {
+        (self.bounds_center.into(), self.bounds_radius)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (file_path, filter = "linear"))]
+    pub fn from_obj(file_path: &str, filter: &str) -> PyResult<Self> {
+        let source = MeshSource::Obj {
+            path: file_path.to_string(),
+            filter: filter.to_string(),
+        };
+        let filter = TextureFilter::from_name(filter)?;
+        Ok(
+            match tobj::load_obj(
+                file_path,
+                &tobj::LoadOptions {
+                    triangulate: true,
+                    single_index: true,
+                    ..Default::default()
+                },
+            ) {
+                Ok((models, materials)) => {
+                    let mut all_vertices: Vec<Vertex> = Vec::new();
+                    let mut all_indices: Vec<u32> = Vec::new();
+                    let mut submeshes: Vec<SubMesh> = Vec::new();
+
+                    let obj_dir = Path::new(file_path)
+                        .parent()
+                        .unwrap_or_else(|| Path::new("."));
+
+                    let loaded_materials = match materials {
+                        Ok(mats) if !mats.is_empty() => {
+                            println!("Loaded {} materials from OBJ", mats.len());
+                            Some(mats)
+                        }
+                        _ => None,
+                    };
+
+                    let material_textures = if let Some(ref mats) = loaded_materials {
+                        let mut tex_map = HashMap::new();
+                        for (idx, mat) in mats.iter().enumerate() {
+                            if let Some(ref diffuse_texture) = mat.diffuse_texture {
+                                let texture_path = obj_dir.join(diffuse_texture);
+                                if let Some(tex_id) = load_texture_from_path(&texture_path, filter)
+                                {
+                                    tex_map.insert(idx, tex_id);
+                                }
                             }
                         }
+                        Some(tex_map)
+                    } else {
+                        None
+                    };
+
+                    for model in models {
+                        let mesh = &model.mesh;
+                        let positions = &mesh.positions;
+                        let normals = &mesh.normals;
+                        let texcoords = &mesh.texcoords;
+                        // Baked AO comes in as grayscale vertex colors.
+                        let colors = &mesh.vertex_color;
+
+                        let start_index = all_indices.len() as i32;
+
+                        for &idx in &mesh.indices {
+                            let i = idx as usize;
+                            let px = *positions.get(i * 3).unwrap_or(&0.0);
+                            let py = *positions.get(i * 3 + 1).unwrap_or(&0.0);
+                            let pz = *positions.get(i * 3 + 2).unwrap_or(&0.0);
+
+                            let nx = *normals.get(i * 3).unwrap_or(&0.0);
+                            let ny = *normals.get(i * 3 + 1).unwrap_or(&0.0);
+                            let nz = *normals.get(i * 3 + 2).unwrap_or(&0.0);
+
+                            let u = *texcoords.get(i * 2).unwrap_or(&0.0);
+                            let v = *texcoords.get(i * 2 + 1).unwrap_or(&0.0);
+
+                            let ao = colors
+                                .get(i * 3..i * 3 + 3)
+                                .map(|c| (c[0] + c[1] + c[2]) / 3.0)
+                                .unwrap_or(1.0);
+
+                            all_vertices.push(Vertex {
+                                ao,
+                                ..Vertex::new(
+                                    Vec3::new(px, py, pz),
+                                    Vec3::new(nx, ny, nz),
+                                    Vec2::new(u, v),
+                                )
+                            });
+                            all_indices.push((all_vertices.len() - 1) as u32);
+                        }
+
+                        let index_count = (all_indices.len() as i32) - start_index;
+
+                        let mat_id = mesh.material_id.unwrap_or(0);
+
+                        let texture_id = material_textures
+                            .as_ref()
+                            .and_then(|map| map.get(&mat_id).copied())
+                            .unwrap_or_else(white_texture);
+
+                        let color = loaded_materials
+                            .as_ref()
+                            .and_then(|mats| mats.get(mat_id))
+                            .and_then(|mat| mat.diffuse.as_ref())
+                            .map(|diffuse| {
+                                let r = (diffuse[0] * 255.0) as u8;
+                                let g = (diffuse[1] * 255.0) as u8;
+                                let b = (diffuse[2] * 255.0) as u8;
+                                let a = 255u8;
+                                (r, g, b, a)
+                            });
+
+                        submeshes.push(SubMesh {
+                            start_index,
+                            index_count,
+                            texture_id,
+                            color,
+                        });
                     }
-                    Some(tex_map)
-                } else {
-                    None
-                };
 
-                for model in models {
-                    let mesh = &model.mesh;
-                    let positions = &mesh.positions;
-                    let normals = &mesh.normals;
-                    let texcoords = &mesh.texcoords;
+                    let mut mesh = Mesh::new_with_texture(all_vertices, all_indices, submeshes);
+                    mesh.owned_textures = material_textures
+                        .map(|map| map.into_values().collect())
+                        .unwrap_or_default();
+                    mesh.with_source(source)
+                }
+                Err(_) => Mesh::new(vec![], vec![]).with_source(source),
+            },
+        )
+    }
 
-                    let start_index = all_indices.len() as i32;
+    /// Loads every mesh in the default scene of a `.gltf` or `.glb` file, with
+    /// node transforms baked in and one submesh per primitive. Skinned
+    /// primitives keep their bind pose and can be animated with an `Animator`.
+    #[staticmethod]
+    pub fn from_gltf(file_path: &str) -> Self {
+        let source = MeshSource::Gltf {
+            path: file_path.to_string(),
+        };
+        let (document, buffers, images) = match gltf::import(file_path) {
+            Ok(import) => import,
+            Err(e) => {
+                eprintln!("Failed to load glTF '{}': {}", file_path, e);
+                return Mesh::new(vec![], vec![]).with_source(source);
+            }
+        };
 
-                    for &idx in &mesh.indices {
-                        let i = idx as usize;
-                        let px = *positions.get(i * 3).unwrap_or(&0.0);
-                        let py = *positions.get(i * 3 + 1).unwrap_or(&0.0);
-                        let pz = *positions.get(i * 3 + 2).unwrap_or(&0.0);
+        let mut all_vertices: Vec<Vertex> = Vec::new();
+        let mut all_indices: Vec<u32> = Vec::new();
+        let mut submeshes: Vec<SubMesh> = Vec::new();
+        let mut image_textures: HashMap<usize, u32> = HashMap::new();
+
+        // Every skin's joints go into one bone palette; a skin's vertices
+        // offset their joint indices by where that skin starts.
+        let mut joints: Vec<(usize, Mat4)> = Vec::new();
+        let mut skin_offsets: Vec<u32> = Vec::new();
+        for skin in document.skins() {
+            skin_offsets.push(joints.len() as u32);
+            let inverse_binds: Vec<Mat4> = skin
+                .reader(|buffer| Some(&buffers[buffer.index()]))
+                .read_inverse_bind_matrices()
+                .map(|m| m.map(|m| Mat4::from_cols_array_2d(&m)).collect())
+                .unwrap_or_default();
+            for (i, joint) in skin.joints().enumerate() {
+                let inverse_bind = inverse_binds.get(i).copied().unwrap_or(Mat4::IDENTITY);
+                joints.push((joint.index(), inverse_bind));
+            }
+        }
 
-                        let nx = *normals.get(i * 3).unwrap_or(&0.0);
-                        let ny = *normals.get(i * 3 + 1).unwrap_or(&0.0);
-                        let nz = *normals.get(i * 3 + 2).unwrap_or(&0.0);
+        let mut stack: Vec<(gltf::Node, Mat4)> = match document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+        {
+            Some(scene) => scene.nodes().map(|n| (n, Mat4::IDENTITY)).collect(),
+            None => document.nodes().map(|n| (n, Mat4::IDENTITY)).collect(),
+        };
 
-                        let u = *texcoords.get(i * 2).unwrap_or(&0.0);
-                        let v = *texcoords.get(i * 2 + 1).unwrap_or(&0.0);
+        while let Some((node, parent_transform)) = stack.pop() {
+            let transform = parent_transform * Mat4::from_cols_array_2d(&node.transform().matrix());
 
-                        all_vertices.push(Vertex {
-                            position: Vec3::new(px, py, pz),
-                            normal: Vec3::new(nx, ny, nz),
-                            tex_coords: Vec2::new(u, v),
-                        });
-                        all_indices.push((all_vertices.len() - 1) as u32);
-                    }
+            for child in node.children() {
+                stack.push((child, transform));
+            }
 
-                    let index_count = (all_indices.len() as i32) - start_index;
-
-                    let mat_id = mesh.material_id.unwrap_or(0);
-
-                    let texture_id = material_textures
-                        .as_ref()
-                        .and_then(|map| map.get(&mat_id).copied())
-                        .unwrap_or_else(create_white_texture);
-
-                    let color = loaded_materials
-                        .as_ref()
-                        .and_then(|mats| mats.get(mat_id))
-                        .and_then(|mat| mat.diffuse.as_ref())
-                        .map(|diffuse| {
-                            let r = (diffuse[0] * 255.0) as u8;
-                            let g = (diffuse[1] * 255.0) as u8;
-                            let b = (diffuse[2] * 255.0) as u8;
-                            let a = 255u8;
-                            (r, g, b, a)
-                        });
+            // Skinned vertices are placed by their joints, so the mesh node's
+            // own transform is ignored.
+            let skin_offset = node.skin().map(|skin| skin_offsets[skin.index()]);
+            let vertex_transform = if skin_offset.is_some() {
+                Mat4::IDENTITY
+            } else {
+                transform
+            };
+            let normal_matrix = Mat3::from_mat4(vertex_transform).inverse().transpose();
+
+            let Some(mesh) = node.mesh() else {
+                continue;
+            };
+
+            for primitive in mesh.primitives() {
+                if primitive.mode() != gltf::mesh::Mode::Triangles {
+                    continue;
+                }
 
-                    submeshes.push(SubMesh {
-                        start_index,
-                        index_count,
-                        texture_id,
-                        color,
+                let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+                let Some(positions) = reader.read_positions() else {
+                    continue;
+                };
+                let positions: Vec<[f32; 3]> = positions.collect();
+                let normals: Vec<[f32; 3]> = reader
+                    .read_normals()
+                    .map(|n| n.collect())
+                    .unwrap_or_default();
+                let texcoords: Vec<[f32; 2]> = reader
+                    .read_tex_coords(0)
+                    .map(|t| t.into_f32().collect())
+                    .unwrap_or_default();
+                let (vertex_joints, vertex_weights): (Vec<[u16; 4]>, Vec<[f32; 4]>) =
+                    match skin_offset {
+                        Some(_) => (
+                            reader
+                                .read_joints(0)
+                                .map(|j| j.into_u16().collect())
+                                .unwrap_or_default(),
+                            reader
+                                .read_weights(0)
+                                .map(|w| w.into_f32().collect())
+                                .unwrap_or_default(),
+                        ),
+                        None => (Vec::new(), Vec::new()),
+                    };
+                let occlusion = read_vertex_ao(&primitive, &buffers);
+
+                let base_vertex = all_vertices.len() as u32;
+                for (i, p) in positions.iter().enumerate() {
+                    let normal = normals
+                        .get(i)
+                        .map(|n| (normal_matrix * Vec3::from_array(*n)).normalize_or_zero())
+                        .unwrap_or(Vec3::ZERO);
+                    let uv = texcoords.get(i).copied().unwrap_or([0.0, 0.0]);
+                    let joints = vertex_joints
+                        .get(i)
+                        .map(|j| j.map(|j| j as u32 + skin_offset.unwrap_or(0)))
+                        .unwrap_or([0; 4]);
+                    let weights = vertex_weights.get(i).copied().unwrap_or([0.0; 4]);
+                    let (joints, weights) = clamp_joint_influences(joints, weights);
+
+                    all_vertices.push(Vertex {
+                        joints,
+                        weights,
+                        ao: occlusion.get(i).copied().unwrap_or(1.0),
+                        ..Vertex::new(
+                            vertex_transform.transform_point3(Vec3::from_array(*p)),
+                            normal,
+                            Vec2::from_array(uv),
+                        )
                     });
                 }
 
-                Mesh::new_with_texture(all_vertices, all_indices, submeshes)
+                let start_index = all_indices.len() as i32;
+                match reader.read_indices() {
+                    Some(indices) => {
+                        all_indices.extend(indices.into_u32().map(|i| base_vertex + i));
+                    }
+                    None => {
+                        all_indices.extend((0..positions.len() as u32).map(|i| base_vertex + i));
+                    }
+                }
+                let index_count = (all_indices.len() as i32) - start_index;
+
+                let pbr = primitive.material().pbr_metallic_roughness();
+                let texture_id = pbr
+                    .base_color_texture()
+                    .and_then(|info| {
+                        let image_index = info.texture().source().index();
+                        if let Some(&id) = image_textures.get(&image_index) {
+                            return Some(id);
+                        }
+                        let id = load_texture_from_gltf_image(images.get(image_index)?)?;
+                        image_textures.insert(image_index, id);
+                        Some(id)
+                    })
+                    .unwrap_or_else(white_texture);
+
+                let [r, g, b, a] = pbr.base_color_factor();
+                let color = Some((
+                    (r * 255.0) as u8,
+                    (g * 255.0) as u8,
+                    (b * 255.0) as u8,
+                    (a * 255.0) as u8,
+                ));
+
+                submeshes.push(SubMesh {
+                    start_index,
+                    index_count,
+                    texture_id,
+                    color,
+                });
+            }
+        }
+
+        let mut mesh = Mesh::new_with_texture(all_vertices, all_indices, submeshes);
+        mesh.owned_textures = image_textures.into_values().collect();
+        if !joints.is_empty() {
+            if joints.len() > MAX_BONES {
+                eprintln!(
+                    "glTF '{}' has {} joints; influences from joints past the first {} are dropped",
+                    file_path,
+                    joints.len(),
+                    MAX_BONES
+                );
             }
-            Err(_) => Mesh::new(vec![], vec![]),
+            mesh.skeleton = Some(Rc::new(Skeleton::from_gltf(&document, &buffers, joints)));
         }
+        mesh.with_source(source)
+    }
+
+    /// Builds a mesh from flat position (xyz), normal (xyz) and texture
+    /// coordinate (uv) arrays. Normals are generated when `normals` is empty.
+    /// Set `dynamic` to allow later calls to `update_vertices`.
+    #[staticmethod]
+    #[pyo3(signature = (positions, normals, tex_coords, indices, dynamic=false))]
+    pub fn from_data(
+        positions: Vec<f32>,
+        normals: Vec<f32>,
+        tex_coords: Vec<f32>,
+        indices: Vec<u32>,
+        dynamic: bool,
+    ) -> PyResult<Self> {
+        if !positions.len().is_multiple_of(3) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "positions length must be a multiple of 3",
+            ));
+        }
+        let vertex_count = positions.len() / 3;
+        if !normals.is_empty() && normals.len() != vertex_count * 3 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "expected {} normal components, got {}",
+                vertex_count * 3,
+                normals.len()
+            )));
+        }
+        if !tex_coords.is_empty() && tex_coords.len() != vertex_count * 2 {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "expected {} texture coordinate components, got {}",
+                vertex_count * 2,
+                tex_coords.len()
+            )));
+        }
+        if let Some(&bad) = indices.iter().find(|&&i| i as usize >= vertex_count) {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "index {} out of range for {} vertices",
+                bad, vertex_count
+            )));
+        }
+
+        let vertices = (0..vertex_count)
+            .map(|i| {
+                Vertex::new(
+                    Vec3::from_slice(&positions[i * 3..i * 3 + 3]),
+                    normals
+                        .get(i * 3..i * 3 + 3)
+                        .map(Vec3::from_slice)
+                        .unwrap_or(Vec3::ZERO),
+                    tex_coords
+                        .get(i * 2..i * 2 + 2)
+                        .map(Vec2::from_slice)
+                        .unwrap_or(Vec2::ZERO),
+                )
+            })
+            .collect();
+
+        Ok(Mesh::build(vertices, indices, Vec::new(), dynamic))
     }
 
     #[staticmethod]
@@ -224,11 +759,7 @@ impl Mesh {
 
         for i in 0..6 {
             for j in 0..4 {
-                vertices.push(Vertex {
-                    position: pos[i * 4 + j],
-                    normal: normals[i],
-                    tex_coords: uvs[j],
-                });
+                vertices.push(Vertex::new(pos[i * 4 + j], normals[i], uvs[j]));
             }
             let base = (i * 4) as u32;
             indices.push(base);
@@ -239,7 +770,7 @@ impl Mesh {
             indices.push(base);
         }
 
-        Mesh::new(vertices, indices)
+        Mesh::new(vertices, indices).with_source(MeshSource::Cube)
     }
 
     #[staticmethod]
@@ -328,11 +859,35 @@ impl Mesh {
         for p in positions.iter() {
             let normal = p.normalize();
             let position = *p * radius;
-            verts.push(Vertex {
-                position,
-                normal,
-                tex_coords: Vec2::new(0.0, 0.0),
-            });
+            let u = 0.5 + normal.z.atan2(normal.x) / std::f32::consts::TAU;
+            let v = 0.5 - normal.y.asin() / std::f32::consts::PI;
+            verts.push(Vertex::new(position, normal, Vec2::new(u, v)));
+        }
+
+        // Triangles straddling the u = 0/1 seam would interpolate across the whole
+        // texture; point their low-u corners at duplicates shifted by one instead.
+        let mut seam_duplicates: HashMap<usize, usize> = HashMap::new();
+        for tri in faces.iter_mut() {
+            let us = tri.map(|i| verts[i].tex_coords.x);
+            let min_u = us.iter().copied().fold(f32::MAX, f32::min);
+            let max_u = us.iter().copied().fold(f32::MIN, f32::max);
+            if max_u - min_u <= 0.5 {
+                continue;
+            }
+            for corner in tri.iter_mut() {
+                if verts[*corner].tex_coords.x >= 0.5 {
+                    continue;
+                }
+                *corner = *seam_duplicates.entry(*corner).or_insert_with(|| {
+                    let original = &verts[*corner];
+                    let duplicate = Vertex {
+                        tex_coords: original.tex_coords + Vec2::new(1.0, 0.0),
+                        ..*original
+                    };
+                    verts.push(duplicate);
+                    verts.len() - 1
+                });
+            }
         }
         let mut inds: Vec<u32> = Vec::with_capacity(faces.len() * 3);
         for tri in faces.iter() {
@@ -341,7 +896,132 @@ impl Mesh {
             inds.push(tri[2] as u32);
         }
 
-        Mesh::new(verts, inds)
+        Mesh::new(verts, inds).with_source(MeshSource::Icosphere { subdivisions })
+    }
+
+    #[staticmethod]
+    pub fn cylinder(segments: u32) -> Self {
+        let segments = segments.max(3);
+        let radius = 0.5f32;
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        for i in 0..=segments {
+            let u = i as f32 / segments as f32;
+            let theta = u * std::f32::consts::TAU;
+            let normal = Vec3::new(theta.cos(), 0.0, theta.sin());
+            vertices.push(Vertex::new(
+                Vec3::new(normal.x * radius, -0.5, normal.z * radius),
+                normal,
+                Vec2::new(u, 0.0),
+            ));
+            vertices.push(Vertex::new(
+                Vec3::new(normal.x * radius, 0.5, normal.z * radius),
+                normal,
+                Vec2::new(u, 1.0),
+            ));
+        }
+
+        for i in 0..segments {
+            let b0 = i * 2;
+            let t0 = b0 + 1;
+            let b1 = b0 + 2;
+            let t1 = b0 + 3;
+            indices.extend_from_slice(&[b0, t0, t1, b0, t1, b1]);
+        }
+
+        push_disc(&mut vertices, &mut indices, segments, radius, 0.5, true);
+        push_disc(&mut vertices, &mut indices, segments, radius, -0.5, false);
+
+        Mesh::new(vertices, indices).with_source(MeshSource::Cylinder { segments })
+    }
+
+    #[staticmethod]
+    pub fn cone(segments: u32) -> Self {
+        let segments = segments.max(3);
+        let radius = 0.5f32;
+        let height = 1.0f32;
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        // The side normal tilts up by the slope of the cone.
+        let side_normal =
+            |theta: f32| Vec3::new(theta.cos() * height, radius, theta.sin() * height).normalize();
+
+        for i in 0..=segments {
+            let u = i as f32 / segments as f32;
+            let theta = u * std::f32::consts::TAU;
+            vertices.push(Vertex::new(
+                Vec3::new(theta.cos() * radius, -0.5, theta.sin() * radius),
+                side_normal(theta),
+                Vec2::new(u, 0.0),
+            ));
+        }
+
+        // One apex vertex per segment so its normal follows the face instead of
+        // pinching to a single direction.
+        let apex_start = vertices.len() as u32;
+        for i in 0..segments {
+            let u = (i as f32 + 0.5) / segments as f32;
+            vertices.push(Vertex::new(
+                Vec3::new(0.0, 0.5, 0.0),
+                side_normal(u * std::f32::consts::TAU),
+                Vec2::new(u, 1.0),
+            ));
+        }
+
+        for i in 0..segments {
+            indices.extend_from_slice(&[i, apex_start + i, i + 1]);
+        }
+
+        push_disc(&mut vertices, &mut indices, segments, radius, -0.5, false);
+
+        Mesh::new(vertices, indices).with_source(MeshSource::Cone { segments })
+    }
+
+    #[staticmethod]
+    pub fn torus(major_segments: u32, minor_segments: u32) -> Self {
+        let major_segments = major_segments.max(3);
+        let minor_segments = minor_segments.max(3);
+        let major_radius = 0.35f32;
+        let minor_radius = 0.15f32;
+        let mut vertices: Vec<Vertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+
+        for i in 0..=major_segments {
+            let u = i as f32 / major_segments as f32;
+            let theta = u * std::f32::consts::TAU;
+            for j in 0..=minor_segments {
+                let v = j as f32 / minor_segments as f32;
+                let phi = v * std::f32::consts::TAU;
+                let ring = major_radius + minor_radius * phi.cos();
+                vertices.push(Vertex::new(
+                    Vec3::new(
+                        ring * theta.cos(),
+                        minor_radius * phi.sin(),
+                        ring * theta.sin(),
+                    ),
+                    Vec3::new(phi.cos() * theta.cos(), phi.sin(), phi.cos() * theta.sin()),
+                    Vec2::new(u, v),
+                ));
+            }
+        }
+
+        let stride = minor_segments + 1;
+        for i in 0..major_segments {
+            for j in 0..minor_segments {
+                let a = i * stride + j;
+                let b = (i + 1) * stride + j;
+                let c = b + 1;
+                let d = a + 1;
+                indices.extend_from_slice(&[a, c, b, a, d, c]);
+            }
+        }
+
+        Mesh::new(vertices, indices).with_source(MeshSource::Torus {
+            major_segments,
+            minor_segments,
+        })
     }
 
     #[staticmethod]
@@ -352,68 +1032,59 @@ impl Mesh {
     #[staticmethod]
     pub fn plane() -> Self {
         let vertices = vec![
-            Vertex {
-                position: Vec3::new(-0.5, 0.0, -0.5),
-                normal: Vec3::new(0.0, 1.0, 0.0),
-                tex_coords: Vec2::new(0.0, 0.0),
-            },
-            Vertex {
-                position: Vec3::new(0.5, 0.0, -0.5),
-                normal: Vec3::new(0.0, 1.0, 0.0),
-                tex_coords: Vec2::new(1.0, 0.0),
-            },
-            Vertex {
-                position: Vec3::new(0.5, 0.0, 0.5),
-                normal: Vec3::new(0.0, 1.0, 0.0),
-                tex_coords: Vec2::new(1.0, 1.0),
-            },
-            Vertex {
-                position: Vec3::new(-0.5, 0.0, 0.5),
-                normal: Vec3::new(0.0, 1.0, 0.0),
-                tex_coords: Vec2::new(0.0, 1.0),
-            },
+            Vertex::new(
+                Vec3::new(-0.5, 0.0, -0.5),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec2::new(0.0, 0.0),
+            ),
+            Vertex::new(
+                Vec3::new(0.5, 0.0, -0.5),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec2::new(1.0, 0.0),
+            ),
+            Vertex::new(
+                Vec3::new(0.5, 0.0, 0.5),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec2::new(1.0, 1.0),
+            ),
+            Vertex::new(
+                Vec3::new(-0.5, 0.0, 0.5),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec2::new(0.0, 1.0),
+            ),
         ];
         let indices = vec![0, 1, 2, 2, 3, 0];
-        Mesh::new(vertices, indices)
+        Mesh::new(vertices, indices).with_source(MeshSource::Plane)
     }
 }
 
 impl Mesh {
-    pub fn new(mut vertices: Vec<Vertex>, indices: Vec<u32>) -> Mesh {
+    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>) -> Mesh {
+        Mesh::build(vertices, indices, Vec::new(), false)
+    }
+
+    pub fn new_with_texture(
+        vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+        submeshes: Vec<SubMesh>,
+    ) -> Mesh {
+        Mesh::build(vertices, indices, submeshes, false)
+    }
+
+    /// Uploads the mesh to the GL. Dynamic meshes keep a CPU copy of their
+    /// vertices so `update_vertices` can rewrite them in place.
+    fn build(
+        mut vertices: Vec<Vertex>,
+        indices: Vec<u32>,
+        submeshes: Vec<SubMesh>,
+        dynamic: bool,
+    ) -> Mesh {
         if !vertices.is_empty() && vertices.iter().all(|v| v.normal.length_squared() == 0.0) {
-            let mut accu = vec![Vec3::ZERO; vertices.len()];
-            for tri in indices.chunks(3) {
-                if tri.len() < 3 {
-                    continue;
-                }
-                let i0 = tri[0] as usize;
-                let i1 = tri[1] as usize;
-                let i2 = tri[2] as usize;
-                if i0 >= vertices.len() || i1 >= vertices.len() || i2 >= vertices.len() {
-                    continue;
-                }
-                let v0 = vertices[i0].position;
-                let v1 = vertices[i1].position;
-                let v2 = vertices[i2].position;
-                let face_normal = (v1 - v0).cross(v2 - v0);
-                let face_normal = if face_normal.length_squared() != 0.0 {
-                    face_normal.normalize()
-                } else {
-                    Vec3::ZERO
-                };
-                accu[i0] += face_normal;
-                accu[i1] += face_normal;
-                accu[i2] += face_normal;
-            }
-            for (v, n) in vertices.iter_mut().zip(accu.iter()) {
-                v.normal = if n.length_squared() != 0.0 {
-                    n.normalize()
-                } else {
-                    *n
-                };
-            }
+            compute_normals(&mut vertices, &indices);
         }
 
+        compute_tangents(&mut vertices, &indices);
+
         let mut vao = 0;
         let mut vbo = 0;
         let mut ebo = 0;
@@ -435,7 +1106,11 @@ impl Mesh {
                 gl::ARRAY_BUFFER,
                 (vertices.len() * mem::size_of::<Vertex>()) as isize,
                 vertex_data_ptr,
-                gl::STATIC_DRAW,
+                if dynamic {
+                    gl::DYNAMIC_DRAW
+                } else {
+                    gl::STATIC_DRAW
+                },
             );
 
             gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
@@ -481,172 +1156,118 @@ impl Mesh {
                 (mem::size_of::<Vec3>() * 2) as *const _,
             );
 
-            gl::BindVertexArray(0);
-        }
-
-        Mesh {
-            vao,
-            vbo,
-            ebo,
-            index_count: indices.len() as i32,
-            texture_id: 0,
-            color: None,
-            submeshes: Vec::new(),
-        }
-    }
-
-    pub fn new_with_texture(
-        mut vertices: Vec<Vertex>,
-        indices: Vec<u32>,
-        submeshes: Vec<SubMesh>,
-    ) -> Mesh {
-        if !vertices.is_empty() && vertices.iter().all(|v| v.normal.length_squared() == 0.0) {
-            let mut accu = vec![Vec3::ZERO; vertices.len()];
-            for tri in indices.chunks(3) {
-                if tri.len() < 3 {
-                    continue;
-                }
-                let i0 = tri[0] as usize;
-                let i1 = tri[1] as usize;
-                let i2 = tri[2] as usize;
-                if i0 >= vertices.len() || i1 >= vertices.len() || i2 >= vertices.len() {
-                    continue;
-                }
-                let v0 = vertices[i0].position;
-                let v1 = vertices[i1].position;
-                let v2 = vertices[i2].position;
-                let face_normal = (v1 - v0).cross(v2 - v0);
-                let face_normal = if face_normal.length_squared() != 0.0 {
-                    face_normal.normalize()
-                } else {
-                    Vec3::ZERO
-                };
-                accu[i0] += face_normal;
-                accu[i1] += face_normal;
-                accu[i2] += face_normal;
-            }
-            for (v, n) in vertices.iter_mut().zip(accu.iter()) {
-                v.normal = if n.length_squared() != 0.0 {
-                    n.normalize()
-                } else {
-                    *n
-                };
-            }
-        }
-
-        let mut vao = 0;
-        let mut vbo = 0;
-        let mut ebo = 0;
-
-        unsafe {
-            gl::GenVertexArrays(1, &mut vao);
-            gl::GenBuffers(1, &mut vbo);
-            gl::GenBuffers(1, &mut ebo);
-
-            gl::BindVertexArray(vao);
-
-            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-            let vertex_data_ptr = if vertices.is_empty() {
-                ptr::null()
-            } else {
-                &vertices[0] as *const Vertex as *const _
-            };
-            gl::BufferData(
-                gl::ARRAY_BUFFER,
-                (vertices.len() * mem::size_of::<Vertex>()) as isize,
-                vertex_data_ptr,
-                gl::STATIC_DRAW,
-            );
-
-            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
-            let index_data_ptr = if indices.is_empty() {
-                ptr::null()
-            } else {
-                &indices[0] as *const u32 as *const _
-            };
-            gl::BufferData(
-                gl::ELEMENT_ARRAY_BUFFER,
-                (indices.len() * mem::size_of::<u32>()) as isize,
-                index_data_ptr,
-                gl::STATIC_DRAW,
-            );
-
-            gl::EnableVertexAttribArray(0);
+            gl::EnableVertexAttribArray(3);
             gl::VertexAttribPointer(
-                0,
+                3,
                 3,
                 gl::FLOAT,
                 gl::FALSE,
                 mem::size_of::<Vertex>() as i32,
-                ptr::null(),
+                (mem::size_of::<Vec3>() * 2 + mem::size_of::<Vec2>()) as *const _,
             );
 
-            gl::EnableVertexAttribArray(1);
+            gl::EnableVertexAttribArray(4);
+            gl::VertexAttribIPointer(
+                4,
+                4,
+                gl::UNSIGNED_INT,
+                mem::size_of::<Vertex>() as i32,
+                mem::offset_of!(Vertex, joints) as *const _,
+            );
+
+            gl::EnableVertexAttribArray(5);
             gl::VertexAttribPointer(
-                1,
-                3,
+                5,
+                4,
                 gl::FLOAT,
                 gl::FALSE,
                 mem::size_of::<Vertex>() as i32,
-                (mem::size_of::<Vec3>()) as *const _,
+                mem::offset_of!(Vertex, weights) as *const _,
             );
 
-            gl::EnableVertexAttribArray(2);
+            gl::EnableVertexAttribArray(6);
             gl::VertexAttribPointer(
-                2,
-                2,
+                6,
+                1,
                 gl::FLOAT,
                 gl::FALSE,
                 mem::size_of::<Vertex>() as i32,
-                (mem::size_of::<Vec3>() * 2) as *const _,
+                mem::offset_of!(Vertex, ao) as *const _,
             );
 
             gl::BindVertexArray(0);
         }
 
+        let (bounds_min, bounds_max) = compute_aabb(&vertices);
+        let (bounds_center, bounds_radius) = compute_bounding_sphere(&vertices);
+
         Mesh {
             vao,
             vbo,
             ebo,
             index_count: indices.len() as i32,
             texture_id: 0,
+            texture: None,
             color: None,
+            material: None,
+            uv_scale: Vec2::ONE,
+            uv_offset: Vec2::ZERO,
+            uv_scroll: Vec2::ZERO,
+            bounds_min,
+            bounds_max,
+            bounds_center,
+            bounds_radius,
             submeshes,
+            owned_textures: Vec::new(),
+            skeleton: None,
+            dynamic_data: dynamic.then_some(DynamicData { vertices, indices }),
+            source: None,
         }
     }
 
+    fn with_source(mut self, source: MeshSource) -> Mesh {
+        self.source = Some(source);
+        self
+    }
+
     pub fn draw(&self) {
-        unsafe {
-            gl::BindVertexArray(self.vao);
-            gl::DrawElements(
-                gl::TRIANGLES,
-                self.index_count,
-                gl::UNSIGNED_INT,
-                ptr::null(),
-            );
-            gl::BindVertexArray(0);
-        }
+        draw_elements(self.vao, 0, self.index_count);
     }
 
     pub fn draw_submesh(&self, submesh_index: usize) {
-        if submesh_index >= self.submeshes.len() {
-            return;
+        if let Some(submesh) = self.submeshes.get(submesh_index) {
+            draw_submesh(self.vao, submesh);
         }
-        let submesh = &self.submeshes[submesh_index];
-        unsafe {
-            gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D, submesh.texture_id);
-            gl::BindVertexArray(self.vao);
-            gl::DrawElements(
-                gl::TRIANGLES,
-                submesh.index_count,
-                gl::UNSIGNED_INT,
-                (submesh.start_index as usize * mem::size_of::<u32>()) as *const _,
-            );
-            gl::BindVertexArray(0);
+    }
+
+    /// Copies out what the geometry pass needs to draw this mesh, reading
+    /// the texture id from the texture itself in case it was deleted.
+    pub fn draw_info(&self, py: Python) -> MeshDrawInfo {
+        MeshDrawInfo {
+            vao: self.vao,
+            index_count: self.index_count,
+            texture_id: self.texture.as_ref().map_or(0, |t| t.borrow(py).id),
+            color: self.color,
+            uv_scale: self.uv_scale,
+            uv_offset: self.uv_offset,
+            uv_scroll: self.uv_scroll,
+            submeshes: self.submeshes.clone(),
         }
     }
 
+    /// Bounding sphere transformed by `model`, conservatively scaled by its
+    /// largest axis.
+    pub fn world_bounding_sphere(&self, model: &Mat4) -> (Vec3, f32) {
+        let center = model.transform_point3(self.bounds_center);
+        let scale = model
+            .x_axis
+            .truncate()
+            .length()
+            .max(model.y_axis.truncate().length())
+            .max(model.z_axis.truncate().length());
+        (center, self.bounds_radius * scale)
+    }
+
     pub fn has_submeshes(&self) -> bool {
         !self.submeshes.is_empty()
     }
@@ -658,54 +1279,321 @@ impl Mesh {
     pub fn get_submesh_color(&self, submesh_index: usize) -> Option<(u8, u8, u8, u8)> {
         self.submeshes.get(submesh_index)?.color
     }
+
+    /// Local-space vertex positions and triangles. Dynamic meshes use their
+    /// CPU copy; others read the geometry back from the GL buffers.
+    pub fn triangles(&self) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        let into_triangles = |indices: &[u32]| {
+            indices
+                .chunks_exact(3)
+                .map(|t| [t[0], t[1], t[2]])
+                .collect::<Vec<_>>()
+        };
+        if let Some(data) = &self.dynamic_data {
+            let positions = data.vertices.iter().map(|v| v.position).collect();
+            return (positions, into_triangles(&data.indices));
+        }
+        if self.is_deleted() {
+            return (Vec::new(), Vec::new());
+        }
+
+        unsafe {
+            let mut vbo_size = 0;
+            gl::BindBuffer(gl::COPY_READ_BUFFER, self.vbo);
+            gl::GetBufferParameteriv(gl::COPY_READ_BUFFER, gl::BUFFER_SIZE, &mut vbo_size);
+            let vertex_count = vbo_size as usize / mem::size_of::<Vertex>();
+            let mut vertices: Vec<Vertex> = Vec::with_capacity(vertex_count);
+            gl::GetBufferSubData(
+                gl::COPY_READ_BUFFER,
+                0,
+                (vertex_count * mem::size_of::<Vertex>()) as isize,
+                vertices.as_mut_ptr() as *mut _,
+            );
+            vertices.set_len(vertex_count);
+
+            let index_count = self.index_count.max(0) as usize;
+            let mut indices = vec![0u32; index_count];
+            gl::BindBuffer(gl::COPY_READ_BUFFER, self.ebo);
+            gl::GetBufferSubData(
+                gl::COPY_READ_BUFFER,
+                0,
+                (index_count * mem::size_of::<u32>()) as isize,
+                indices.as_mut_ptr() as *mut _,
+            );
+            gl::BindBuffer(gl::COPY_READ_BUFFER, 0);
+
+            let positions = vertices.iter().map(|v| v.position).collect();
+            (positions, into_triangles(&indices))
+        }
+    }
 }
 
 impl Drop for Mesh {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteVertexArrays(1, &self.vao);
-            gl::DeleteBuffers(1, &self.vbo);
-            gl::DeleteBuffers(1, &self.ebo);
+        self.delete();
+    }
+}
+
+/// Draw state copied out of a `Mesh` so it can be rendered without holding a
+/// Python borrow.
+pub struct MeshDrawInfo {
+    pub vao: u32,
+    pub index_count: i32,
+    pub texture_id: u32,
+    pub color: Option<(u8, u8, u8, u8)>,
+    pub uv_scale: Vec2,
+    pub uv_offset: Vec2,
+    pub uv_scroll: Vec2,
+    pub submeshes: Vec<SubMesh>,
+}
+
+impl MeshDrawInfo {
+    pub fn draw(&self) {
+        draw_elements(self.vao, 0, self.index_count);
+    }
+
+    /// The texture coordinate offset `time` seconds in. Only the fractional
+    /// part of the scroll is kept, so long sessions don't lose precision.
+    pub fn uv_offset_at(&self, time: f32) -> Vec2 {
+        self.uv_offset + (self.uv_scroll * time).fract()
+    }
+}
+
+pub fn draw_submesh(vao: u32, submesh: &SubMesh) {
+    unsafe {
+        gl::ActiveTexture(gl::TEXTURE0);
+        gl::BindTexture(gl::TEXTURE_2D, submesh.texture_id);
+    }
+    draw_elements(vao, submesh.start_index, submesh.index_count);
+}
+
+fn draw_elements(vao: u32, start_index: i32, index_count: i32) {
+    if vao == 0 {
+        return;
+    }
+    unsafe {
+        gl::BindVertexArray(vao);
+        gl::DrawElements(
+            gl::TRIANGLES,
+            index_count,
+            gl::UNSIGNED_INT,
+            (start_index as usize * mem::size_of::<u32>()) as *const _,
+        );
+        gl::BindVertexArray(0);
+    }
+}
+
+fn compute_aabb(vertices: &[Vertex]) -> (Vec3, Vec3) {
+    if vertices.is_empty() {
+        return (Vec3::ZERO, Vec3::ZERO);
+    }
+
+    vertices.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), v| (min.min(v.position), max.max(v.position)),
+    )
+}
+
+fn compute_bounding_sphere(vertices: &[Vertex]) -> (Vec3, f32) {
+    if vertices.is_empty() {
+        return (Vec3::ZERO, 0.0);
+    }
+
+    let (min, max) = compute_aabb(vertices);
+    let center = (min + max) * 0.5;
+    let radius = vertices
+        .iter()
+        .map(|v| v.position.distance_squared(center))
+        .fold(0.0f32, f32::max)
+        .sqrt();
+
+    (center, radius)
+}
+
+/// Sets each vertex normal to the normalized sum of its faces' normals.
+fn compute_normals(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut accu = vec![Vec3::ZERO; vertices.len()];
+    for tri in indices.chunks(3) {
+        if tri.len() < 3 {
+            continue;
+        }
+        let i0 = tri[0] as usize;
+        let i1 = tri[1] as usize;
+        let i2 = tri[2] as usize;
+        if i0 >= vertices.len() || i1 >= vertices.len() || i2 >= vertices.len() {
+            continue;
+        }
+        let v0 = vertices[i0].position;
+        let v1 = vertices[i1].position;
+        let v2 = vertices[i2].position;
+        let face_normal = (v1 - v0).cross(v2 - v0);
+        let face_normal = if face_normal.length_squared() != 0.0 {
+            face_normal.normalize()
+        } else {
+            Vec3::ZERO
+        };
+        accu[i0] += face_normal;
+        accu[i1] += face_normal;
+        accu[i2] += face_normal;
+    }
+    for (v, n) in vertices.iter_mut().zip(accu.iter()) {
+        v.normal = if n.length_squared() != 0.0 {
+            n.normalize()
+        } else {
+            *n
+        };
+    }
+}
+
+/// Accumulates per-triangle tangents from positions and UVs, then
+/// orthogonalizes them against the vertex normals.
+fn compute_tangents(vertices: &mut [Vertex], indices: &[u32]) {
+    let mut accu = vec![Vec3::ZERO; vertices.len()];
+    for tri in indices.chunks_exact(3) {
+        let (i0, i1, i2) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+        if i0 >= vertices.len() || i1 >= vertices.len() || i2 >= vertices.len() {
+            continue;
+        }
+        let edge1 = vertices[i1].position - vertices[i0].position;
+        let edge2 = vertices[i2].position - vertices[i0].position;
+        let duv1 = vertices[i1].tex_coords - vertices[i0].tex_coords;
+        let duv2 = vertices[i2].tex_coords - vertices[i0].tex_coords;
+
+        let det = duv1.x * duv2.y - duv2.x * duv1.y;
+        if det.abs() < f32::EPSILON {
+            continue;
+        }
+        let tangent = (edge1 * duv2.y - edge2 * duv1.y) / det;
+        accu[i0] += tangent;
+        accu[i1] += tangent;
+        accu[i2] += tangent;
+    }
+
+    for (v, t) in vertices.iter_mut().zip(accu.iter()) {
+        let n = v.normal;
+        let t = (*t - n * n.dot(*t)).normalize_or_zero();
+        // Degenerate UVs still need a usable basis, so pick any perpendicular.
+        v.tangent = if t == Vec3::ZERO {
+            n.any_orthonormal_vector()
+        } else {
+            t
+        };
+    }
+}
+
+/// Appends a flat cap at height `y` facing up or down, used to close cylinders and cones.
+fn push_disc(
+    vertices: &mut Vec<Vertex>,
+    indices: &mut Vec<u32>,
+    segments: u32,
+    radius: f32,
+    y: f32,
+    facing_up: bool,
+) {
+    let normal = if facing_up { Vec3::Y } else { -Vec3::Y };
+    let center = vertices.len() as u32;
+    vertices.push(Vertex::new(
+        Vec3::new(0.0, y, 0.0),
+        normal,
+        Vec2::new(0.5, 0.5),
+    ));
+
+    for i in 0..=segments {
+        let theta = i as f32 / segments as f32 * std::f32::consts::TAU;
+        let (sin, cos) = theta.sin_cos();
+        vertices.push(Vertex::new(
+            Vec3::new(cos * radius, y, sin * radius),
+            normal,
+            Vec2::new(0.5 + cos * 0.5, 0.5 + sin * 0.5),
+        ));
+    }
+
+    for i in 0..segments {
+        let p0 = center + 1 + i;
+        let p1 = p0 + 1;
+        if facing_up {
+            indices.extend_from_slice(&[center, p1, p0]);
+        } else {
+            indices.extend_from_slice(&[center, p0, p1]);
         }
     }
 }
 
-fn load_texture_from_path(path: &Path) -> Option<u32> {
+fn load_texture_from_path(path: &Path, filter: TextureFilter) -> Option<u32> {
     let img = image::open(path).ok()?;
     let img = img.to_rgba8();
     let (width, height) = img.dimensions();
     let data = img.into_raw();
 
-    let mut texture_id = 0;
-    unsafe {
-        gl::GenTextures(1, &mut texture_id);
-        gl::BindTexture(gl::TEXTURE_2D, texture_id);
-
-        gl::TexImage2D(
-            gl::TEXTURE_2D,
-            0,
-            gl::RGBA as i32,
-            width as i32,
-            height as i32,
-            0,
-            gl::RGBA,
-            gl::UNSIGNED_BYTE,
-            data.as_ptr() as *const _,
-        );
-
-        gl::GenerateMipmap(gl::TEXTURE_2D);
+    Some(upload_rgba_texture(
+        width,
+        height,
+        &data,
+        filter,
+        TextureWrap::Repeat,
+        true,
+    ))
+}
 
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-        gl::TexParameteri(
-            gl::TEXTURE_2D,
-            gl::TEXTURE_MIN_FILTER,
-            gl::LINEAR_MIPMAP_LINEAR as i32,
-        );
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+fn load_texture_from_gltf_image(image: &gltf::image::Data) -> Option<u32> {
+    use gltf::image::Format;
+
+    let data: Vec<u8> = match image.format {
+        Format::R8G8B8A8 => image.pixels.clone(),
+        Format::R8G8B8 => image
+            .pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        Format::R8G8 => image
+            .pixels
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[1], 0, 255])
+            .collect(),
+        Format::R8 => image.pixels.iter().flat_map(|&p| [p, p, p, 255]).collect(),
+        _ => return None,
+    };
+
+    Some(upload_rgba_texture(
+        image.width,
+        image.height,
+        &data,
+        TextureFilter::Linear,
+        TextureWrap::Repeat,
+        true,
+    ))
+}
 
-        gl::BindTexture(gl::TEXTURE_2D, 0);
+/// Drops influences from joints past the shader's `MAX_BONES` palette so the
+/// vertex shader never indexes out of range, then renormalizes the rest.
+fn clamp_joint_influences(mut joints: [u32; 4], mut weights: [f32; 4]) -> ([u32; 4], [f32; 4]) {
+    for (joint, weight) in joints.iter_mut().zip(weights.iter_mut()) {
+        if *joint as usize >= MAX_BONES {
+            *joint = 0;
+            *weight = 0.0;
+        }
+    }
+    let total: f32 = weights.iter().sum();
+    if total > 0.0 {
+        weights = weights.map(|w| w / total);
     }
+    (joints, weights)
+}
 
-    Some(texture_id)
+/// Reads per-vertex ambient occlusion from the custom `_AO` float attribute.
+/// Returns an empty list when the primitive has none.
+fn read_vertex_ao(primitive: &gltf::Primitive, buffers: &[gltf::buffer::Data]) -> Vec<f32> {
+    use gltf::accessor::{DataType, Dimensions};
+
+    let Some(accessor) = primitive.get(&gltf::Semantic::Extras("AO".to_string())) else {
+        return Vec::new();
+    };
+    if accessor.data_type() != DataType::F32 || accessor.dimensions() != Dimensions::Scalar {
+        return Vec::new();
+    }
+    gltf::accessor::Iter::<f32>::new(accessor, |buffer: gltf::Buffer| {
+        buffers.get(buffer.index()).map(|data| &data[..])
+    })
+    .map(|iter| iter.map(|ao| ao.clamp(0.0, 1.0)).collect())
+    .unwrap_or_default()
 }
@@ -1,173 +1,532 @@
+pub mod animation;
+pub mod cascades;
+pub mod frustum;
+pub mod material;
 pub mod mesh;
 pub mod shader;
+pub mod skybox;
+pub mod sprite;
 pub mod texture;
 
 use gl;
+use material::MaterialParams;
 use shader::Shader;
 use std::mem;
 use std::ptr;
 
+/// Size of the `pointShadowMaps` sampler array in `lighting.fsh`.
+pub const MAX_POINT_SHADOWS: usize = 16;
+/// Texture unit of `pointShadowMaps[0]`; units below it hold the G-buffer
+/// and the directional shadow map.
+pub const POINT_SHADOW_UNIT: u32 = 5;
+/// Window rectangle (x, y, width, height), in pixels from the top-left.
+pub type ViewportRect = (i32, i32, u32, u32);
+/// Upper bound on the bloom mip chain length; the first mip is half the
+/// framebuffer size, so eight levels already reach 1/256 scale.
+pub const MAX_BLOOM_MIPS: usize = 8;
+
+/// One level of the bloom mip chain.
+struct BloomMip {
+    fbo: u32,
+    texture: u32,
+    width: u32,
+    height: u32,
+}
+
 pub struct Renderer {
     g_buffer: u32,
     g_position: u32,
     g_normal: u32,
     g_albedo_spec: u32,
+    /// Screen-space motion of each pixel since the previous frame, in UV units.
+    g_velocity: u32,
+    /// Specular tint of dielectric surfaces, scaling their 4% reflectance.
+    g_specular: u32,
     rbo_depth: u32,
     hdr_fbo: u32,
     hdr_color: u32,
 
-    bloom_fbo: u32,
-    bloom_color: u32,
-    pingpong_fbos: [u32; 2],
-    pingpong_color: [u32; 2],
-    bloom_output: u32,
+    ssr_fbo: u32,
+    ssr_color: u32,
+    ssr_shader: Shader,
+    pub ssr_enabled: bool,
+    /// Ray-march steps per pixel; more find thinner or farther hits.
+    pub ssr_steps: u32,
+    /// World-space distance a reflected ray travels before giving up.
+    pub ssr_max_distance: f32,
+
+    motion_blur_fbo: u32,
+    motion_blur_color: u32,
+    motion_blur_shader: Shader,
+    /// Scales the per-pixel velocity into a blur streak; 0 disables the pass.
+    pub motion_blur_strength: f32,
+
+    dof_fbo: u32,
+    dof_color: u32,
+    dof_shader: Shader,
+    pub dof_enabled: bool,
+    /// Distance from the camera that stays sharp.
+    pub dof_focus_distance: f32,
+    /// Scales the circle of confusion; larger gives shallower focus.
+    pub dof_aperture: f32,
+
+    bloom_mips: Vec<BloomMip>,
+    bloom_mip_count: usize,
     bloom_shader: Shader,
-    blur_shader: Shader,
+    bloom_downsample_shader: Shader,
+    bloom_upsample_shader: Shader,
     pub bloom_enabled: bool,
     pub bloom_threshold: f32,
     pub bloom_intensity: f32,
+    /// Upsample tent filter radius in UV units; larger spreads the glow wider.
+    pub bloom_radius: f32,
+
+    pub tone_mapping_mode: i32,
+    pub exposure: f32,
+
+    /// Distance from the screen center (0.5 reaches the edge midpoints)
+    /// where the vignette starts darkening.
+    pub vignette_radius: f32,
+    /// Distance over which the vignette fades in past `vignette_radius`.
+    pub vignette_softness: f32,
+    /// How dark the vignette gets at full strength; 0 disables it.
+    pub vignette_intensity: f32,
+
+    /// 3D color grading table applied to the final image, 0 when unset.
+    color_lut: u32,
+
+    /// How far red and blue are pulled apart towards the frame edges, as a
+    /// fraction of the distance from the center; 0 disables it.
+    pub chromatic_aberration: f32,
+    /// Amplitude of the animated noise added to the final image; 0
+    /// disables it.
+    pub film_grain: f32,
+
+    /// Background color wherever no geometry or skybox was drawn.
+    pub clear_color: glam::Vec3,
+    /// Constant light added to every surface, scaled by its albedo.
+    pub ambient_light: glam::Vec3,
+
+    /// 0 = off, 1 = linear, 2 = exp, 3 = exp2.
+    pub fog_mode: i32,
+    pub fog_color: glam::Vec3,
+    pub fog_density: f32,
+    pub fog_start: f32,
+    pub fog_end: f32,
+
+    ldr_fbo: u32,
+    ldr_color: u32,
+    fxaa_shader: Shader,
+    pub fxaa_enabled: bool,
+
+    /// Offscreen target the finished frame is drawn into instead of the
+    /// window, for headless engines. 0 when drawing to the window.
+    output_fbo: u32,
+    output_color: u32,
+    /// Where the finished frame is drawn in the window; `None` fills it.
+    pub output_viewport: Option<ViewportRect>,
+
+    /// Multisampled target for the forward path, resolved into the HDR
+    /// buffer each frame. 0 when the deferred pipeline is in use.
+    msaa_fbo: u32,
+    msaa_color: u32,
+    msaa_depth: u32,
+    msaa_samples: u32,
+
+    pub polygon_mode: u32,
+    /// Faces discarded in the geometry pass (`gl::BACK` or `gl::FRONT`), or
+    /// `None` to draw both sides.
+    pub cull_mode: Option<u32>,
+    /// Winding that counts as front-facing, `gl::CCW` or `gl::CW`.
+    pub front_face: u32,
 
     pub directional_shadow_fbo: u32,
     pub directional_shadow_map: u32,
     pub point_shadow_fbos: Vec<[u32; 6]>,
     pub point_shadow_maps: Vec<u32>,
+    pub directional_shadow_size: u32,
+    pub point_shadow_size: u32,
+    /// How many point shadow maps the lighting pass can sample at once.
+    max_point_shadows: usize,
+
+    /// Number of directional shadow cascades, each a layer of
+    /// `directional_shadow_map`.
+    pub cascade_count: usize,
+    /// Blend between uniform (0) and logarithmic (1) cascade splits.
+    pub cascade_split_lambda: f32,
+    /// View distance covered by the last cascade.
+    pub shadow_distance: f32,
+    /// Width of the PCF kernel in shadow texels; 1 gives hard edges.
+    pub shadow_softness: i32,
 
     pub geometry_shader: Shader,
     pub lighting_shader: Shader,
     pub composite_shader: Shader,
     pub directional_shadow_shader: Shader,
     pub point_shadow_shader: Shader,
+    pub forward_shader: Shader,
+    sprite_shader: Shader,
+    emissive_shader: Shader,
+    outline_shader: Shader,
 
     quad_vao: u32,
+    skybox_vao: u32,
+    skybox_shader: Shader,
+    white_texture: u32,
 
     light_sphere: mesh::Mesh,
     light_sphere_shader: Shader,
 
+    /// When set, `reload_shaders` reads `<name>.vsh`/`<name>.fsh` from this
+    /// directory instead of the sources compiled into the extension.
+    pub shader_dir: Option<String>,
+
     pub width: u32,
     pub height: u32,
 }
 
+/// Loads a built-in shader pair, preferring `<dir>/<name>.vsh|.fsh` when a
+/// directory is given and falling back to the embedded source otherwise.
+macro_rules! builtin_shader {
+    ($dir:expr, $name:literal) => {
+        builtin_shader!($dir, $name, $name)
+    };
+    ($dir:expr, $vertex:literal, $fragment:literal) => {
+        match $dir {
+            Some(dir) => Shader::from_glsl(
+                &format!("{}/{}.vsh", dir, $vertex),
+                &format!("{}/{}.fsh", dir, $fragment),
+            ),
+            None => Shader::new(
+                include_str!(concat!("shaders/", $vertex, ".vsh")),
+                include_str!(concat!("shaders/", $fragment, ".fsh")),
+            ),
+        }
+    };
+}
+
+struct BuiltinShaders {
+    geometry_shader: Shader,
+    lighting_shader: Shader,
+    composite_shader: Shader,
+    directional_shadow_shader: Shader,
+    point_shadow_shader: Shader,
+    bloom_shader: Shader,
+    bloom_downsample_shader: Shader,
+    bloom_upsample_shader: Shader,
+    light_sphere_shader: Shader,
+    fxaa_shader: Shader,
+    ssr_shader: Shader,
+    motion_blur_shader: Shader,
+    dof_shader: Shader,
+    skybox_shader: Shader,
+    forward_shader: Shader,
+    sprite_shader: Shader,
+    emissive_shader: Shader,
+    outline_shader: Shader,
+}
+
+impl BuiltinShaders {
+    fn load(dir: Option<&str>) -> Result<Self, String> {
+        Ok(BuiltinShaders {
+            geometry_shader: builtin_shader!(dir, "geometry")?,
+            lighting_shader: builtin_shader!(dir, "lighting")?,
+            composite_shader: builtin_shader!(dir, "composite")?,
+            directional_shadow_shader: builtin_shader!(dir, "directional_shadow")?,
+            point_shadow_shader: builtin_shader!(dir, "point_shadow")?,
+            bloom_shader: builtin_shader!(dir, "bloom")?,
+            bloom_downsample_shader: builtin_shader!(dir, "bloom", "bloom_downsample")?,
+            bloom_upsample_shader: builtin_shader!(dir, "bloom", "bloom_upsample")?,
+            light_sphere_shader: builtin_shader!(dir, "light_sphere")?,
+            fxaa_shader: builtin_shader!(dir, "fxaa")?,
+            ssr_shader: builtin_shader!(dir, "composite", "ssr")?,
+            motion_blur_shader: builtin_shader!(dir, "composite", "motion_blur")?,
+            dof_shader: builtin_shader!(dir, "composite", "dof")?,
+            skybox_shader: builtin_shader!(dir, "skybox")?,
+            forward_shader: builtin_shader!(dir, "forward")?,
+            sprite_shader: builtin_shader!(dir, "sprite")?,
+            // Shares the geometry pass's vertex stage so depths match exactly.
+            emissive_shader: builtin_shader!(dir, "geometry", "emissive")?,
+            outline_shader: builtin_shader!(dir, "geometry", "outline")?,
+        })
+    }
+}
+
 impl Renderer {
+    /// Where the first shadow cascade starts. The camera's own near plane is
+    /// far too close to split from.
+    const CASCADE_NEAR: f32 = 0.1;
+
     pub fn new(width: u32, height: u32) -> Result<Renderer, String> {
-        let (g_buffer, g_position, g_normal, g_albedo_spec, rbo_depth) =
-            unsafe { create_g_buffer(width, height) };
+        let (g_buffer, g_position, g_normal, g_albedo_spec, g_velocity, g_specular, rbo_depth) =
+            unsafe { create_g_buffer(width, height)? };
         let (quad_vao, _quad_vbo) = unsafe { create_quad() };
-        let (hdr_fbo, hdr_color) = unsafe { create_hdr_buffer(width, height) };
+        let (skybox_vao, _skybox_vbo) = unsafe { create_skybox_cube() };
+        let (hdr_fbo, hdr_color) = unsafe { create_hdr_buffer(width, height)? };
+        let directional_shadow_size = 2048;
+        let point_shadow_size = 1024;
+        let mut texture_units = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_TEXTURE_IMAGE_UNITS, &mut texture_units);
+        }
+        let max_point_shadows = (texture_units.max(0) as usize)
+            .saturating_sub(POINT_SHADOW_UNIT as usize)
+            .min(MAX_POINT_SHADOWS);
+        let cascade_count = 4;
         let (directional_shadow_fbo, directional_shadow_map) =
-            unsafe { create_directional_shadow_buffer() };
+            unsafe { create_directional_shadow_buffer(directional_shadow_size, cascade_count)? };
 
         let point_shadow_fbos = Vec::new();
         let point_shadow_maps = Vec::new();
 
-        let geometry_shader = Shader::new(
-            include_str!("shaders/geometry.vsh"),
-            include_str!("shaders/geometry.fsh"),
-        )?;
-        let lighting_shader = Shader::new(
-            include_str!("shaders/lighting.vsh"),
-            include_str!("shaders/lighting.fsh"),
-        )?;
-        let composite_shader = Shader::new(
-            include_str!("shaders/composite.vsh"),
-            include_str!("shaders/composite.fsh"),
-        )?;
-        let directional_shadow_shader = Shader::new(
-            include_str!("shaders/directional_shadow.vsh"),
-            include_str!("shaders/directional_shadow.fsh"),
-        )?;
-        let point_shadow_shader = Shader::new(
-            include_str!("shaders/point_shadow.vsh"),
-            include_str!("shaders/point_shadow.fsh"),
-        )?;
-        let bloom_shader = Shader::new(
-            include_str!("shaders/bloom.vsh"),
-            include_str!("shaders/bloom.fsh"),
-        )?;
-        let blur_shader = Shader::new(
-            include_str!("shaders/blur.vsh"),
-            include_str!("shaders/blur.fsh"),
-        )?;
-        let light_sphere_shader = Shader::new(
-            include_str!("shaders/light_sphere.vsh"),
-            include_str!("shaders/light_sphere.fsh"),
-        )?;
-
-        let (bloom_fbo, bloom_color) = unsafe { create_bloom_buffer(width, height) };
-        let (pingpong_fbos, pingpong_color) = unsafe { create_pingpong_buffers(width, height) };
-
-        geometry_shader.use_program();
-
-        unsafe {
-            let loc = gl::GetUniformLocation(
-                geometry_shader.id,
-                std::ffi::CString::new("albedoColor").unwrap().as_ptr(),
-            );
-            gl::Uniform4f(loc, 0.95, 0.95, 0.95, 0.5);
-        }
-
-        lighting_shader.use_program();
-        lighting_shader.set_int("gPosition", 0);
-        lighting_shader.set_int("gNormal", 1);
-        lighting_shader.set_int("gAlbedoSpec", 2);
-        lighting_shader.set_int("directionalShadowMap", 3);
-
-        for i in 0..16 {
-            let uniform_name = format!("pointShadowMaps[{}]", i);
-            lighting_shader.set_int(&uniform_name, 4 + i);
-        }
-
-        composite_shader.use_program();
-        composite_shader.set_int("scene", 0);
-        composite_shader.set_int("bloomBlur", 1);
-        composite_shader.set_int("toneMappingMode", 1);
-        composite_shader.set_float("exposure", 1.0);
-        composite_shader.set_float("bloomIntensity", 1.0);
-
-        blur_shader.use_program();
-        blur_shader.set_int("image", 0);
-
-        bloom_shader.use_program();
-        bloom_shader.set_int("scene", 0);
-        bloom_shader.set_float("threshold", 0.05);
-
+        let BuiltinShaders {
+            geometry_shader,
+            lighting_shader,
+            composite_shader,
+            directional_shadow_shader,
+            point_shadow_shader,
+            bloom_shader,
+            bloom_downsample_shader,
+            bloom_upsample_shader,
+            light_sphere_shader,
+            fxaa_shader,
+            ssr_shader,
+            motion_blur_shader,
+            dof_shader,
+            skybox_shader,
+            forward_shader,
+            sprite_shader,
+            emissive_shader,
+            outline_shader,
+        } = BuiltinShaders::load(None)?;
+
+        let bloom_mip_count = 6;
+        let bloom_mips = unsafe { create_bloom_mips(width, height, bloom_mip_count)? };
+        let (ldr_fbo, ldr_color) = unsafe { create_color_buffer(width, height, gl::RGBA8, "LDR")? };
+        let (ssr_fbo, ssr_color) =
+            unsafe { create_color_buffer(width, height, gl::RGBA16F, "SSR")? };
+        let (motion_blur_fbo, motion_blur_color) =
+            unsafe { create_color_buffer(width, height, gl::RGBA16F, "Motion blur")? };
+        let (dof_fbo, dof_color) =
+            unsafe { create_color_buffer(width, height, gl::RGBA16F, "Depth of field")? };
+
+        let white_texture = texture::white_texture();
         let light_sphere = mesh::Mesh::icosphere(2);
 
-        Ok(Renderer {
+        let renderer = Renderer {
             g_buffer,
             g_position,
             g_normal,
             g_albedo_spec,
+            g_velocity,
+            g_specular,
             rbo_depth,
             hdr_fbo,
             hdr_color,
-            bloom_fbo,
-            bloom_color,
-            pingpong_fbos,
-            pingpong_color,
-            bloom_output: pingpong_color[0],
+            ssr_fbo,
+            ssr_color,
+            ssr_shader,
+            ssr_enabled: false,
+            ssr_steps: 48,
+            ssr_max_distance: 20.0,
+            motion_blur_fbo,
+            motion_blur_color,
+            motion_blur_shader,
+            motion_blur_strength: 0.0,
+            dof_fbo,
+            dof_color,
+            dof_shader,
+            dof_enabled: false,
+            dof_focus_distance: 10.0,
+            dof_aperture: 1.0,
+            bloom_mips,
+            bloom_mip_count,
             bloom_shader,
-            blur_shader,
+            bloom_downsample_shader,
+            bloom_upsample_shader,
             bloom_enabled: true,
             bloom_threshold: 0.3,
             bloom_intensity: 1.0,
+            bloom_radius: 0.005,
+            tone_mapping_mode: 1,
+            exposure: 1.0,
+            vignette_radius: 0.75,
+            vignette_softness: 0.45,
+            vignette_intensity: 0.0,
+            color_lut: 0,
+            chromatic_aberration: 0.0,
+            film_grain: 0.0,
+            clear_color: glam::Vec3::ZERO,
+            ambient_light: glam::Vec3::splat(0.1),
+            fog_mode: 0,
+            fog_color: glam::Vec3::splat(0.5),
+            fog_density: 0.02,
+            fog_start: 10.0,
+            fog_end: 100.0,
+            ldr_fbo,
+            ldr_color,
+            fxaa_shader,
+            fxaa_enabled: false,
+            output_fbo: 0,
+            output_color: 0,
+            output_viewport: None,
+            msaa_fbo: 0,
+            msaa_color: 0,
+            msaa_depth: 0,
+            msaa_samples: 0,
+            polygon_mode: gl::FILL,
+            cull_mode: None,
+            front_face: gl::CCW,
             directional_shadow_fbo,
             directional_shadow_map,
             point_shadow_fbos,
             point_shadow_maps,
+            directional_shadow_size,
+            point_shadow_size,
+            max_point_shadows,
+            cascade_count,
+            cascade_split_lambda: 0.75,
+            shadow_distance: 100.0,
+            shadow_softness: 3,
             geometry_shader,
             lighting_shader,
             composite_shader,
             directional_shadow_shader,
             point_shadow_shader,
+            forward_shader,
+            sprite_shader,
+            emissive_shader,
+            outline_shader,
             quad_vao,
+            skybox_vao,
+            skybox_shader,
+            white_texture,
             light_sphere,
             light_sphere_shader,
+            shader_dir: None,
             width,
             height,
-        })
+        };
+        renderer.configure_shader_uniforms();
+        check_gl_error("creating the renderer")?;
+
+        Ok(renderer)
     }
 
-    pub fn configure_point_lights(&mut self, num_point_lights: usize) {
+    /// Sets the constant sampler bindings and defaults on the built-in shaders.
+    fn configure_shader_uniforms(&self) {
+        self.geometry_shader.use_program();
+        self.geometry_shader
+            .set_vec4("albedoColor", &glam::Vec4::new(0.95, 0.95, 0.95, 0.5));
+
+        self.lighting_shader.use_program();
+        self.lighting_shader.set_int("gPosition", 0);
+        self.lighting_shader.set_int("gNormal", 1);
+        self.lighting_shader.set_int("gAlbedoSpec", 2);
+        self.lighting_shader.set_int("directionalShadowMap", 3);
+        self.lighting_shader.set_int("gSpecular", 4);
+
+        for i in 0..MAX_POINT_SHADOWS {
+            let uniform_name = format!("pointShadowMaps[{}]", i);
+            self.lighting_shader
+                .set_int(&uniform_name, (POINT_SHADOW_UNIT as usize + i) as i32);
+        }
+
+        self.composite_shader.use_program();
+        self.composite_shader.set_int("scene", 0);
+        self.composite_shader.set_int("bloomBlur", 1);
+        self.composite_shader.set_int("colorLut", 2);
+        self.composite_shader.set_int("toneMappingMode", 1);
+        self.composite_shader.set_float("exposure", 1.0);
+        self.composite_shader.set_float("bloomIntensity", 1.0);
+
+        self.bloom_downsample_shader.use_program();
+        self.bloom_downsample_shader.set_int("image", 0);
+
+        self.bloom_upsample_shader.use_program();
+        self.bloom_upsample_shader.set_int("image", 0);
+
+        self.fxaa_shader.use_program();
+        self.fxaa_shader.set_int("screen", 0);
+
+        self.ssr_shader.use_program();
+        self.ssr_shader.set_int("scene", 0);
+        self.ssr_shader.set_int("gPosition", 1);
+        self.ssr_shader.set_int("gNormal", 2);
+        self.ssr_shader.set_int("gAlbedoSpec", 3);
+
+        self.motion_blur_shader.use_program();
+        self.motion_blur_shader.set_int("scene", 0);
+        self.motion_blur_shader.set_int("gVelocity", 1);
+
+        self.dof_shader.use_program();
+        self.dof_shader.set_int("scene", 0);
+        self.dof_shader.set_int("gPosition", 1);
+        self.dof_shader.set_int("gNormal", 2);
+
+        self.skybox_shader.use_program();
+        self.skybox_shader.set_int("skybox", 0);
+
+        self.forward_shader.use_program();
+        self.forward_shader.set_int("albedoMap", 0);
+
+        self.sprite_shader.use_program();
+        self.sprite_shader.set_int("spriteTexture", 0);
+
+        self.bloom_shader.use_program();
+        self.bloom_shader.set_int("scene", 0);
+        self.bloom_shader.set_float("threshold", 0.05);
+
+        self.geometry_shader.use_program();
+        self.geometry_shader.set_int("albedoMap", 0);
+        self.geometry_shader.set_int("metallicRoughnessMap", 1);
+        self.geometry_shader.set_int("normalMap", 2);
+        self.geometry_shader.set_int("heightMap", 3);
+        self.geometry_shader.set_float("metallic", 0.0);
+        self.geometry_shader.set_float("roughness", 0.5);
+    }
+
+    /// Recompiles the built-in shaders, from `shader_dir` when set. On failure
+    /// the current programs are kept and the compile error is returned.
+    pub fn reload_shaders(&mut self) -> Result<(), String> {
+        let shaders = BuiltinShaders::load(self.shader_dir.as_deref())?;
+
+        self.geometry_shader = shaders.geometry_shader;
+        self.lighting_shader = shaders.lighting_shader;
+        self.composite_shader = shaders.composite_shader;
+        self.directional_shadow_shader = shaders.directional_shadow_shader;
+        self.point_shadow_shader = shaders.point_shadow_shader;
+        self.bloom_shader = shaders.bloom_shader;
+        self.bloom_downsample_shader = shaders.bloom_downsample_shader;
+        self.bloom_upsample_shader = shaders.bloom_upsample_shader;
+        self.light_sphere_shader = shaders.light_sphere_shader;
+        self.fxaa_shader = shaders.fxaa_shader;
+        self.ssr_shader = shaders.ssr_shader;
+        self.motion_blur_shader = shaders.motion_blur_shader;
+        self.dof_shader = shaders.dof_shader;
+        self.skybox_shader = shaders.skybox_shader;
+        self.forward_shader = shaders.forward_shader;
+        self.sprite_shader = shaders.sprite_shader;
+        self.emissive_shader = shaders.emissive_shader;
+        self.outline_shader = shaders.outline_shader;
+
+        self.configure_shader_uniforms();
+        Ok(())
+    }
+
+    /// The most point lights that can cast shadows at once: the shader's
+    /// sampler array size, or fewer if the GPU lacks the texture units.
+    pub fn max_point_shadows(&self) -> usize {
+        self.max_point_shadows
+    }
+
+    pub fn configure_point_lights(&mut self, num_point_lights: usize) -> Result<(), String> {
+        if num_point_lights > self.max_point_shadows {
+            return Err(format!(
+                "{} point shadow maps requested, but at most {} are supported",
+                num_point_lights, self.max_point_shadows
+            ));
+        }
         unsafe {
             for fbos in &self.point_shadow_fbos {
                 gl::DeleteFramebuffers(6, fbos.as_ptr());
@@ -181,77 +540,529 @@ impl Renderer {
         self.point_shadow_maps.clear();
 
         for _ in 0..num_point_lights {
-            let (fbos, map) = unsafe { create_point_shadow_buffer() };
+            let (fbos, map) = unsafe { create_point_shadow_buffer(self.point_shadow_size)? };
             self.point_shadow_fbos.push(fbos);
             self.point_shadow_maps.push(map);
         }
+        Ok(())
+    }
+
+    pub fn set_shadow_resolution(
+        &mut self,
+        directional_size: u32,
+        point_size: u32,
+    ) -> Result<(), String> {
+        self.directional_shadow_size = directional_size.max(1);
+        self.point_shadow_size = point_size.max(1);
+        self.recreate_directional_shadow_buffer()?;
+
+        let num_point_lights = self.point_shadow_maps.len();
+        self.configure_point_lights(num_point_lights)
+    }
+
+    /// Sets how many cascades the directional shadow is split into and how
+    /// they are spaced over `distance`.
+    pub fn set_shadow_cascades(
+        &mut self,
+        count: usize,
+        split_lambda: f32,
+        distance: f32,
+    ) -> Result<(), String> {
+        self.cascade_count = count.clamp(1, cascades::MAX_CASCADES);
+        self.cascade_split_lambda = split_lambda.clamp(0.0, 1.0);
+        self.shadow_distance = distance.max(Self::CASCADE_NEAR * 2.0);
+        self.recreate_directional_shadow_buffer()
     }
 
-    pub fn begin_composite_pass(&self) {
+    fn recreate_directional_shadow_buffer(&mut self) -> Result<(), String> {
         unsafe {
-            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            gl::DeleteFramebuffers(1, &self.directional_shadow_fbo);
+            gl::DeleteTextures(1, &self.directional_shadow_map);
+
+            let (fbo, map) =
+                create_directional_shadow_buffer(self.directional_shadow_size, self.cascade_count)?;
+            self.directional_shadow_fbo = fbo;
+            self.directional_shadow_map = map;
+        }
+        Ok(())
+    }
+
+    /// Far distance of each shadow cascade, measured along the view direction.
+    pub fn cascade_splits(&self) -> Vec<f32> {
+        cascades::split_distances(
+            Self::CASCADE_NEAR,
+            self.shadow_distance,
+            self.cascade_count,
+            self.cascade_split_lambda,
+        )
+    }
+
+    /// `time` is in seconds and animates the film grain.
+    pub fn begin_composite_pass(&self, time: f32) {
+        // With FXAA on, the composite is written to an intermediate LDR target
+        // which `apply_fxaa` then resolves onto the output framebuffer.
+        if self.fxaa_enabled {
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, self.ldr_fbo);
+                gl::Viewport(0, 0, self.width as i32, self.height as i32);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            }
+        } else {
+            self.bind_output();
         }
         self.composite_shader.use_program();
         self.composite_shader.set_int("scene", 0);
         self.composite_shader.set_int("bloomBlur", 1);
-        self.composite_shader.set_int("toneMappingMode", 1);
-        self.composite_shader.set_float("exposure", 1.0);
         self.composite_shader
-            .set_float("bloomIntensity", self.bloom_intensity);
+            .set_int("toneMappingMode", self.tone_mapping_mode);
+        self.composite_shader.set_float("exposure", self.exposure);
+        // The mip chain is left untouched while bloom is off, so it must not
+        // be sampled either.
+        let bloom_intensity = if self.bloom_enabled {
+            self.bloom_intensity
+        } else {
+            0.0
+        };
+        self.composite_shader
+            .set_float("bloomIntensity", bloom_intensity);
+        self.composite_shader
+            .set_float("vignetteRadius", self.vignette_radius);
+        self.composite_shader
+            .set_float("vignetteSoftness", self.vignette_softness);
+        self.composite_shader
+            .set_float("vignetteIntensity", self.vignette_intensity);
+        self.composite_shader
+            .set_bool("colorLutEnabled", self.color_lut != 0);
+        self.composite_shader
+            .set_float("chromaticAberration", self.chromatic_aberration);
+        self.composite_shader
+            .set_float("filmGrain", self.film_grain);
+        self.composite_shader.set_float("time", time);
+
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.scene_color());
+
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.bloom_mips[0].texture);
+
+            gl::ActiveTexture(gl::TEXTURE2);
+            gl::BindTexture(gl::TEXTURE_3D, self.color_lut);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+    }
 
+    /// Adds screen-space reflections to the lit HDR frame: each pixel's
+    /// reflected view ray is marched against the G-buffer positions and, on
+    /// a hit, blended in by the surface's Fresnel term and smoothness.
+    pub fn apply_ssr(&self, view: &glam::Mat4, projection: &glam::Mat4, view_pos: &glam::Vec3) {
+        if !self.ssr_enabled || self.forward_rendering() {
+            return;
+        }
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.ssr_fbo);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+            gl::Disable(gl::DEPTH_TEST);
+        }
+        self.ssr_shader.use_program();
+        self.ssr_shader.set_mat4("view", view);
+        self.ssr_shader.set_mat4("projection", projection);
+        self.ssr_shader.set_vec3("viewPos", view_pos);
+        self.ssr_shader.set_int("maxSteps", self.ssr_steps as i32);
+        self.ssr_shader
+            .set_float("maxDistance", self.ssr_max_distance);
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, self.hdr_color);
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.g_position);
+            gl::ActiveTexture(gl::TEXTURE2);
+            gl::BindTexture(gl::TEXTURE_2D, self.g_normal);
+            gl::ActiveTexture(gl::TEXTURE3);
+            gl::BindTexture(gl::TEXTURE_2D, self.g_albedo_spec);
+        }
+        self.render_quad();
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
 
+    /// Blurs the lit HDR frame along each pixel's velocity into the motion
+    /// blur target, which bloom and the composite then read instead.
+    pub fn apply_motion_blur(&self) {
+        if self.motion_blur_strength <= 0.0 || self.forward_rendering() {
+            return;
+        }
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.motion_blur_fbo);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+            gl::Disable(gl::DEPTH_TEST);
+        }
+        self.motion_blur_shader.use_program();
+        self.motion_blur_shader
+            .set_float("strength", self.motion_blur_strength);
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.ssr_output());
             gl::ActiveTexture(gl::TEXTURE1);
-            gl::BindTexture(gl::TEXTURE_2D, self.bloom_output);
+            gl::BindTexture(gl::TEXTURE_2D, self.g_velocity);
+        }
+        self.render_quad();
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
         }
     }
 
-    pub fn resize(&mut self, width: u32, height: u32) {
-        self.width = width;
-        self.height = height;
+    /// Blurs the frame by each pixel's circle of confusion, computed from
+    /// its distance to `view_pos` against the focus distance.
+    pub fn apply_dof(&self, view_pos: &glam::Vec3) {
+        if !self.dof_enabled || self.forward_rendering() {
+            return;
+        }
         unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.dof_fbo);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+            gl::Disable(gl::DEPTH_TEST);
+        }
+        self.dof_shader.use_program();
+        self.dof_shader.set_vec3("viewPos", view_pos);
+        self.dof_shader
+            .set_float("focusDistance", self.dof_focus_distance);
+        self.dof_shader.set_float("aperture", self.dof_aperture);
+        self.dof_shader.set_vec2(
+            "texelSize",
+            &glam::Vec2::new(1.0 / self.width as f32, 1.0 / self.height as f32),
+        );
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.motion_blur_output());
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, self.g_position);
+            gl::ActiveTexture(gl::TEXTURE2);
+            gl::BindTexture(gl::TEXTURE_2D, self.g_normal);
+        }
+        self.render_quad();
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    /// The HDR frame with reflections, or the lit buffer when SSR is off.
+    fn ssr_output(&self) -> u32 {
+        if self.ssr_enabled && !self.forward_rendering() {
+            self.ssr_color
+        } else {
+            self.hdr_color
+        }
+    }
+
+    /// The HDR frame after motion blur, or the previous stage's when it is
+    /// off.
+    fn motion_blur_output(&self) -> u32 {
+        if self.motion_blur_strength > 0.0 && !self.forward_rendering() {
+            self.motion_blur_color
+        } else {
+            self.ssr_output()
+        }
+    }
+
+    /// The HDR frame bloom and the composite read, after whichever of
+    /// reflections, motion blur and depth of field ran.
+    fn scene_color(&self) -> u32 {
+        if self.dof_enabled && !self.forward_rendering() {
+            self.dof_color
+        } else {
+            self.motion_blur_output()
+        }
+    }
+
+    /// Replaces the color grading table with one built from a 2D texture
+    /// holding an `n*n` by `n` strip of slices: red runs across each slice,
+    /// green down it, and blue steps from one slice to the next. `None`
+    /// removes grading.
+    pub fn set_color_lut(&mut self, texture: Option<u32>) -> Result<(), String> {
+        let lut = match texture {
+            Some(texture) => unsafe { create_color_lut(texture)? },
+            None => 0,
+        };
+        if self.color_lut != 0 {
+            unsafe {
+                gl::DeleteTextures(1, &self.color_lut);
+            }
+        }
+        self.color_lut = lut;
+        Ok(())
+    }
+
+    pub fn apply_fxaa(&self) {
+        if !self.fxaa_enabled {
+            return;
+        }
+        self.bind_output();
+        self.fxaa_shader.use_program();
+        self.fxaa_shader.set_vec2(
+            "texelSize",
+            &glam::Vec2::new(1.0 / self.width as f32, 1.0 / self.height as f32),
+        );
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.ldr_color);
+        }
+        self.render_quad();
+    }
+
+    /// Binds the output framebuffer for the last full-screen pass. The whole
+    /// window is cleared first; a sub-viewport is drawn over what is there.
+    fn bind_output(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.output_fbo);
+            match self.output_viewport {
+                Some((x, y, width, height)) => {
+                    // GL counts rows from the bottom of the window.
+                    let bottom = self.height as i32 - y - height as i32;
+                    gl::Viewport(x, bottom, width as i32, height as i32);
+                }
+                None => {
+                    gl::Viewport(0, 0, self.width as i32, self.height as i32);
+                    gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+                }
+            }
+        }
+    }
+
+    /// Draws sprites over the finished frame in order, so later sprites
+    /// cover earlier ones.
+    pub fn render_sprites(&self, sprites: &[sprite::SpriteDrawInfo]) {
+        if sprites.is_empty() {
+            return;
+        }
+        let projection = glam::Mat4::orthographic_rh_gl(
+            0.0,
+            self.width as f32,
+            self.height as f32,
+            0.0,
+            -1.0,
+            1.0,
+        );
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.output_fbo);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+            gl::Disable(gl::DEPTH_TEST);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        self.sprite_shader.use_program();
+        self.sprite_shader.set_mat4("projection", &projection);
+
+        for sprite in sprites {
+            let texture = if sprite.texture_id != 0 {
+                sprite.texture_id
+            } else {
+                self.white_texture
+            };
+            unsafe {
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+            self.sprite_shader.set_vec2("spritePos", &sprite.position);
+            self.sprite_shader.set_vec2("spriteSize", &sprite.size);
+            self.sprite_shader.set_vec4("tint", &sprite.tint);
+            self.render_quad();
+        }
+
+        unsafe {
+            gl::Disable(gl::BLEND);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+    }
+
+    /// Draws finished frames into an offscreen buffer instead of the window.
+    pub fn enable_offscreen_output(&mut self) -> Result<(), String> {
+        if self.output_fbo != 0 {
+            return Ok(());
+        }
+        let (fbo, color) =
+            unsafe { create_color_buffer(self.width, self.height, gl::RGBA8, "LDR")? };
+        self.output_fbo = fbo;
+        self.output_color = color;
+        Ok(())
+    }
+
+    /// Switches to forward rendering into a multisampled buffer with up to
+    /// `samples` samples per pixel. The G-buffer passes, and the effects that
+    /// read it, are skipped from then on.
+    pub fn enable_forward_msaa(&mut self, samples: u32) -> Result<(), String> {
+        if self.msaa_fbo != 0 {
+            return Ok(());
+        }
+        let mut max_samples = 0;
+        unsafe {
+            gl::GetIntegerv(gl::MAX_SAMPLES, &mut max_samples);
+        }
+        self.msaa_samples = samples.min(max_samples.max(1) as u32);
+        let (fbo, color, depth) =
+            unsafe { create_msaa_buffer(self.width, self.height, self.msaa_samples)? };
+        self.msaa_fbo = fbo;
+        self.msaa_color = color;
+        self.msaa_depth = depth;
+        Ok(())
+    }
+
+    /// Whether frames are forward rendered into the multisampled buffer
+    /// instead of going through the G-buffer.
+    pub fn forward_rendering(&self) -> bool {
+        self.msaa_fbo != 0
+    }
+
+    /// The buffer lit geometry is drawn into: the multisampled one on the
+    /// forward path, the HDR buffer otherwise.
+    fn scene_fbo(&self) -> u32 {
+        if self.forward_rendering() {
+            self.msaa_fbo
+        } else {
+            self.hdr_fbo
+        }
+    }
+
+    /// Reads back the last finished frame as tightly packed RGBA rows, top
+    /// row first. Without an offscreen buffer this reads the window's front
+    /// buffer.
+    pub fn read_pixels(&self) -> Vec<u8> {
+        let row = self.width as usize * 4;
+        let mut pixels = vec![0u8; row * self.height as usize];
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.output_fbo);
+            gl::ReadBuffer(if self.output_fbo != 0 {
+                gl::COLOR_ATTACHMENT0
+            } else {
+                gl::FRONT
+            });
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_mut_ptr() as *mut _,
+            );
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, 0);
+        }
+
+        // GL rows start at the bottom of the image.
+        let mut flipped = Vec::with_capacity(pixels.len());
+        for line in pixels.chunks_exact(row).rev() {
+            flipped.extend_from_slice(line);
+        }
+        flipped
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), String> {
+        unsafe {
+            // Build every new target before deleting the old ones, so a
+            // failure leaves the renderer holding live objects.
+            let (gb, gp, gn, ga, gv, gs, rdo) = create_g_buffer(width, height)?;
+            let (hf, hc) = create_hdr_buffer(width, height)?;
+            let (sf, sc) = create_color_buffer(width, height, gl::RGBA16F, "SSR")?;
+            let (mf, mc) = create_color_buffer(width, height, gl::RGBA16F, "Motion blur")?;
+            let (df, dc) = create_color_buffer(width, height, gl::RGBA16F, "Depth of field")?;
+            let bloom_mips = create_bloom_mips(width, height, self.bloom_mip_count)?;
+            let (lf, lc) = create_color_buffer(width, height, gl::RGBA8, "LDR")?;
+            let output = if self.output_fbo != 0 {
+                Some(create_color_buffer(width, height, gl::RGBA8, "LDR")?)
+            } else {
+                None
+            };
+            let msaa = if self.msaa_fbo != 0 {
+                Some(create_msaa_buffer(width, height, self.msaa_samples)?)
+            } else {
+                None
+            };
+
             gl::DeleteFramebuffers(1, &self.g_buffer);
             gl::DeleteTextures(1, &self.g_position);
             gl::DeleteTextures(1, &self.g_normal);
             gl::DeleteTextures(1, &self.g_albedo_spec);
+            gl::DeleteTextures(1, &self.g_velocity);
+            gl::DeleteTextures(1, &self.g_specular);
             gl::DeleteRenderbuffers(1, &self.rbo_depth);
             gl::DeleteFramebuffers(1, &self.hdr_fbo);
             gl::DeleteTextures(1, &self.hdr_color);
+            gl::DeleteFramebuffers(1, &self.ssr_fbo);
+            gl::DeleteTextures(1, &self.ssr_color);
+            gl::DeleteFramebuffers(1, &self.motion_blur_fbo);
+            gl::DeleteTextures(1, &self.motion_blur_color);
+            gl::DeleteFramebuffers(1, &self.dof_fbo);
+            gl::DeleteTextures(1, &self.dof_color);
+            delete_bloom_mips(&self.bloom_mips);
+            gl::DeleteFramebuffers(1, &self.ldr_fbo);
+            gl::DeleteTextures(1, &self.ldr_color);
 
-            gl::DeleteFramebuffers(1, &self.bloom_fbo);
-            gl::DeleteTextures(1, &self.bloom_color);
-            gl::DeleteFramebuffers(2, self.pingpong_fbos.as_ptr());
-            gl::DeleteTextures(2, self.pingpong_color.as_ptr());
-
-            let (gb, gp, gn, ga, rdo) = create_g_buffer(width, height);
             self.g_buffer = gb;
             self.g_position = gp;
             self.g_normal = gn;
             self.g_albedo_spec = ga;
+            self.g_velocity = gv;
+            self.g_specular = gs;
             self.rbo_depth = rdo;
-            let (hf, hc) = create_hdr_buffer(width, height);
             self.hdr_fbo = hf;
             self.hdr_color = hc;
+            self.ssr_fbo = sf;
+            self.ssr_color = sc;
+            self.motion_blur_fbo = mf;
+            self.motion_blur_color = mc;
+            self.dof_fbo = df;
+            self.dof_color = dc;
+            self.bloom_mips = bloom_mips;
+            self.ldr_fbo = lf;
+            self.ldr_color = lc;
+
+            if let Some((of, oc)) = output {
+                gl::DeleteFramebuffers(1, &self.output_fbo);
+                gl::DeleteTextures(1, &self.output_color);
+                self.output_fbo = of;
+                self.output_color = oc;
+            }
 
-            let (bf, bc) = create_bloom_buffer(width, height);
-            self.bloom_fbo = bf;
-            self.bloom_color = bc;
-            let (pp_fbos, pp_cols) = create_pingpong_buffers(width, height);
-            self.pingpong_fbos = pp_fbos;
-            self.pingpong_color = pp_cols;
-            self.bloom_output = self.pingpong_color[0];
+            if let Some((mf, mc, md)) = msaa {
+                gl::DeleteFramebuffers(1, &self.msaa_fbo);
+                gl::DeleteRenderbuffers(1, &self.msaa_color);
+                gl::DeleteRenderbuffers(1, &self.msaa_depth);
+                self.msaa_fbo = mf;
+                self.msaa_color = mc;
+                self.msaa_depth = md;
+            }
         }
+        self.width = width;
+        self.height = height;
+        check_gl_error("resizing render targets")
     }
 
     pub fn begin_geometry_pass(&self) {
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, self.g_buffer);
             gl::Viewport(0, 0, self.width as i32, self.height as i32);
+            // Always zero: the lighting pass reads a zero normal as "no geometry",
+            // and motion blur reads zero velocity as "still".
             gl::ClearColor(0.0, 0.0, 0.0, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            // Zero alpha in the specular target marks texels no shader wrote,
+            // e.g. from custom shaders; lighting treats them as untinted.
+            gl::ClearBufferfv(gl::COLOR, 4, [0.0f32; 4].as_ptr());
+            gl::PolygonMode(gl::FRONT_AND_BACK, self.polygon_mode);
+            gl::FrontFace(self.front_face);
+            if let Some(face) = self.cull_mode {
+                gl::Enable(gl::CULL_FACE);
+                gl::CullFace(face);
+            }
         }
         self.geometry_shader.use_program();
     }
@@ -260,8 +1071,70 @@ impl Renderer {
         &self.geometry_shader
     }
 
+    /// Uploads the material parameters for the next geometry draw. Without a
+    /// material the mesh is treated as a rough dielectric and its own colors
+    /// are left untouched.
+    pub fn bind_material(&self, shader: &Shader, material: Option<&MaterialParams>) {
+        let or_white = |id: u32| if id != 0 { id } else { self.white_texture };
+
+        let (metallic, roughness, albedo_map, metallic_roughness_map, normal_map) = match material {
+            Some(m) => (
+                m.metallic,
+                m.roughness,
+                or_white(m.albedo_map),
+                or_white(m.metallic_roughness_map),
+                m.normal_map,
+            ),
+            None => (0.0, 0.5, self.white_texture, self.white_texture, 0),
+        };
+
+        shader.set_float("metallic", metallic);
+        shader.set_float("roughness", roughness);
+        shader.set_vec3(
+            "specularColor",
+            &material.map_or(glam::Vec3::ONE, |m| m.specular_color),
+        );
+        shader.set_bool("normalMapEnabled", normal_map != 0);
+        let height_map = material.map_or(0, |m| m.height_map);
+        shader.set_bool("heightMapEnabled", height_map != 0);
+        if let Some(m) = material {
+            shader.set_vec4("albedoColor", &m.albedo.extend(1.0));
+            shader.set_float("heightScale", m.height_scale);
+            shader.set_int("parallaxSteps", m.parallax_steps as i32);
+        }
+
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, albedo_map);
+            gl::ActiveTexture(gl::TEXTURE1);
+            gl::BindTexture(gl::TEXTURE_2D, metallic_roughness_map);
+            gl::ActiveTexture(gl::TEXTURE2);
+            gl::BindTexture(gl::TEXTURE_2D, normal_map);
+            gl::ActiveTexture(gl::TEXTURE3);
+            gl::BindTexture(gl::TEXTURE_2D, height_map);
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+    }
+
+    /// Sets depth testing and depth writes for the following draws.
+    pub fn set_depth_state(&self, test: bool, write: bool) {
+        unsafe {
+            if test {
+                gl::Enable(gl::DEPTH_TEST);
+            } else {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+            gl::DepthMask(if write { gl::TRUE } else { gl::FALSE });
+        }
+    }
+
     pub fn end_geometry_pass(&self) {
         unsafe {
+            // The screen-space passes that follow must always rasterize filled quads.
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+            gl::Disable(gl::CULL_FACE);
+            gl::CullFace(gl::BACK);
+            gl::FrontFace(gl::CCW);
             gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
         }
     }
@@ -270,32 +1143,52 @@ impl Renderer {
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, self.hdr_fbo);
             gl::Viewport(0, 0, self.width as i32, self.height as i32);
-            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
+            gl::ClearColor(
+                self.clear_color.x,
+                self.clear_color.y,
+                self.clear_color.z,
+                1.0,
+            );
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
             self.lighting_shader.use_program();
+            self.lighting_shader
+                .set_vec3("ambientLight", &self.ambient_light);
+            self.lighting_shader.set_int("fogMode", self.fog_mode);
+            self.lighting_shader.set_vec3("fogColor", &self.fog_color);
+            self.lighting_shader
+                .set_float("fogDensity", self.fog_density);
+            self.lighting_shader.set_float("fogStart", self.fog_start);
+            self.lighting_shader.set_float("fogEnd", self.fog_end);
+            self.lighting_shader
+                .set_int("shadowKernelRadius", self.shadow_softness / 2);
             gl::ActiveTexture(gl::TEXTURE0);
             gl::BindTexture(gl::TEXTURE_2D, self.g_position);
             gl::ActiveTexture(gl::TEXTURE1);
             gl::BindTexture(gl::TEXTURE_2D, self.g_normal);
             gl::ActiveTexture(gl::TEXTURE2);
             gl::BindTexture(gl::TEXTURE_2D, self.g_albedo_spec);
+            gl::ActiveTexture(gl::TEXTURE4);
+            gl::BindTexture(gl::TEXTURE_2D, self.g_specular);
         }
     }
 
+    /// Renders the bright-pass of the HDR buffer into the first (half
+    /// resolution) level of the bloom mip chain.
     pub fn begin_bloom_extract_pass(&self) {
         if !self.bloom_enabled {
             return;
         }
+        let mip = &self.bloom_mips[0];
         unsafe {
-            gl::BindFramebuffer(gl::FRAMEBUFFER, self.bloom_fbo);
-            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, mip.fbo);
+            gl::Viewport(0, 0, mip.width as i32, mip.height as i32);
             gl::ClearColor(0.0, 0.0, 0.0, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT);
         }
         self.bloom_shader.use_program();
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0);
-            gl::BindTexture(gl::TEXTURE_2D, self.hdr_color);
+            gl::BindTexture(gl::TEXTURE_2D, self.scene_color());
         }
         self.bloom_shader
             .set_float("threshold", self.bloom_threshold);
@@ -310,81 +1203,424 @@ impl Renderer {
         }
     }
 
-    pub fn apply_gaussian_blur(&mut self, iterations: i32) {
+    pub fn bloom_mip_count(&self) -> usize {
+        self.bloom_mip_count
+    }
+
+    /// Rebuilds the bloom mip chain with `count` levels, clamped to
+    /// `1..=MAX_BLOOM_MIPS`. More levels give a wider, softer glow.
+    pub fn set_bloom_mip_count(&mut self, count: usize) -> Result<(), String> {
+        let count = count.clamp(1, MAX_BLOOM_MIPS);
+        if count == self.bloom_mip_count {
+            return Ok(());
+        }
+        unsafe {
+            let mips = create_bloom_mips(self.width, self.height, count)?;
+            delete_bloom_mips(&self.bloom_mips);
+            self.bloom_mips = mips;
+        }
+        self.bloom_mip_count = count;
+        Ok(())
+    }
+
+    /// Blurs the bright-pass by walking it down the mip chain with a 13-tap
+    /// filter and then back up, adding each tent-filtered level onto the next
+    /// larger one. The result ends up in the first mip.
+    pub fn apply_bloom_blur(&self) {
         if !self.bloom_enabled {
-            self.bloom_output = self.pingpong_color[0];
             return;
         }
 
-        let mut horizontal = true;
-        let mut first_iteration = true;
-        let mut read_tex: u32;
+        self.bloom_downsample_shader.use_program();
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+        }
+        for pair in self.bloom_mips.windows(2) {
+            let (source, target) = (&pair[0], &pair[1]);
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+                gl::Viewport(0, 0, target.width as i32, target.height as i32);
+                gl::BindTexture(gl::TEXTURE_2D, source.texture);
+            }
+            self.render_quad();
+        }
+
+        self.bloom_upsample_shader.use_program();
+        self.bloom_upsample_shader
+            .set_float("filterRadius", self.bloom_radius);
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::ONE, gl::ONE);
+            gl::BlendEquation(gl::FUNC_ADD);
+        }
+        for pair in self.bloom_mips.windows(2).rev() {
+            let (target, source) = (&pair[0], &pair[1]);
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+                gl::Viewport(0, 0, target.width as i32, target.height as i32);
+                gl::BindTexture(gl::TEXTURE_2D, source.texture);
+            }
+            self.render_quad();
+        }
+        unsafe {
+            gl::Disable(gl::BLEND);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+    }
+
+    pub fn render_quad(&self) {
+        unsafe {
+            gl::BindVertexArray(self.quad_vao);
+            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+            gl::BindVertexArray(0);
+        }
+    }
+
+    pub fn render_sphere_at(
+        &self,
+        model: &glam::Mat4,
+        view: &glam::Mat4,
+        projection: &glam::Mat4,
+        color: &glam::Vec3,
+    ) {
+        self.light_sphere_shader.use_program();
+        self.light_sphere_shader.set_mat4("model", model);
+        self.light_sphere_shader.set_mat4("view", view);
+        self.light_sphere_shader.set_mat4("projection", projection);
+        self.light_sphere_shader.set_vec3("color", color);
+        let (scale, _rot, _trans) = model.to_scale_rotation_translation();
+        let s = scale.x.max(scale.y).max(scale.z);
+        let intensity = s * 20.0;
+        self.light_sphere_shader.set_float("intensity", intensity);
 
-        self.blur_shader.use_program();
+        unsafe {
+            gl::Enable(gl::DEPTH_TEST);
+            gl::DepthMask(gl::TRUE);
+        }
+
+        self.light_sphere.draw();
+    }
+
+    /// Draws the cubemap behind everything already in the HDR buffer. The
+    /// translation is stripped from `view` so the box stays centered on the camera.
+    pub fn render_skybox(&self, cubemap: u32, view: &glam::Mat4, projection: &glam::Mat4) {
+        let view = glam::Mat4::from_mat3(glam::Mat3::from_mat4(*view));
+
+        self.skybox_shader.use_program();
+        self.skybox_shader.set_mat4("view", &view);
+        self.skybox_shader.set_mat4("projection", projection);
+
+        unsafe {
+            gl::DepthFunc(gl::LEQUAL);
+            gl::DepthMask(gl::FALSE);
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_CUBE_MAP, cubemap);
+            gl::BindVertexArray(self.skybox_vao);
+            gl::DrawArrays(gl::TRIANGLES, 0, 36);
+            gl::BindVertexArray(0);
+
+            gl::DepthMask(gl::TRUE);
+            gl::DepthFunc(gl::LESS);
+        }
+    }
+
+    fn use_forward_shader(
+        &self,
+        view: &glam::Mat4,
+        projection: &glam::Mat4,
+        view_pos: &glam::Vec3,
+    ) {
+        let shader = &self.forward_shader;
+        shader.use_program();
+        shader.set_mat4("view", view);
+        shader.set_mat4("projection", projection);
+        shader.set_vec3("viewPos", view_pos);
+        shader.set_vec3("ambientLight", &self.ambient_light);
+        shader.set_int("fogMode", self.fog_mode);
+        shader.set_vec3("fogColor", &self.fog_color);
+        shader.set_float("fogDensity", self.fog_density);
+        shader.set_float("fogStart", self.fog_start);
+        shader.set_float("fogEnd", self.fog_end);
+    }
+
+    /// Clears the multisampled buffer and prepares to shade opaque surfaces
+    /// straight into it with the forward shader.
+    pub fn begin_forward_pass(
+        &self,
+        view: &glam::Mat4,
+        projection: &glam::Mat4,
+        view_pos: &glam::Vec3,
+    ) {
+        self.use_forward_shader(view, projection, view_pos);
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.msaa_fbo);
+            gl::Viewport(0, 0, self.width as i32, self.height as i32);
+            gl::ClearColor(
+                self.clear_color.x,
+                self.clear_color.y,
+                self.clear_color.z,
+                1.0,
+            );
+            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+            gl::Enable(gl::MULTISAMPLE);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::PolygonMode(gl::FRONT_AND_BACK, self.polygon_mode);
+            gl::FrontFace(self.front_face);
+            if let Some(face) = self.cull_mode {
+                gl::Enable(gl::CULL_FACE);
+                gl::CullFace(face);
+            }
+        }
+    }
+
+    pub fn end_forward_pass(&self) {
+        unsafe {
+            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
+            gl::Disable(gl::CULL_FACE);
+            gl::CullFace(gl::BACK);
+            gl::FrontFace(gl::CCW);
+        }
+    }
+
+    /// Averages the multisampled buffer down into the HDR buffer the post
+    /// passes read. Does nothing on the deferred path.
+    pub fn resolve_msaa(&self) {
+        if !self.forward_rendering() {
+            return;
+        }
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.msaa_fbo);
+            gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, self.hdr_fbo);
+            gl::BlitFramebuffer(
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                gl::COLOR_BUFFER_BIT,
+                gl::NEAREST,
+            );
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.hdr_fbo);
+        }
+    }
+
+    /// Prepares the forward pass that blends transparent surfaces over the lit
+    /// HDR buffer. Depth is tested against the opaque scene but not written.
+    pub fn begin_transparent_pass(
+        &self,
+        view: &glam::Mat4,
+        projection: &glam::Mat4,
+        view_pos: &glam::Vec3,
+    ) {
+        self.use_forward_shader(view, projection, view_pos);
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_fbo());
+            gl::Enable(gl::DEPTH_TEST);
+            gl::DepthMask(gl::FALSE);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+        }
+    }
+
+    /// Draws one mesh with the forward shader. Colors follow the same
+    /// precedence as the geometry pass: material, then submesh, then mesh.
+    /// `time` is the engine's elapsed time, which scrolls the texture.
+    pub fn draw_forward_mesh(
+        &self,
+        mesh: &mesh::MeshDrawInfo,
+        material: Option<&MaterialParams>,
+        model: &glam::Mat4,
+        opacity: f32,
+        time: f32,
+    ) {
+        let shader = &self.forward_shader;
+        shader.set_mat4("model", model);
+        shader.set_vec2("uvScale", &mesh.uv_scale);
+        shader.set_vec2("uvOffset", &mesh.uv_offset_at(time));
+
+        let rgba = |color: Option<(u8, u8, u8, u8)>| match color {
+            Some((r, g, b, a)) => glam::Vec4::new(
+                r as f32 / 255.0,
+                g as f32 / 255.0,
+                b as f32 / 255.0,
+                a as f32 / 255.0 * opacity,
+            ),
+            None => glam::Vec4::new(1.0, 1.0, 1.0, opacity),
+        };
+
+        if let Some(m) = material {
+            let texture = if m.albedo_map != 0 {
+                m.albedo_map
+            } else {
+                self.white_texture
+            };
+            shader.set_float("metallic", m.metallic);
+            shader.set_float("roughness", m.roughness);
+            shader.set_vec3("specularColor", &m.specular_color);
+            shader.set_vec3("emissive", &m.emissive);
+            shader.set_vec4("albedoColor", &m.albedo.extend(opacity));
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+            mesh.draw();
+            return;
+        }
+
+        shader.set_float("metallic", 0.0);
+        shader.set_float("roughness", 0.5);
+        shader.set_vec3("specularColor", &glam::Vec3::ONE);
+        shader.set_vec3("emissive", &glam::Vec3::ZERO);
+
+        if !mesh.submeshes.is_empty() {
+            for submesh in &mesh.submeshes {
+                shader.set_vec4("albedoColor", &rgba(submesh.color));
+                mesh::draw_submesh(mesh.vao, submesh);
+            }
+        } else {
+            let texture = if mesh.texture_id != 0 {
+                mesh.texture_id
+            } else {
+                self.white_texture
+            };
+            shader.set_vec4("albedoColor", &rgba(mesh.color));
+            unsafe {
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+            }
+            mesh.draw();
+        }
+    }
+
+    /// Prepares the pass that adds emissive light onto opaque surfaces in the
+    /// HDR buffer, matching the depth the geometry pass wrote.
+    pub fn begin_emissive_pass(
+        &self,
+        view: &glam::Mat4,
+        projection: &glam::Mat4,
+        view_pos: &glam::Vec3,
+    ) {
+        let shader = &self.emissive_shader;
+        shader.use_program();
+        shader.set_mat4("view", view);
+        shader.set_mat4("projection", projection);
+        shader.set_vec3("viewPos", view_pos);
+        shader.set_int("fogMode", self.fog_mode);
+        shader.set_float("fogDensity", self.fog_density);
+        shader.set_float("fogStart", self.fog_start);
+        shader.set_float("fogEnd", self.fog_end);
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.hdr_fbo);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::DepthFunc(gl::LEQUAL);
+            gl::DepthMask(gl::FALSE);
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::ONE, gl::ONE);
+        }
+    }
 
-        for _ in 0..iterations {
-            let idx = if horizontal { 1 } else { 0 };
-            unsafe {
-                gl::BindFramebuffer(gl::FRAMEBUFFER, self.pingpong_fbos[idx]);
-                gl::Viewport(0, 0, self.width as i32, self.height as i32);
-            }
-            self.blur_shader
-                .set_int("horizontal", if horizontal { 1 } else { 0 });
+    pub fn draw_emissive_mesh(
+        &self,
+        mesh: &mesh::MeshDrawInfo,
+        model: &glam::Mat4,
+        emissive: &glam::Vec3,
+        bones: Option<&[glam::Mat4]>,
+    ) {
+        let shader = &self.emissive_shader;
+        shader.set_mat4("model", model);
+        shader.set_vec3("emissive", emissive);
+        animation::bind_bones(shader, bones);
+        mesh.draw();
+    }
 
-            unsafe {
-                gl::ActiveTexture(gl::TEXTURE0);
-            }
-            if first_iteration {
-                read_tex = self.bloom_color;
-                first_iteration = false;
-            } else {
-                read_tex = self.pingpong_color[if horizontal { 0 } else { 1 }];
-            }
+    pub fn end_emissive_pass(&self) {
+        unsafe {
+            gl::Disable(gl::BLEND);
+            gl::DepthMask(gl::TRUE);
+            gl::DepthFunc(gl::LESS);
+        }
+    }
 
-            unsafe {
-                gl::BindTexture(gl::TEXTURE_2D, read_tex);
-                self.render_quad();
-                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
-            }
+    /// Starts the outline pass in the HDR buffer. Outlined entities are first
+    /// drawn into the stencil buffer only, with `draw_outline_mask`.
+    pub fn begin_outline_pass(&self, view: &glam::Mat4, projection: &glam::Mat4) {
+        let shader = &self.outline_shader;
+        shader.use_program();
+        shader.set_mat4("view", view);
+        shader.set_mat4("projection", projection);
 
-            horizontal = !horizontal;
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.scene_fbo());
+            gl::Enable(gl::STENCIL_TEST);
+            gl::StencilMask(0xFF);
+            gl::ClearStencil(0);
+            gl::Clear(gl::STENCIL_BUFFER_BIT);
+            gl::StencilFunc(gl::ALWAYS, 1, 0xFF);
+            gl::StencilOp(gl::KEEP, gl::KEEP, gl::REPLACE);
+            gl::ColorMask(gl::FALSE, gl::FALSE, gl::FALSE, gl::FALSE);
+            gl::Enable(gl::DEPTH_TEST);
+            gl::DepthFunc(gl::LEQUAL);
+            gl::DepthMask(gl::FALSE);
         }
+    }
 
-        let final_idx = if horizontal { 0 } else { 1 };
-        self.bloom_output = self.pingpong_color[final_idx];
+    pub fn draw_outline_mask(
+        &self,
+        mesh: &mesh::MeshDrawInfo,
+        model: &glam::Mat4,
+        bones: Option<&[glam::Mat4]>,
+    ) {
+        let shader = &self.outline_shader;
+        shader.set_mat4("model", model);
+        animation::bind_bones(shader, bones);
+        mesh.draw();
     }
 
-    pub fn render_quad(&self) {
+    /// Switches from marking the stencil to drawing the enlarged shells,
+    /// which only color pixels outside every marked silhouette.
+    pub fn begin_outline_shells(&self) {
         unsafe {
-            gl::BindVertexArray(self.quad_vao);
-            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
-            gl::BindVertexArray(0);
+            gl::ColorMask(gl::TRUE, gl::TRUE, gl::TRUE, gl::TRUE);
+            gl::StencilFunc(gl::NOTEQUAL, 1, 0xFF);
+            gl::StencilMask(0x00);
         }
     }
 
-    pub fn render_sphere_at(
+    pub fn draw_outline_shell(
         &self,
+        mesh: &mesh::MeshDrawInfo,
         model: &glam::Mat4,
-        view: &glam::Mat4,
-        projection: &glam::Mat4,
+        bones: Option<&[glam::Mat4]>,
         color: &glam::Vec3,
     ) {
-        self.light_sphere_shader.use_program();
-        self.light_sphere_shader.set_mat4("model", model);
-        self.light_sphere_shader.set_mat4("view", view);
-        self.light_sphere_shader.set_mat4("projection", projection);
-        self.light_sphere_shader.set_vec3("color", color);
-        let (scale, _rot, _trans) = model.to_scale_rotation_translation();
-        let s = scale.x.max(scale.y).max(scale.z);
-        let intensity = s * 20.0;
-        self.light_sphere_shader.set_float("intensity", intensity);
+        let shader = &self.outline_shader;
+        shader.set_mat4("model", model);
+        shader.set_vec3("color", color);
+        animation::bind_bones(shader, bones);
+        mesh.draw();
+    }
 
+    pub fn end_outline_pass(&self) {
         unsafe {
-            gl::Enable(gl::DEPTH_TEST);
+            gl::StencilMask(0xFF);
+            gl::Disable(gl::STENCIL_TEST);
             gl::DepthMask(gl::TRUE);
+            gl::DepthFunc(gl::LESS);
         }
+    }
 
-        self.light_sphere.draw();
+    pub fn end_transparent_pass(&self) {
+        unsafe {
+            gl::Disable(gl::BLEND);
+            gl::DepthMask(gl::TRUE);
+        }
     }
 
     pub fn blit_depth_from_gbuffer_to_hdr(&self) {
@@ -407,10 +1643,22 @@ impl Renderer {
         }
     }
 
-    pub fn begin_directional_shadow_pass(&self) {
+    pub fn begin_directional_shadow_pass(&self, cascade: usize) {
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, self.directional_shadow_fbo);
-            gl::Viewport(0, 0, 2048, 2048);
+            gl::FramebufferTextureLayer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                self.directional_shadow_map,
+                0,
+                cascade as i32,
+            );
+            gl::Viewport(
+                0,
+                0,
+                self.directional_shadow_size as i32,
+                self.directional_shadow_size as i32,
+            );
             gl::Clear(gl::DEPTH_BUFFER_BIT);
             gl::CullFace(gl::FRONT);
         }
@@ -427,7 +1675,12 @@ impl Renderer {
     pub fn begin_point_shadow_pass(&self, light_index: usize, face: usize) {
         unsafe {
             gl::BindFramebuffer(gl::FRAMEBUFFER, self.point_shadow_fbos[light_index][face]);
-            gl::Viewport(0, 0, 1024, 1024);
+            gl::Viewport(
+                0,
+                0,
+                self.point_shadow_size as i32,
+                self.point_shadow_size as i32,
+            );
             gl::Clear(gl::DEPTH_BUFFER_BIT);
             gl::CullFace(gl::FRONT);
         }
@@ -442,7 +1695,61 @@ impl Renderer {
     }
 }
 
-unsafe fn create_directional_shadow_buffer() -> (u32, u32) {
+/// Fails with the status name if the bound framebuffer is incomplete.
+unsafe fn check_framebuffer(name: &str) -> Result<(), String> {
+    let status = unsafe { gl::CheckFramebufferStatus(gl::FRAMEBUFFER) };
+    let status_name = match status {
+        gl::FRAMEBUFFER_COMPLETE => return Ok(()),
+        gl::FRAMEBUFFER_UNDEFINED => "GL_FRAMEBUFFER_UNDEFINED",
+        gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "GL_FRAMEBUFFER_INCOMPLETE_ATTACHMENT",
+        gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => {
+            "GL_FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT"
+        }
+        gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => "GL_FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER",
+        gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => "GL_FRAMEBUFFER_INCOMPLETE_READ_BUFFER",
+        gl::FRAMEBUFFER_UNSUPPORTED => "GL_FRAMEBUFFER_UNSUPPORTED",
+        gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => "GL_FRAMEBUFFER_INCOMPLETE_MULTISAMPLE",
+        gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => "GL_FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS",
+        _ => "unknown status",
+    };
+    Err(format!(
+        "{} framebuffer is incomplete: {} (0x{:X})",
+        name, status_name, status
+    ))
+}
+
+/// Drains the GL error queue, failing with the first error found.
+fn check_gl_error(context: &str) -> Result<(), String> {
+    let mut first = None;
+    // Bounded in case a lost context keeps reporting errors.
+    for _ in 0..32 {
+        let error = unsafe { gl::GetError() };
+        if error == gl::NO_ERROR {
+            break;
+        }
+        first.get_or_insert(error);
+    }
+    let Some(error) = first else {
+        return Ok(());
+    };
+    let error_name = match error {
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        _ => "unknown error",
+    };
+    Err(format!(
+        "GL error while {}: {} (0x{:X})",
+        context, error_name, error
+    ))
+}
+
+unsafe fn create_directional_shadow_buffer(
+    size: u32,
+    cascades: usize,
+) -> Result<(u32, u32), String> {
     let mut fbo = 0;
     let mut shadow_map = 0;
     unsafe {
@@ -450,57 +1757,70 @@ unsafe fn create_directional_shadow_buffer() -> (u32, u32) {
         gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
 
         gl::GenTextures(1, &mut shadow_map);
-        gl::BindTexture(gl::TEXTURE_2D, shadow_map);
-        gl::TexImage2D(
-            gl::TEXTURE_2D,
+        gl::BindTexture(gl::TEXTURE_2D_ARRAY, shadow_map);
+        gl::TexImage3D(
+            gl::TEXTURE_2D_ARRAY,
             0,
             gl::DEPTH_COMPONENT as i32,
-            2048,
-            2048,
+            size as i32,
+            size as i32,
+            cascades as i32,
             0,
             gl::DEPTH_COMPONENT,
             gl::FLOAT,
             ptr::null(),
         );
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+        // Hardware depth comparison with linear filtering blends the four
+        // nearest comparisons, which smooths each PCF tap.
         gl::TexParameteri(
-            gl::TEXTURE_2D,
+            gl::TEXTURE_2D_ARRAY,
+            gl::TEXTURE_MIN_FILTER,
+            gl::LINEAR as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_2D_ARRAY,
+            gl::TEXTURE_MAG_FILTER,
+            gl::LINEAR as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_2D_ARRAY,
+            gl::TEXTURE_COMPARE_MODE,
+            gl::COMPARE_REF_TO_TEXTURE as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_2D_ARRAY,
+            gl::TEXTURE_COMPARE_FUNC,
+            gl::LEQUAL as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_2D_ARRAY,
             gl::TEXTURE_WRAP_S,
             gl::CLAMP_TO_BORDER as i32,
         );
         gl::TexParameteri(
-            gl::TEXTURE_2D,
+            gl::TEXTURE_2D_ARRAY,
             gl::TEXTURE_WRAP_T,
             gl::CLAMP_TO_BORDER as i32,
         );
         let border_color = [1.0f32, 1.0f32, 1.0f32, 1.0f32];
         gl::TexParameterfv(
-            gl::TEXTURE_2D,
+            gl::TEXTURE_2D_ARRAY,
             gl::TEXTURE_BORDER_COLOR,
             border_color.as_ptr(),
         );
 
-        gl::FramebufferTexture2D(
-            gl::FRAMEBUFFER,
-            gl::DEPTH_ATTACHMENT,
-            gl::TEXTURE_2D,
-            shadow_map,
-            0,
-        );
+        gl::FramebufferTextureLayer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, shadow_map, 0, 0);
         gl::DrawBuffer(gl::NONE);
         gl::ReadBuffer(gl::NONE);
 
-        if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
-            panic!("Directional shadow framebuffer not complete");
-        }
+        check_framebuffer("Directional shadow")?;
 
         gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
     }
-    (fbo, shadow_map)
+    Ok((fbo, shadow_map))
 }
 
-unsafe fn create_point_shadow_buffer() -> ([u32; 6], u32) {
+unsafe fn create_point_shadow_buffer(size: u32) -> Result<([u32; 6], u32), String> {
     let mut fbos = [0u32; 6];
     let mut shadow_map = 0;
     unsafe {
@@ -512,8 +1832,8 @@ unsafe fn create_point_shadow_buffer() -> ([u32; 6], u32) {
                 gl::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
                 0,
                 gl::DEPTH_COMPONENT as i32,
-                1024,
-                1024,
+                size as i32,
+                size as i32,
                 0,
                 gl::DEPTH_COMPONENT,
                 gl::FLOAT,
@@ -560,17 +1880,18 @@ unsafe fn create_point_shadow_buffer() -> ([u32; 6], u32) {
             gl::DrawBuffer(gl::NONE);
             gl::ReadBuffer(gl::NONE);
 
-            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
-                panic!("Point shadow framebuffer {} not complete", i);
-            }
+            check_framebuffer(&format!("Point shadow face {}", i))?;
         }
 
         gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
     }
-    (fbos, shadow_map)
+    Ok((fbos, shadow_map))
 }
 
-unsafe fn create_g_buffer(width: u32, height: u32) -> (u32, u32, u32, u32, u32) {
+/// G-buffer names: (fbo, position, normal, albedo, velocity, specular, depth).
+type GBufferTargets = (u32, u32, u32, u32, u32, u32, u32);
+
+unsafe fn create_g_buffer(width: u32, height: u32) -> Result<GBufferTargets, String> {
     let mut g_buffer = 0;
     unsafe {
         gl::GenFramebuffers(1, &mut g_buffer);
@@ -648,38 +1969,96 @@ unsafe fn create_g_buffer(width: u32, height: u32) -> (u32, u32, u32, u32, u32)
             0,
         );
 
+        let mut g_velocity = 0;
+        gl::GenTextures(1, &mut g_velocity);
+        gl::BindTexture(gl::TEXTURE_2D, g_velocity);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::RG16F as i32,
+            width as i32,
+            height as i32,
+            0,
+            gl::RG,
+            gl::FLOAT,
+            ptr::null(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+        gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT3,
+            gl::TEXTURE_2D,
+            g_velocity,
+            0,
+        );
+
+        let mut g_specular = 0;
+        gl::GenTextures(1, &mut g_specular);
+        gl::BindTexture(gl::TEXTURE_2D, g_specular);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::RGBA as i32,
+            width as i32,
+            height as i32,
+            0,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            ptr::null(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+        gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT4,
+            gl::TEXTURE_2D,
+            g_specular,
+            0,
+        );
+
         let attachments = [
             gl::COLOR_ATTACHMENT0,
             gl::COLOR_ATTACHMENT1,
             gl::COLOR_ATTACHMENT2,
+            gl::COLOR_ATTACHMENT3,
+            gl::COLOR_ATTACHMENT4,
         ];
-        gl::DrawBuffers(3, attachments.as_ptr());
+        gl::DrawBuffers(5, attachments.as_ptr());
 
         let mut rbo_depth = 0;
         gl::GenRenderbuffers(1, &mut rbo_depth);
         gl::BindRenderbuffer(gl::RENDERBUFFER, rbo_depth);
         gl::RenderbufferStorage(
             gl::RENDERBUFFER,
-            gl::DEPTH_COMPONENT,
+            gl::DEPTH24_STENCIL8,
             width as i32,
             height as i32,
         );
         gl::FramebufferRenderbuffer(
             gl::FRAMEBUFFER,
-            gl::DEPTH_ATTACHMENT,
+            gl::DEPTH_STENCIL_ATTACHMENT,
             gl::RENDERBUFFER,
             rbo_depth,
         );
 
-        if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {}
+        check_framebuffer("G-buffer")?;
 
         gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
 
-        (g_buffer, g_position, g_normal, g_albedo_spec, rbo_depth)
+        Ok((
+            g_buffer,
+            g_position,
+            g_normal,
+            g_albedo_spec,
+            g_velocity,
+            g_specular,
+            rbo_depth,
+        ))
     }
 }
 
-unsafe fn create_hdr_buffer(width: u32, height: u32) -> (u32, u32) {
+unsafe fn create_hdr_buffer(width: u32, height: u32) -> Result<(u32, u32), String> {
     let mut fbo = 0;
     let mut color = 0;
     let mut rbo = 0;
@@ -715,82 +2094,110 @@ unsafe fn create_hdr_buffer(width: u32, height: u32) -> (u32, u32) {
 
         gl::GenRenderbuffers(1, &mut rbo);
         gl::BindRenderbuffer(gl::RENDERBUFFER, rbo);
+        // Same format as the G-buffer's so depth can be blitted across, with
+        // stencil bits for the outline pass.
         gl::RenderbufferStorage(
             gl::RENDERBUFFER,
-            gl::DEPTH_COMPONENT,
+            gl::DEPTH24_STENCIL8,
             width as i32,
             height as i32,
         );
-        gl::FramebufferRenderbuffer(gl::FRAMEBUFFER, gl::DEPTH_ATTACHMENT, gl::RENDERBUFFER, rbo);
+        gl::FramebufferRenderbuffer(
+            gl::FRAMEBUFFER,
+            gl::DEPTH_STENCIL_ATTACHMENT,
+            gl::RENDERBUFFER,
+            rbo,
+        );
 
-        if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {}
+        check_framebuffer("HDR")?;
 
         gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
     }
-    (fbo, color)
+    Ok((fbo, color))
 }
 
-unsafe fn create_bloom_buffer(width: u32, height: u32) -> (u32, u32) {
+/// Creates the multisampled HDR color and depth-stencil target the forward
+/// path renders into, returning (fbo, color, depth) renderbuffers.
+unsafe fn create_msaa_buffer(
+    width: u32,
+    height: u32,
+    samples: u32,
+) -> Result<(u32, u32, u32), String> {
     let mut fbo = 0;
     let mut color = 0;
+    let mut depth = 0;
     unsafe {
         gl::GenFramebuffers(1, &mut fbo);
         gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
 
-        gl::GenTextures(1, &mut color);
-        gl::BindTexture(gl::TEXTURE_2D, color);
-        gl::TexImage2D(
-            gl::TEXTURE_2D,
-            0,
-            gl::RGBA16F as i32,
+        gl::GenRenderbuffers(1, &mut color);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, color);
+        gl::RenderbufferStorageMultisample(
+            gl::RENDERBUFFER,
+            samples as i32,
+            gl::RGBA16F,
             width as i32,
             height as i32,
-            0,
-            gl::RGBA,
-            gl::FLOAT,
-            ptr::null(),
         );
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
-
-        gl::FramebufferTexture2D(
+        gl::FramebufferRenderbuffer(
             gl::FRAMEBUFFER,
             gl::COLOR_ATTACHMENT0,
-            gl::TEXTURE_2D,
+            gl::RENDERBUFFER,
             color,
-            0,
         );
 
-        if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
-            panic!("Bloom framebuffer not complete");
-        }
+        gl::GenRenderbuffers(1, &mut depth);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, depth);
+        gl::RenderbufferStorageMultisample(
+            gl::RENDERBUFFER,
+            samples as i32,
+            gl::DEPTH24_STENCIL8,
+            width as i32,
+            height as i32,
+        );
+        gl::FramebufferRenderbuffer(
+            gl::FRAMEBUFFER,
+            gl::DEPTH_STENCIL_ATTACHMENT,
+            gl::RENDERBUFFER,
+            depth,
+        );
+
+        check_framebuffer("MSAA")?;
 
         gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
     }
-    (fbo, color)
+    Ok((fbo, color, depth))
 }
 
-unsafe fn create_pingpong_buffers(width: u32, height: u32) -> ([u32; 2], [u32; 2]) {
-    let mut fbos = [0u32; 2];
-    let mut colors = [0u32; 2];
+/// Allocates `count` successively halved RGBA16F targets, starting at half
+/// of `width`x`height`. Levels never shrink below one pixel.
+unsafe fn create_bloom_mips(
+    width: u32,
+    height: u32,
+    count: usize,
+) -> Result<Vec<BloomMip>, String> {
+    let mut mips = Vec::with_capacity(count);
+    let (mut mip_width, mut mip_height) = (width, height);
     unsafe {
-        gl::GenFramebuffers(2, fbos.as_mut_ptr());
-        gl::GenTextures(2, colors.as_mut_ptr());
+        for i in 0..count {
+            mip_width = (mip_width / 2).max(1);
+            mip_height = (mip_height / 2).max(1);
 
-        for (i, fbo) in fbos.iter().enumerate() {
-            let color = colors[i];
-            gl::BindFramebuffer(gl::FRAMEBUFFER, *fbo);
-            gl::BindTexture(gl::TEXTURE_2D, color);
+            let mut fbo = 0;
+            let mut texture = 0;
+            gl::GenFramebuffers(1, &mut fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
             gl::TexImage2D(
                 gl::TEXTURE_2D,
                 0,
-                gl::RGBA16F as i32,
-                width as i32,
-                height as i32,
+                gl::R11F_G11F_B10F as i32,
+                mip_width as i32,
+                mip_height as i32,
                 0,
-                gl::RGBA,
+                gl::RGB,
                 gl::FLOAT,
                 ptr::null(),
             );
@@ -803,18 +2210,144 @@ unsafe fn create_pingpong_buffers(width: u32, height: u32) -> ([u32; 2], [u32; 2
                 gl::FRAMEBUFFER,
                 gl::COLOR_ATTACHMENT0,
                 gl::TEXTURE_2D,
-                color,
+                texture,
                 0,
             );
 
-            if gl::CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
-                panic!("Pingpong framebuffer {} not complete", i);
+            mips.push(BloomMip {
+                fbo,
+                texture,
+                width: mip_width,
+                height: mip_height,
+            });
+            if let Err(err) = check_framebuffer(&format!("Bloom mip {}", i)) {
+                delete_bloom_mips(&mips);
+                return Err(err);
             }
         }
 
         gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
     }
-    (fbos, colors)
+    Ok(mips)
+}
+
+unsafe fn delete_bloom_mips(mips: &[BloomMip]) {
+    for mip in mips {
+        unsafe {
+            gl::DeleteFramebuffers(1, &mip.fbo);
+            gl::DeleteTextures(1, &mip.texture);
+        }
+    }
+}
+
+/// Allocates a linearly filtered, edge-clamped color target without depth.
+unsafe fn create_color_buffer(
+    width: u32,
+    height: u32,
+    internal_format: u32,
+    label: &str,
+) -> Result<(u32, u32), String> {
+    let mut fbo = 0;
+    let mut color = 0;
+    unsafe {
+        gl::GenFramebuffers(1, &mut fbo);
+        gl::BindFramebuffer(gl::FRAMEBUFFER, fbo);
+
+        gl::GenTextures(1, &mut color);
+        gl::BindTexture(gl::TEXTURE_2D, color);
+        gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            internal_format as i32,
+            width as i32,
+            height as i32,
+            0,
+            gl::RGBA,
+            gl::FLOAT,
+            ptr::null(),
+        );
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+
+        gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_2D,
+            color,
+            0,
+        );
+
+        check_framebuffer(label)?;
+
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+    }
+    Ok((fbo, color))
+}
+
+/// Reads a LUT strip back from `texture` and re-uploads it as a 3D texture.
+unsafe fn create_color_lut(texture: u32) -> Result<u32, String> {
+    let (mut width, mut height) = (0, 0);
+    let mut lut = 0;
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, texture);
+        gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_WIDTH, &mut width);
+        gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_HEIGHT, &mut height);
+        let size = height.max(0) as usize;
+        if size < 2 || width.max(0) as usize != size * size {
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            return Err(format!(
+                "Color LUT must be an n*n by n strip with n >= 2, got {}x{}",
+                width, height
+            ));
+        }
+        let mut strip = vec![0u8; size * size * size * 4];
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::GetTexImage(
+            gl::TEXTURE_2D,
+            0,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            strip.as_mut_ptr() as *mut _,
+        );
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+
+        // Strip rows run across every slice; the 3D texture wants each
+        // slice stored whole.
+        let row = size * 4;
+        let mut volume = Vec::with_capacity(strip.len());
+        for slice in 0..size {
+            for y in 0..size {
+                let start = y * row * size + slice * row;
+                volume.extend_from_slice(&strip[start..start + row]);
+            }
+        }
+
+        gl::GenTextures(1, &mut lut);
+        gl::BindTexture(gl::TEXTURE_3D, lut);
+        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+        gl::TexImage3D(
+            gl::TEXTURE_3D,
+            0,
+            gl::RGBA8 as i32,
+            size as i32,
+            size as i32,
+            size as i32,
+            0,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            volume.as_ptr() as *const _,
+        );
+        gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+        gl::TexParameteri(gl::TEXTURE_3D, gl::TEXTURE_WRAP_R, gl::CLAMP_TO_EDGE as i32);
+        gl::BindTexture(gl::TEXTURE_3D, 0);
+    }
+    check_gl_error("creating the color LUT")?;
+    Ok(lut)
 }
 
 unsafe fn create_quad() -> (u32, u32) {
@@ -861,3 +2394,54 @@ unsafe fn create_quad() -> (u32, u32) {
     }
     (quad_vao, quad_vbo)
 }
+
+unsafe fn create_skybox_cube() -> (u32, u32) {
+    #[rustfmt::skip]
+    let cube_vertices: [f32; 108] = [
+        -1.0,  1.0, -1.0,  -1.0, -1.0, -1.0,   1.0, -1.0, -1.0,
+         1.0, -1.0, -1.0,   1.0,  1.0, -1.0,  -1.0,  1.0, -1.0,
+
+        -1.0, -1.0,  1.0,  -1.0, -1.0, -1.0,  -1.0,  1.0, -1.0,
+        -1.0,  1.0, -1.0,  -1.0,  1.0,  1.0,  -1.0, -1.0,  1.0,
+
+         1.0, -1.0, -1.0,   1.0, -1.0,  1.0,   1.0,  1.0,  1.0,
+         1.0,  1.0,  1.0,   1.0,  1.0, -1.0,   1.0, -1.0, -1.0,
+
+        -1.0, -1.0,  1.0,  -1.0,  1.0,  1.0,   1.0,  1.0,  1.0,
+         1.0,  1.0,  1.0,   1.0, -1.0,  1.0,  -1.0, -1.0,  1.0,
+
+        -1.0,  1.0, -1.0,   1.0,  1.0, -1.0,   1.0,  1.0,  1.0,
+         1.0,  1.0,  1.0,  -1.0,  1.0,  1.0,  -1.0,  1.0, -1.0,
+
+        -1.0, -1.0, -1.0,  -1.0, -1.0,  1.0,   1.0, -1.0, -1.0,
+         1.0, -1.0, -1.0,  -1.0, -1.0,  1.0,   1.0, -1.0,  1.0,
+    ];
+
+    let mut vao = 0;
+    let mut vbo = 0;
+    unsafe {
+        gl::GenVertexArrays(1, &mut vao);
+        gl::GenBuffers(1, &mut vbo);
+        gl::BindVertexArray(vao);
+        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+        gl::BufferData(
+            gl::ARRAY_BUFFER,
+            (cube_vertices.len() * mem::size_of::<f32>()) as isize,
+            &cube_vertices[0] as *const f32 as *const _,
+            gl::STATIC_DRAW,
+        );
+
+        gl::EnableVertexAttribArray(0);
+        gl::VertexAttribPointer(
+            0,
+            3,
+            gl::FLOAT,
+            gl::FALSE,
+            3 * mem::size_of::<f32>() as i32,
+            ptr::null(),
+        );
+
+        gl::BindVertexArray(0);
+    }
+    (vao, vbo)
+}
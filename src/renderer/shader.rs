@@ -1,11 +1,83 @@
-use glam::{Mat4, Vec3};
-use std::ffi::CString;
+use glam::{Mat4, Vec2, Vec3, Vec4};
+use pyo3::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CString, NulError};
 use std::fs;
 use std::ptr;
 use std::str;
 
+#[pyclass(unsendable)]
 pub struct Shader {
+    #[pyo3(get)]
     pub id: u32,
+    locations: RefCell<HashMap<String, i32>>,
+}
+
+/// Python-facing constructors and setters. Custom shaders replace the built-in
+/// geometry shader, so they must write the same G-buffer outputs: locations
+/// 0-2 are required, an unwritten `gVelocity` (location 3) clears to zero
+/// motion, and an unwritten `gSpecular` (location 4) is untinted.
+#[pymethods]
+impl Shader {
+    #[staticmethod]
+    pub fn from_files(vsh: &str, fsh: &str) -> PyResult<Self> {
+        Shader::from_glsl(vsh, fsh).map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    #[staticmethod]
+    pub fn from_source(vsh: &str, fsh: &str) -> PyResult<Self> {
+        Shader::new(vsh, fsh).map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)
+    }
+
+    #[pyo3(name = "set_int")]
+    pub fn set_int_py(&self, name: &str, value: i32) -> PyResult<()> {
+        self.check_uniform_name(name)?;
+        self.use_program();
+        self.set_int(name, value);
+        Ok(())
+    }
+
+    #[pyo3(name = "set_float")]
+    pub fn set_float_py(&self, name: &str, value: f32) -> PyResult<()> {
+        self.check_uniform_name(name)?;
+        self.use_program();
+        self.set_float(name, value);
+        Ok(())
+    }
+
+    #[pyo3(name = "set_vec2")]
+    pub fn set_vec2_py(&self, name: &str, value: (f32, f32)) -> PyResult<()> {
+        self.check_uniform_name(name)?;
+        self.use_program();
+        self.set_vec2(name, &Vec2::new(value.0, value.1));
+        Ok(())
+    }
+
+    #[pyo3(name = "set_vec3")]
+    pub fn set_vec3_py(&self, name: &str, value: (f32, f32, f32)) -> PyResult<()> {
+        self.check_uniform_name(name)?;
+        self.use_program();
+        self.set_vec3(name, &Vec3::new(value.0, value.1, value.2));
+        Ok(())
+    }
+
+    #[pyo3(name = "set_vec4")]
+    pub fn set_vec4_py(&self, name: &str, value: (f32, f32, f32, f32)) -> PyResult<()> {
+        self.check_uniform_name(name)?;
+        self.use_program();
+        self.set_vec4(name, &Vec4::new(value.0, value.1, value.2, value.3));
+        Ok(())
+    }
+
+    /// Takes a column-major 4x4 matrix.
+    #[pyo3(name = "set_mat4")]
+    pub fn set_mat4_py(&self, name: &str, value: [[f32; 4]; 4]) -> PyResult<()> {
+        self.check_uniform_name(name)?;
+        self.use_program();
+        self.set_mat4(name, &Mat4::from_cols_array_2d(&value));
+        Ok(())
+    }
 }
 
 impl Shader {
@@ -13,7 +85,10 @@ impl Shader {
         let vertex = compile_shader(vertex_code, gl::VERTEX_SHADER)?;
         let fragment = compile_shader(fragment_code, gl::FRAGMENT_SHADER)?;
         let id = link_program(vertex, fragment)?;
-        Ok(Shader { id })
+        Ok(Shader {
+            id,
+            locations: RefCell::new(HashMap::new()),
+        })
     }
 
     pub fn from_glsl(vertex_path: &str, fragment_path: &str) -> Result<Shader, String> {
@@ -28,53 +103,112 @@ impl Shader {
         Shader::new(&vertex_code, &fragment_code)
     }
 
+    /// Rejects uniform names that can't be passed to GL, so a bad name from
+    /// Python raises instead of being silently ignored.
+    fn check_uniform_name(&self, name: &str) -> PyResult<()> {
+        self.uniform_location(name).map(|_| ()).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!(
+                "Invalid uniform name {:?}: {}",
+                name, e
+            ))
+        })
+    }
+
     pub fn use_program(&self) {
         unsafe {
             gl::UseProgram(self.id);
         }
     }
 
-    fn uniform_location(&self, name: &str) -> i32 {
-        let c = CString::new(name).unwrap();
-        unsafe { gl::GetUniformLocation(self.id, c.as_ptr()) }
+    /// Looks up a uniform, caching the result so the draw loop doesn't
+    /// allocate a `CString` and query GL for every call.
+    fn uniform_location(&self, name: &str) -> Result<i32, NulError> {
+        if let Some(&location) = self.locations.borrow().get(name) {
+            return Ok(location);
+        }
+        let c = CString::new(name)?;
+        let location = unsafe { gl::GetUniformLocation(self.id, c.as_ptr()) };
+        self.locations
+            .borrow_mut()
+            .insert(name.to_owned(), location);
+        Ok(location)
+    }
+
+    /// Location for the setters below. A name GL can't accept is treated
+    /// like a missing uniform (-1), which GL ignores.
+    fn location(&self, name: &str) -> i32 {
+        self.uniform_location(name).unwrap_or(-1)
     }
 
     pub fn set_bool(&self, name: &str, value: bool) {
         unsafe {
-            gl::Uniform1i(self.uniform_location(name), value as i32);
+            gl::Uniform1i(self.location(name), value as i32);
         }
     }
 
     pub fn set_int(&self, name: &str, value: i32) {
         unsafe {
-            gl::Uniform1i(self.uniform_location(name), value);
+            gl::Uniform1i(self.location(name), value);
         }
     }
 
     pub fn set_float(&self, name: &str, value: f32) {
         unsafe {
-            gl::Uniform1f(self.uniform_location(name), value);
+            gl::Uniform1f(self.location(name), value);
+        }
+    }
+
+    pub fn set_vec2(&self, name: &str, value: &Vec2) {
+        unsafe {
+            gl::Uniform2fv(self.location(name), 1, value.as_ref().as_ptr());
         }
     }
 
     pub fn set_vec3(&self, name: &str, value: &Vec3) {
         unsafe {
-            gl::Uniform3fv(self.uniform_location(name), 1, value.as_ref().as_ptr());
+            gl::Uniform3fv(self.location(name), 1, value.as_ref().as_ptr());
+        }
+    }
+
+    pub fn set_vec4(&self, name: &str, value: &Vec4) {
+        unsafe {
+            gl::Uniform4fv(self.location(name), 1, value.as_ref().as_ptr());
         }
     }
 
     pub fn set_mat4(&self, name: &str, value: &Mat4) {
+        unsafe {
+            gl::UniformMatrix4fv(self.location(name), 1, gl::FALSE, value.as_ref().as_ptr());
+        }
+    }
+
+    /// Uploads consecutive elements of a `mat4` array uniform, starting at
+    /// index 0.
+    pub fn set_mat4_array(&self, name: &str, values: &[Mat4]) {
+        if values.is_empty() {
+            return;
+        }
         unsafe {
             gl::UniformMatrix4fv(
-                self.uniform_location(name),
-                1,
+                self.location(name),
+                values.len() as i32,
                 gl::FALSE,
-                value.as_ref().as_ptr(),
+                values.as_ptr() as *const f32,
             );
         }
     }
 }
 
+impl Drop for Shader {
+    fn drop(&mut self) {
+        if self.id != 0 {
+            unsafe {
+                gl::DeleteProgram(self.id);
+            }
+        }
+    }
+}
+
 fn compile_shader(code: &str, kind: u32) -> Result<u32, String> {
     let code_c = CString::new(code).map_err(|e| e.to_string())?;
     unsafe {
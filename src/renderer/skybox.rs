@@ -0,0 +1,97 @@
+use pyo3::prelude::*;
+use std::path::Path;
+
+#[pyclass(unsendable)]
+pub struct Skybox {
+    #[pyo3(get)]
+    pub id: u32,
+}
+
+#[pymethods]
+impl Skybox {
+    /// Faces are given in cubemap order: +X, -X, +Y, -Y, +Z, -Z.
+    #[staticmethod]
+    pub fn from_faces(
+        right: &str,
+        left: &str,
+        top: &str,
+        bottom: &str,
+        front: &str,
+        back: &str,
+    ) -> PyResult<Self> {
+        let id = load_cubemap_from_files(&[right, left, top, bottom, front, back])
+            .map_err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>)?;
+        Ok(Skybox { id })
+    }
+}
+
+impl Drop for Skybox {
+    fn drop(&mut self) {
+        if self.id != 0 {
+            unsafe {
+                gl::DeleteTextures(1, &self.id);
+            }
+        }
+    }
+}
+
+fn load_cubemap_from_files(paths: &[&str; 6]) -> Result<u32, String> {
+    let mut faces = Vec::with_capacity(6);
+    for path in paths {
+        let img = image::open(Path::new(path))
+            .map_err(|e| format!("Failed to open skybox face '{}': {}", path, e))?
+            .to_rgba8();
+        faces.push(img);
+    }
+
+    let mut texture_id = 0;
+    unsafe {
+        gl::GenTextures(1, &mut texture_id);
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, texture_id);
+
+        for (i, img) in faces.iter().enumerate() {
+            let (width, height) = img.dimensions();
+            gl::TexImage2D(
+                gl::TEXTURE_CUBE_MAP_POSITIVE_X + i as u32,
+                0,
+                gl::SRGB8_ALPHA8 as i32,
+                width as i32,
+                height as i32,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                img.as_raw().as_ptr() as *const _,
+            );
+        }
+
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_MIN_FILTER,
+            gl::LINEAR as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_MAG_FILTER,
+            gl::LINEAR as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_WRAP_S,
+            gl::CLAMP_TO_EDGE as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_WRAP_T,
+            gl::CLAMP_TO_EDGE as i32,
+        );
+        gl::TexParameteri(
+            gl::TEXTURE_CUBE_MAP,
+            gl::TEXTURE_WRAP_R,
+            gl::CLAMP_TO_EDGE as i32,
+        );
+
+        gl::BindTexture(gl::TEXTURE_CUBE_MAP, 0);
+    }
+
+    Ok(texture_id)
+}
@@ -0,0 +1,86 @@
+use crate::renderer::texture::Texture;
+use glam::{Vec2, Vec4};
+use pyo3::prelude::*;
+
+/// A textured rectangle drawn over the finished frame, positioned in window
+/// pixels from the top-left corner.
+#[pyclass(unsendable)]
+pub struct Sprite {
+    #[pyo3(get)]
+    pub texture: Option<Py<Texture>>,
+    pub position: Vec2,
+    pub size: Vec2,
+    /// Multiplies the texture color; an untextured sprite is a solid `tint`.
+    pub tint: Vec4,
+    #[pyo3(get, set)]
+    pub visible: bool,
+}
+
+#[pymethods]
+impl Sprite {
+    #[new]
+    #[pyo3(signature = (texture, x, y, width, height))]
+    pub fn new(texture: Option<Py<Texture>>, x: f32, y: f32, width: f32, height: f32) -> Self {
+        Sprite {
+            texture,
+            position: Vec2::new(x, y),
+            size: Vec2::new(width, height),
+            tint: Vec4::ONE,
+            visible: true,
+        }
+    }
+
+    pub fn set_texture(&mut self, texture: Option<Py<Texture>>) {
+        self.texture = texture;
+    }
+
+    #[getter]
+    pub fn get_position(&self) -> (f32, f32) {
+        (self.position.x, self.position.y)
+    }
+
+    #[setter]
+    pub fn set_position(&mut self, position: (f32, f32)) {
+        self.position = Vec2::new(position.0, position.1);
+    }
+
+    #[getter]
+    pub fn get_size(&self) -> (f32, f32) {
+        (self.size.x, self.size.y)
+    }
+
+    #[setter]
+    pub fn set_size(&mut self, size: (f32, f32)) {
+        self.size = Vec2::new(size.0, size.1);
+    }
+
+    #[getter]
+    pub fn get_tint(&self) -> (f32, f32, f32, f32) {
+        (self.tint.x, self.tint.y, self.tint.z, self.tint.w)
+    }
+
+    #[setter]
+    pub fn set_tint(&mut self, tint: (f32, f32, f32, f32)) {
+        self.tint = Vec4::new(tint.0, tint.1, tint.2, tint.3);
+    }
+}
+
+impl Sprite {
+    /// Copies out what the overlay pass needs, with the texture resolved to
+    /// its GL id (0 when untextured).
+    pub fn draw_info(&self, py: Python) -> SpriteDrawInfo {
+        SpriteDrawInfo {
+            texture_id: self.texture.as_ref().map(|t| t.borrow(py).id).unwrap_or(0),
+            position: self.position,
+            size: self.size,
+            tint: self.tint,
+        }
+    }
+}
+
+pub struct SpriteDrawInfo {
+    pub texture_id: u32,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub tint: Vec4,
+}
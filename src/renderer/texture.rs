@@ -1,6 +1,57 @@
 use pyo3::prelude::*;
+use std::cell::Cell;
 use std::path::Path;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextureFilter {
+    /// Crisp texels with no mipmaps, for pixel art.
+    Nearest,
+    /// Trilinear filtering with mipmaps.
+    Linear,
+}
+
+impl TextureFilter {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "nearest" => Ok(TextureFilter::Nearest),
+            "linear" => Ok(TextureFilter::Linear),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown texture filter: {}",
+                name
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TextureWrap {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+impl TextureWrap {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name {
+            "repeat" => Ok(TextureWrap::Repeat),
+            "clamp" => Ok(TextureWrap::Clamp),
+            "mirror" => Ok(TextureWrap::Mirror),
+            _ => Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Unknown texture wrap mode: {}",
+                name
+            ))),
+        }
+    }
+
+    fn gl_enum(self) -> u32 {
+        match self {
+            TextureWrap::Repeat => gl::REPEAT,
+            TextureWrap::Clamp => gl::CLAMP_TO_EDGE,
+            TextureWrap::Mirror => gl::MIRRORED_REPEAT,
+        }
+    }
+}
+
 #[pyclass(unsendable)]
 pub struct Texture {
     #[pyo3(get)]
@@ -23,9 +74,14 @@ impl Texture {
         Texture { id: 0, r, g, b, a }
     }
 
+    /// Pass `srgb=True` for color textures so they are linearized on sample;
+    /// leave it off for data such as normal or metallic/roughness maps.
     #[staticmethod]
-    pub fn from_image(path: &str) -> Self {
-        match load_texture_from_file(path) {
+    #[pyo3(signature = (path, filter = "linear", wrap = "repeat", srgb = false))]
+    pub fn from_image(path: &str, filter: &str, wrap: &str, srgb: bool) -> PyResult<Self> {
+        let filter = TextureFilter::from_name(filter)?;
+        let wrap = TextureWrap::from_name(wrap)?;
+        Ok(match load_texture_from_file(path, filter, wrap, srgb) {
             Ok(id) => Texture {
                 id,
                 r: 255,
@@ -43,6 +99,51 @@ impl Texture {
                     a: 255,
                 }
             }
+        })
+    }
+
+    /// Uploads a tightly packed RGBA8 buffer, row by row from the first row.
+    #[staticmethod]
+    #[pyo3(signature = (data, width, height, filter = "linear", wrap = "repeat", srgb = false))]
+    pub fn from_bytes(
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+        filter: &str,
+        wrap: &str,
+        srgb: bool,
+    ) -> PyResult<Self> {
+        let filter = TextureFilter::from_name(filter)?;
+        let wrap = TextureWrap::from_name(wrap)?;
+
+        let expected = width as usize * height as usize * 4;
+        if width == 0 || height == 0 || data.len() != expected {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "Expected {} bytes for a {}x{} RGBA texture, got {}",
+                expected,
+                width,
+                height,
+                data.len()
+            )));
+        }
+
+        Ok(Texture {
+            id: upload_rgba_texture(width, height, &data, filter, wrap, srgb),
+            r: 255,
+            g: 255,
+            b: 255,
+            a: 255,
+        })
+    }
+
+    /// Frees the GL texture now instead of when the wrapper is garbage
+    /// collected. Meshes and sprites still using it draw untextured.
+    pub fn delete(&mut self) {
+        if self.id != 0 {
+            unsafe {
+                gl::DeleteTextures(1, &self.id);
+            }
+            self.id = 0;
         }
     }
 
@@ -58,21 +159,39 @@ impl Texture {
 
 impl Drop for Texture {
     fn drop(&mut self) {
-        if self.id != 0 {
-            unsafe {
-                gl::DeleteTextures(1, &self.id);
-            }
-        }
+        self.delete();
     }
 }
 
-fn load_texture_from_file(path: &str) -> Result<u32, String> {
+fn load_texture_from_file(
+    path: &str,
+    filter: TextureFilter,
+    wrap: TextureWrap,
+    srgb: bool,
+) -> Result<u32, String> {
     let img = image::open(Path::new(path)).map_err(|e| format!("Failed to open image: {}", e))?;
 
     let img = img.to_rgba8();
     let (width, height) = img.dimensions();
     let data = img.into_raw();
 
+    Ok(upload_rgba_texture(
+        width, height, &data, filter, wrap, srgb,
+    ))
+}
+
+pub fn upload_rgba_texture(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    filter: TextureFilter,
+    wrap: TextureWrap,
+    srgb: bool,
+) -> u32 {
+    // sRGB storage makes the GPU linearize texels on sample, which the
+    // composite pass's gamma encode assumes for color data.
+    let internal_format = if srgb { gl::SRGB8_ALPHA8 } else { gl::RGBA8 };
+
     let mut texture_id = 0;
     unsafe {
         gl::GenTextures(1, &mut texture_id);
@@ -81,7 +200,7 @@ fn load_texture_from_file(path: &str) -> Result<u32, String> {
         gl::TexImage2D(
             gl::TEXTURE_2D,
             0,
-            gl::RGBA as i32,
+            internal_format as i32,
             width as i32,
             height as i32,
             0,
@@ -90,24 +209,48 @@ fn load_texture_from_file(path: &str) -> Result<u32, String> {
             data.as_ptr() as *const _,
         );
 
-        gl::GenerateMipmap(gl::TEXTURE_2D);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, wrap.gl_enum() as i32);
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, wrap.gl_enum() as i32);
 
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
-        gl::TexParameteri(
-            gl::TEXTURE_2D,
-            gl::TEXTURE_MIN_FILTER,
-            gl::LINEAR_MIPMAP_LINEAR as i32,
-        );
-        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        match filter {
+            TextureFilter::Nearest => {
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            }
+            TextureFilter::Linear => {
+                gl::GenerateMipmap(gl::TEXTURE_2D);
+                gl::TexParameteri(
+                    gl::TEXTURE_2D,
+                    gl::TEXTURE_MIN_FILTER,
+                    gl::LINEAR_MIPMAP_LINEAR as i32,
+                );
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            }
+        }
 
         gl::BindTexture(gl::TEXTURE_2D, 0);
     }
 
-    Ok(texture_id)
+    texture_id
+}
+
+thread_local! {
+    static WHITE_TEXTURE: Cell<u32> = const { Cell::new(0) };
+}
+
+/// A 1x1 white texture shared by everything that needs a texture but has
+/// none. It is created on first use and lives as long as the GL context, so
+/// it must never be passed to `glDeleteTextures`.
+pub fn white_texture() -> u32 {
+    WHITE_TEXTURE.with(|cell| {
+        if cell.get() == 0 {
+            cell.set(create_white_texture());
+        }
+        cell.get()
+    })
 }
 
-pub fn create_white_texture() -> u32 {
+fn create_white_texture() -> u32 {
     let white_pixel: [u8; 4] = [255, 255, 255, 255];
 
     let mut texture_id = 0;
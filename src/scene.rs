@@ -1,6 +1,11 @@
-use crate::renderer::mesh::Mesh;
-use glam::{EulerRot, Mat4, Quat, Vec3};
+use crate::renderer::animation::Animator;
+use crate::renderer::material::Material;
+use crate::renderer::mesh::{Mesh, MeshSource};
+use crate::renderer::shader::Shader;
+use glam::{EulerRot, Mat3, Mat4, Quat, Vec3};
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
 
 #[derive(Clone)]
 pub struct Transform {
@@ -38,24 +43,114 @@ impl Transform {
 #[pyclass]
 pub struct Entity {
     pub transform: Transform,
+    /// Optional unique-by-convention name, looked up with `find_by_name`.
+    #[pyo3(get, set)]
+    pub name: Option<String>,
+    /// Free-form labels such as "enemy", looked up with `find_by_tag`.
+    #[pyo3(get, set)]
+    pub tags: Vec<String>,
     #[pyo3(get)]
     pub mesh: Option<Py<Mesh>>,
+    #[pyo3(get)]
+    pub material: Option<Py<Material>>,
+    #[pyo3(get)]
+    pub parent: Option<Py<Entity>>,
+    #[pyo3(get)]
+    pub shader: Option<Py<Shader>>,
+    #[pyo3(get)]
+    pub animator: Option<Py<Animator>>,
+    /// Transparent entities skip the deferred path and are blended in a
+    /// forward pass after opaque geometry is lit.
+    #[pyo3(get, set)]
+    pub transparent: bool,
+    #[pyo3(get, set)]
+    pub opacity: f32,
+    /// Turning depth testing off draws the entity over everything drawn
+    /// before it in the geometry pass, for in-world gizmos and overlays.
+    #[pyo3(get, set)]
+    pub depth_test: bool,
+    /// Whether the entity occludes geometry drawn after it.
+    #[pyo3(get, set)]
+    pub depth_write: bool,
 }
 
 #[pymethods]
 impl Entity {
     #[new]
-    pub fn new() -> Self {
+    #[pyo3(signature = (name=None))]
+    pub fn new(name: Option<String>) -> Self {
         Entity {
             transform: Transform::new(),
+            name,
+            tags: Vec::new(),
             mesh: None,
+            material: None,
+            parent: None,
+            shader: None,
+            animator: None,
+            transparent: false,
+            opacity: 1.0,
+            depth_test: true,
+            depth_write: true,
+        }
+    }
+
+    /// Adds `tag` unless the entity already has it.
+    pub fn add_tag(&mut self, tag: String) {
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
         }
     }
 
+    /// Returns whether the tag was present.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let len = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() != len
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
     pub fn set_mesh(&mut self, mesh: Py<Mesh>) {
         self.mesh = Some(mesh);
     }
 
+    pub fn set_material(&mut self, material: Option<Py<Material>>) {
+        self.material = material;
+    }
+
+    /// Overrides the built-in geometry shader for this entity.
+    pub fn set_shader(&mut self, shader: Option<Py<Shader>>) {
+        self.shader = shader;
+    }
+
+    /// Poses this entity's skinned mesh with `animator`, which the engine
+    /// advances every frame.
+    pub fn set_animator(&mut self, animator: Option<Py<Animator>>) {
+        self.animator = animator;
+    }
+
+    /// Parents this entity so its transform becomes relative to `parent`.
+    /// Raises `ValueError` if that would create a cycle.
+    pub fn set_parent(slf: &Bound<'_, Self>, parent: Option<Bound<'_, Entity>>) -> PyResult<()> {
+        if Entity::would_cycle(slf, parent.as_ref()) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Entity parenting would create a cycle",
+            ));
+        }
+
+        slf.borrow_mut().parent = parent.map(Bound::unbind);
+        Ok(())
+    }
+
+    /// Column-major model matrix including all parent transforms.
+    #[pyo3(name = "get_world_matrix")]
+    pub fn get_world_matrix_py(&self, py: Python) -> [[f32; 4]; 4] {
+        self.get_world_matrix(py).to_cols_array_2d()
+    }
+
     #[getter]
     pub fn get_position(&self) -> (f32, f32, f32) {
         (
@@ -82,6 +177,40 @@ impl Entity {
             Quat::from_euler(EulerRot::XYZ, rotation.0, rotation.1, rotation.2);
     }
 
+    /// Rotation as an `(x, y, z, w)` quaternion, avoiding the Euler round-trip.
+    pub fn get_quaternion(&self) -> (f32, f32, f32, f32) {
+        self.transform.rotation.into()
+    }
+
+    /// Sets the rotation from an `(x, y, z, w)` quaternion, normalizing it.
+    pub fn set_quaternion(&mut self, x: f32, y: f32, z: f32, w: f32) -> PyResult<()> {
+        let rotation = Quat::from_xyzw(x, y, z, w);
+        if rotation.length_squared() == 0.0 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Quaternion must be non-zero",
+            ));
+        }
+        self.transform.rotation = rotation.normalize();
+        Ok(())
+    }
+
+    /// Rotates the entity so its -Z axis points at `target`, given in the same
+    /// space as `position`. Does nothing if `target` is the entity's position.
+    #[pyo3(signature = (target, up=(0.0, 1.0, 0.0)))]
+    pub fn look_at(&mut self, target: (f32, f32, f32), up: (f32, f32, f32)) {
+        let forward = (Vec3::from(target) - self.transform.position).normalize_or_zero();
+        if forward == Vec3::ZERO {
+            return;
+        }
+        let mut right = forward.cross(Vec3::from(up)).normalize_or_zero();
+        if right == Vec3::ZERO {
+            // Looking straight along `up`; any perpendicular axis will do.
+            right = forward.any_orthonormal_vector();
+        }
+        let up = right.cross(forward);
+        self.transform.rotation = Quat::from_mat3(&Mat3::from_cols(right, up, -forward));
+    }
+
     #[getter]
     pub fn get_scale(&self) -> (f32, f32, f32) {
         (
@@ -97,12 +226,81 @@ impl Entity {
     }
 }
 
+impl Entity {
+    /// Upper bound on hierarchy depth, in case a cycle slips in from Rust.
+    const MAX_HIERARCHY_DEPTH: usize = 64;
+
+    pub fn get_world_matrix(&self, py: Python) -> Mat4 {
+        let mut matrix = self.transform.get_model_matrix();
+        let mut parent = self.parent.as_ref().map(|p| p.clone_ref(py));
+        let mut depth = 0;
+
+        while let Some(entity_py) = parent {
+            if depth >= Self::MAX_HIERARCHY_DEPTH {
+                break;
+            }
+            let entity = entity_py.borrow(py);
+            matrix = entity.transform.get_model_matrix() * matrix;
+            parent = entity.parent.as_ref().map(|p| p.clone_ref(py));
+            depth += 1;
+        }
+
+        matrix
+    }
+
+    /// Whether parenting `slf` to `parent` would make `slf` its own ancestor.
+    fn would_cycle(slf: &Bound<'_, Self>, parent: Option<&Bound<'_, Entity>>) -> bool {
+        let mut current = parent.cloned();
+        while let Some(ancestor) = current {
+            if ancestor.as_ptr() == slf.as_ptr() {
+                return true;
+            }
+            current = ancestor
+                .borrow()
+                .parent
+                .as_ref()
+                .map(|p| p.bind(slf.py()).clone());
+        }
+        false
+    }
+
+    /// World matrix of the parent chain, or identity for a root entity.
+    fn parent_world_matrix(&self, py: Python) -> Mat4 {
+        self.parent.as_ref().map_or(Mat4::IDENTITY, |parent| {
+            parent.borrow(py).get_world_matrix(py)
+        })
+    }
+
+    /// World-space scale, rotation and position, decomposed from the world
+    /// matrix.
+    pub fn world_transform(&self, py: Python) -> (Vec3, Quat, Vec3) {
+        self.get_world_matrix(py).to_scale_rotation_translation()
+    }
+
+    /// Sets the local transform so the entity lands at a world-space
+    /// `position` and `rotation` under its current parents.
+    pub fn set_world_pose(&mut self, py: Python, position: Vec3, rotation: Quat) {
+        let parent = self.parent_world_matrix(py);
+        let (_, parent_rotation, _) = parent.to_scale_rotation_translation();
+        self.transform.position = parent.inverse().transform_point3(position);
+        self.transform.rotation = (parent_rotation.inverse() * rotation).normalize();
+    }
+}
+
 impl Default for Entity {
     fn default() -> Self {
-        Self::new()
+        Self::new(None)
     }
 }
 
+#[pyclass]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectionMode {
+    Perspective = 0,
+    Orthographic = 1,
+}
+
 #[pyclass]
 pub struct Camera {
     pub position: Vec3,
@@ -110,7 +308,15 @@ pub struct Camera {
     pub up: Vec3,
     pub yaw: f32,
     pub pitch: f32,
+    /// Degrees of rotation around `front`, applied on top of `up`.
+    #[pyo3(get, set)]
+    pub roll: f32,
     pub fov: f32,
+    #[pyo3(get, set)]
+    pub projection_mode: ProjectionMode,
+    /// Half the visible height in world units when orthographic.
+    #[pyo3(get, set)]
+    pub ortho_size: f32,
 }
 
 #[pymethods]
@@ -123,7 +329,10 @@ impl Camera {
             up: Vec3::new(0.0, 1.0, 0.0),
             yaw: -90.0,
             pitch: 0.0,
+            roll: 0.0,
             fov: 45.0,
+            projection_mode: ProjectionMode::Perspective,
+            ortho_size: 5.0,
         }
     }
 
@@ -137,6 +346,24 @@ impl Camera {
         self.position = Vec3::new(position.0, position.1, position.2);
     }
 
+    /// Reference up direction the view is rolled from.
+    #[getter]
+    pub fn get_up(&self) -> (f32, f32, f32) {
+        self.up.into()
+    }
+
+    #[setter]
+    pub fn set_up(&mut self, up: (f32, f32, f32)) -> PyResult<()> {
+        let up = Vec3::from(up).normalize_or_zero();
+        if up == Vec3::ZERO {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "Camera up vector must be non-zero",
+            ));
+        }
+        self.up = up;
+        Ok(())
+    }
+
     #[getter]
     pub fn get_fov(&self) -> f32 {
         self.fov
@@ -156,22 +383,95 @@ impl Camera {
     pub fn set_yaw_pitch(&mut self, yaw_pitch: (f32, f32)) {
         self.yaw = yaw_pitch.0;
         self.pitch = yaw_pitch.1;
+        self.update_front();
+    }
+
+    /// Column-major view matrix, as used by the renderer.
+    #[pyo3(name = "get_view_matrix")]
+    pub fn get_view_matrix_py(&self) -> [[f32; 4]; 4] {
+        self.get_view_matrix().to_cols_array_2d()
+    }
+
+    /// Column-major projection matrix for the given aspect ratio.
+    #[pyo3(name = "get_projection_matrix")]
+    pub fn get_projection_matrix_py(&self, aspect: f32) -> [[f32; 4]; 4] {
+        self.get_projection_matrix(aspect).to_cols_array_2d()
+    }
+}
+
+impl Camera {
+    pub const NEAR_PLANE: f32 = 0.001;
+    pub const FAR_PLANE: f32 = 1000.0;
 
+    pub fn get_view_matrix(&self) -> Mat4 {
+        Mat4::look_at_rh(self.position, self.position + self.front, self.view_up())
+    }
+
+    /// Points `front` along the current yaw and pitch.
+    pub fn update_front(&mut self) {
         let front_x = self.yaw.to_radians().cos() * self.pitch.to_radians().cos();
         let front_y = self.pitch.to_radians().sin();
         let front_z = self.yaw.to_radians().sin() * self.pitch.to_radians().cos();
         self.front = Vec3::new(front_x, front_y, front_z).normalize();
     }
-}
 
-impl Camera {
-    pub fn get_view_matrix(&self) -> Mat4 {
-        Mat4::look_at_rh(self.position, self.position + self.front, self.up)
+    /// `up` made perpendicular to `front` and rotated by `roll`.
+    pub fn view_up(&self) -> Vec3 {
+        let right = self.front.cross(self.up).normalize_or_zero();
+        let up = right.cross(self.front);
+        Quat::from_axis_angle(self.front.normalize(), self.roll.to_radians()) * up
+    }
+
+    /// World-space corners of the view volume between `near` and `far` along
+    /// the view direction, near face first.
+    pub fn frustum_corners(&self, aspect: f32, near: f32, far: f32) -> [Vec3; 8] {
+        let up = self.view_up();
+        let right = self.front.cross(up).normalize();
+        let mut corners = [Vec3::ZERO; 8];
+        for (face, distance) in [near, far].into_iter().enumerate() {
+            let half_height = match self.projection_mode {
+                ProjectionMode::Perspective => distance * (self.fov.to_radians() / 2.0).tan(),
+                ProjectionMode::Orthographic => self.ortho_size,
+            };
+            let half_width = half_height * aspect;
+            let center = self.position + self.front * distance;
+            for (i, (x, y)) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+                .into_iter()
+                .enumerate()
+            {
+                corners[face * 4 + i] = center + right * (x * half_width) + up * (y * half_height);
+            }
+        }
+        corners
+    }
+
+    pub fn get_projection_matrix(&self, aspect: f32) -> Mat4 {
+        match self.projection_mode {
+            ProjectionMode::Perspective => Mat4::perspective_rh_gl(
+                self.fov.to_radians(),
+                aspect,
+                Self::NEAR_PLANE,
+                Self::FAR_PLANE,
+            ),
+            ProjectionMode::Orthographic => {
+                let half_height = self.ortho_size;
+                let half_width = half_height * aspect;
+                Mat4::orthographic_rh_gl(
+                    -half_width,
+                    half_width,
+                    -half_height,
+                    half_height,
+                    Self::NEAR_PLANE,
+                    Self::FAR_PLANE,
+                )
+            }
+        }
     }
 }
 
 #[pyclass]
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LightType {
     Point = 0,
     Directional = 1,
@@ -202,6 +502,255 @@ impl Scene {
     pub fn add_light(&mut self, light: Py<Light>) {
         self.lights.push(light);
     }
+
+    /// Removes the given entity by identity. Returns whether it was found.
+    pub fn remove_entity(&mut self, entity: &Bound<'_, Entity>) -> bool {
+        let len = self.entities.len();
+        self.entities.retain(|e| e.as_ptr() != entity.as_ptr());
+        self.entities.len() != len
+    }
+
+    /// Removes the given light by identity. Returns whether it was found.
+    pub fn remove_light(&mut self, light: &Bound<'_, Light>) -> bool {
+        let len = self.lights.len();
+        self.lights.retain(|l| l.as_ptr() != light.as_ptr());
+        self.lights.len() != len
+    }
+
+    pub fn clear(&mut self) {
+        self.entities.clear();
+        self.lights.clear();
+    }
+
+    /// Returns the first entity with the given name.
+    pub fn find_by_name(&self, py: Python, name: &str) -> Option<Py<Entity>> {
+        self.entities
+            .iter()
+            .find(|e| e.borrow(py).name.as_deref() == Some(name))
+            .map(|e| e.clone_ref(py))
+    }
+
+    /// Returns every entity carrying `tag`, in scene order.
+    pub fn find_by_tag(&self, py: Python, tag: &str) -> Vec<Py<Entity>> {
+        self.entities
+            .iter()
+            .filter(|e| e.borrow(py).has_tag(tag))
+            .map(|e| e.clone_ref(py))
+            .collect()
+    }
+}
+
+impl Scene {
+    /// Writes entity transforms and mesh sources, lights and `camera` to a
+    /// JSON file. Meshes built from raw data have no source and are saved as
+    /// `null`.
+    pub fn save(&self, py: Python, path: &str, camera: &Camera) -> PyResult<()> {
+        let entities = self
+            .entities
+            .iter()
+            .map(|entity_py| {
+                let entity = entity_py.borrow(py);
+                EntityData {
+                    name: entity.name.clone(),
+                    tags: entity.tags.clone(),
+                    position: entity.transform.position,
+                    rotation: entity.transform.rotation,
+                    scale: entity.transform.scale,
+                    mesh: entity
+                        .mesh
+                        .as_ref()
+                        .and_then(|mesh| mesh.borrow(py).source.clone()),
+                    parent: entity.parent.as_ref().and_then(|parent| {
+                        self.entities
+                            .iter()
+                            .position(|e| e.as_ptr() == parent.as_ptr())
+                    }),
+                }
+            })
+            .collect();
+
+        let lights = self
+            .lights
+            .iter()
+            .map(|light_py| {
+                let light = light_py.borrow(py);
+                LightData {
+                    light_type: light.light_type,
+                    color: light.color,
+                    radius: light.radius,
+                    position: light.position,
+                    cast_shadows: light.cast_shadows,
+                    shadow_bias: light.shadow_bias,
+                }
+            })
+            .collect();
+
+        let file = SceneFile {
+            entities,
+            lights,
+            camera: CameraData {
+                position: camera.position,
+                yaw: camera.yaw,
+                pitch: camera.pitch,
+                roll: camera.roll,
+                up: camera.up,
+                fov: camera.fov,
+                projection_mode: camera.projection_mode,
+                ortho_size: camera.ortho_size,
+            },
+        };
+
+        let json = serde_json::to_string_pretty(&file).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to serialize scene: {}", e))
+        })?;
+        fs::write(path, json).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "Failed to write scene file '{}': {}",
+                path, e
+            ))
+        })
+    }
+
+    /// Reads a scene written by `save`, rebuilding each mesh from its source.
+    /// Entities that shared a source share the rebuilt mesh.
+    pub fn load(py: Python, path: &str) -> PyResult<(Scene, Camera)> {
+        let json = fs::read_to_string(path).map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!(
+                "Failed to read scene file '{}': {}",
+                path, e
+            ))
+        })?;
+        let file: SceneFile = serde_json::from_str(&json).map_err(|e| {
+            pyo3::exceptions::PyValueError::new_err(format!("Invalid scene file '{}': {}", path, e))
+        })?;
+
+        let mut meshes: Vec<(MeshSource, Py<Mesh>)> = Vec::new();
+        let mut entities = Vec::with_capacity(file.entities.len());
+        for data in &file.entities {
+            let mut entity = Entity::new(data.name.clone());
+            entity.tags = data.tags.clone();
+            entity.transform = Transform {
+                position: data.position,
+                rotation: data.rotation,
+                scale: data.scale,
+            };
+            if let Some(source) = &data.mesh {
+                let mesh = match meshes.iter().find(|(s, _)| s == source) {
+                    Some((_, mesh)) => mesh.clone_ref(py),
+                    None => {
+                        let mesh = Py::new(py, source.load()?)?;
+                        meshes.push((source.clone(), mesh.clone_ref(py)));
+                        mesh
+                    }
+                };
+                entity.mesh = Some(mesh);
+            }
+            entities.push(Py::new(py, entity)?);
+        }
+
+        for (data, entity) in file.entities.iter().zip(&entities) {
+            if let Some(parent) = data.parent {
+                let parent = entities.get(parent).ok_or_else(|| {
+                    pyo3::exceptions::PyValueError::new_err(format!(
+                        "Invalid scene file '{}': parent index {} out of range",
+                        path, parent
+                    ))
+                })?;
+                let (entity, parent) = (entity.bind(py), parent.bind(py));
+                if Entity::would_cycle(entity, Some(parent)) {
+                    return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                        "Invalid scene file '{}': parent cycle",
+                        path
+                    )));
+                }
+                entity.borrow_mut().parent = Some(parent.clone().unbind());
+            }
+        }
+
+        let lights = file
+            .lights
+            .iter()
+            .map(|data| {
+                let mut light = Light::new(
+                    data.color.x,
+                    data.color.y,
+                    data.color.z,
+                    data.radius,
+                    data.light_type,
+                );
+                light.position = data.position;
+                light.cast_shadows = data.cast_shadows;
+                light.shadow_bias = data.shadow_bias;
+                Py::new(py, light)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let data = &file.camera;
+        let mut camera = Camera::new(data.position.x, data.position.y, data.position.z);
+        camera.yaw = data.yaw;
+        camera.pitch = data.pitch;
+        camera.roll = data.roll;
+        camera.set_up(data.up.into())?;
+        camera.fov = data.fov;
+        camera.projection_mode = data.projection_mode;
+        camera.ortho_size = data.ortho_size;
+        camera.update_front();
+
+        Ok((Scene { entities, lights }, camera))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SceneFile {
+    entities: Vec<EntityData>,
+    lights: Vec<LightData>,
+    camera: CameraData,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntityData {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    position: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+    mesh: Option<MeshSource>,
+    /// Index of the parent entity in `SceneFile::entities`.
+    parent: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LightData {
+    light_type: LightType,
+    color: Vec3,
+    radius: f32,
+    position: Vec3,
+    #[serde(default = "default_cast_shadows")]
+    cast_shadows: bool,
+    #[serde(default = "default_shadow_bias")]
+    shadow_bias: f32,
+}
+
+fn default_cast_shadows() -> bool {
+    true
+}
+
+fn default_shadow_bias() -> f32 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize)]
+struct CameraData {
+    position: Vec3,
+    yaw: f32,
+    pitch: f32,
+    roll: f32,
+    up: Vec3,
+    fov: f32,
+    projection_mode: ProjectionMode,
+    ortho_size: f32,
 }
 
 impl Default for Scene {
@@ -219,6 +768,17 @@ pub struct Light {
     pub radius: f32,
     #[pyo3(get, set)]
     pub light_type: LightType,
+    /// Whether the engine draws a glowing sphere at this point light while
+    /// light gizmos are enabled.
+    #[pyo3(get, set)]
+    pub show_gizmo: bool,
+    /// Whether the light renders a shadow map. Off makes a cheap fill light.
+    #[pyo3(get, set)]
+    pub cast_shadows: bool,
+    /// Scales the depth bias of the shadow comparison: raise it to fix
+    /// shadow acne, lower it to fix shadows detaching from their casters.
+    #[pyo3(get, set)]
+    pub shadow_bias: f32,
 }
 
 #[pymethods]
@@ -230,6 +790,9 @@ impl Light {
             color: Vec3::new(r, g, b),
             radius,
             light_type,
+            show_gizmo: true,
+            cast_shadows: true,
+            shadow_bias: 1.0,
         }
     }
 
@@ -240,6 +803,9 @@ impl Light {
             color: Vec3::new(r, g, b),
             radius,
             light_type: LightType::Point,
+            show_gizmo: true,
+            cast_shadows: true,
+            shadow_bias: 1.0,
         }
     }
 
@@ -250,6 +816,9 @@ impl Light {
             color: Vec3::new(r, g, b),
             radius: 0.0,
             light_type: LightType::Directional,
+            show_gizmo: true,
+            cast_shadows: true,
+            shadow_bias: 1.0,
         }
     }
 
@@ -273,3 +842,14 @@ impl Light {
         self.color = Vec3::new(color.0, color.1, color.2);
     }
 }
+
+impl Light {
+    /// Near plane of the point shadow cube projection.
+    pub const SHADOW_NEAR_PLANE: f32 = 0.1;
+
+    /// Far plane of the point shadow cube. Attenuation reaches zero at
+    /// `radius`, so nothing past it can cast a visible shadow.
+    pub fn shadow_far_plane(&self) -> f32 {
+        self.radius.max(Self::SHADOW_NEAR_PLANE * 2.0)
+    }
+}